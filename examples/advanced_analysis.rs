@@ -32,7 +32,7 @@ fn demo_nlp_analysis() -> Result<()> {
     println!("📊 Part 1: NLP Sentiment Analysis");
     println!("═══════════════════════════════════════════════════════════\n");
     
-    let test_articles = vec![
+    let test_articles = [
         ("Bitcoin surges to record high with strong gains", "Very Positive"),
         ("Market crashes amid fears and concerns", "Very Negative"),
         ("Trading volume remains steady today", "Neutral"),
@@ -43,12 +43,7 @@ fn demo_nlp_analysis() -> Result<()> {
         println!("Test #{}: {}", i + 1, expected);
         println!("Text: \"{}\"", text);
         
-        let article = Article {
-            title: text.to_string(),
-            content: String::new(),
-            source: "Test".to_string(),
-            timestamp: 0,
-        };
+        let article = Article::new(text, "", "Test", 0);
         
         let sentiment = analyze_sentiment(&article)?;
         println!("{}", dashboard::format_sentiment(&sentiment));
@@ -75,6 +70,7 @@ fn demo_signal_generation() -> Result<()> {
         (dec!(0.35), dec!(0.35), dec!(0.30), "BNB", "Hold"),
         (dec!(0.70), dec!(0.20), dec!(0.10), "ADA", "Moderate Buy"),
     ];
+    let config = SignalConfig::default();
     
     for (pos, neg, neu, symbol, description) in test_cases {
         println!("Test: {}", description);
@@ -85,12 +81,12 @@ fn demo_signal_generation() -> Result<()> {
             neutral: neu,
         };
         
-        let (signal, signal_type) = generate_signal_with_type(&sentiment, &symbol)?;
+        let signal = generate_signal(&sentiment, symbol, &config)?;
         
-        println!("{}", dashboard::format_signal(&signal, &signal_type));
-        println!("Signal Strength: {}/100", calculate_signal_strength(&sentiment));
+        println!("{}", dashboard::format_signal(&signal));
+        println!("{}", dashboard::format_strength(&calculate_signal_strength(&sentiment)));
         
-        let actionable = is_signal_actionable(&signal, dec!(0.7));
+        let actionable = is_signal_actionable(&signal, &config);
         println!("Actionable (>70% confidence): {}", if actionable { "✅ Yes" } else { "❌ No" });
         println!();
     }
@@ -182,10 +178,11 @@ fn demo_dashboard() -> Result<()> {
             "MARKET".to_string()
         };
         
-        let (signal, signal_type) = generate_signal_with_type(&sentiment, &symbol)?;
+        let signal = generate_signal(&sentiment, &symbol, &SignalConfig::default())?
+            .with_source_articles(&[&article.id]);
         
         sentiments.push(sentiment);
-        signals.push((signal, signal_type));
+        signals.push(signal);
     }
     
     println!("\n");
@@ -197,13 +194,13 @@ fn demo_dashboard() -> Result<()> {
     println!("📊 Detailed Analytics:\n");
     
     let avg_confidence = signals.iter()
-        .map(|(s, _)| s.confidence)
+        .map(|s| s.confidence)
         .sum::<rust_decimal::Decimal>() / rust_decimal::Decimal::from(signals.len());
     
     println!("  Average Signal Confidence: {:.1}%", avg_confidence * dec!(100));
     
     let high_confidence_count = signals.iter()
-        .filter(|(s, _)| s.confidence > dec!(0.8))
+        .filter(|s| s.confidence > dec!(0.8))
         .count();
     
     println!("  High Confidence Signals (>80%): {}", high_confidence_count);
@@ -0,0 +1,50 @@
+use anyhow::{bail, Result};
+use sentiment_analysis_trading::signals::AuditTrail;
+
+/// Re-generates logged signals from their recorded inputs and checks they
+/// come out identical.
+///
+/// Usage: `cargo run --example audit_replay -- <audit.jsonl> [SYMBOL [FROM TO]]`
+/// where FROM and TO are Unix timestamps bounding `generated_at`.
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(path) = args.first() else {
+        bail!("usage: audit_replay <audit.jsonl> [SYMBOL [FROM TO]]");
+    };
+    let symbol = args.get(1).map(String::as_str);
+    let from = args.get(2).map(|v| v.parse()).transpose()?.unwrap_or(i64::MIN);
+    let to = args.get(3).map(|v| v.parse()).transpose()?.unwrap_or(i64::MAX);
+
+    let trail = AuditTrail::load(path)?;
+    let records = trail.query(symbol, from, to);
+    println!("🔁 Replaying {} of {} records from {}\n", records.len(), trail.records().len(), path);
+
+    let mut mismatches = 0;
+    for record in records {
+        let signal = &record.signal;
+        match trail.replay(record) {
+            Ok(outcome) if outcome.matches() => {
+                println!("  ✅ #{} {} {} {}", record.sequence, signal.symbol, signal.action.as_str(), signal.id);
+            }
+            Ok(outcome) => {
+                mismatches += 1;
+                println!(
+                    "  ❌ #{} {} logged {} but replayed {} ({} @ {})",
+                    record.sequence,
+                    signal.symbol,
+                    outcome.logged_id,
+                    outcome.replayed.id,
+                    outcome.replayed.action.as_str(),
+                    outcome.replayed.confidence
+                );
+            }
+            Err(e) => println!("  ⏭️  #{} {} skipped: {}", record.sequence, signal.symbol, e),
+        }
+    }
+
+    if mismatches > 0 {
+        bail!("{} signal(s) did not replay identically", mismatches);
+    }
+    println!("\n✅ All replayable signals match");
+    Ok(())
+}
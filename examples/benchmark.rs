@@ -64,10 +64,11 @@ fn benchmark_signal_generation() {
     };
     
     let iterations = 10000;
+    let config = SignalConfig::default();
     
     let start = Instant::now();
     for _ in 0..iterations {
-        let _ = black_box(generate_signal(&sentiment, "BTC"));
+        let _ = black_box(generate_signal(&sentiment, "BTC", &config));
     }
     let duration = start.elapsed();
     
@@ -112,6 +113,7 @@ fn benchmark_complete_pipeline() {
     
     let articles = MockDataProvider::get_sample_articles();
     let iterations = 100;
+    let config = SignalConfig::default();
     
     let start = Instant::now();
     for _ in 0..iterations {
@@ -124,7 +126,7 @@ fn benchmark_complete_pipeline() {
                 } else {
                     "MARKET"
                 };
-                let _ = generate_signal(&sentiment, symbol);
+                let _ = generate_signal(&sentiment, symbol, &config);
             }
         }
     }
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use sentiment_analysis_trading::*;
+use sentiment_analysis_trading::scrapers::{PollConfig, RedditSource};
+use chrono::Utc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Polls a few subreddits continuously, scores every new article and prints
+/// debounced per-symbol signals until Ctrl-C or SIGTERM. Set `CRAWLER_CONTACT`
+/// to an email or URL so site operators can reach you.
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+
+    let contact = std::env::var("CRAWLER_CONTACT").ok();
+    let config = CrawlerConfig::new("sentiment-analysis-trading/0.1", contact.as_deref());
+    let poll = PollConfig::every(Duration::from_secs(120)).with_jitter(Duration::from_secs(30));
+
+    let scheduler = Scheduler::new(2)
+        .add_source(RedditSource::new("CryptoCurrency", config.clone())?, poll)
+        .add_source(RedditSource::new("Bitcoin", config.clone())?, poll)
+        .add_source(RedditSource::new("ethereum", config)?, poll);
+
+    println!("📡 Polling {} sources, press Ctrl-C to stop\n", scheduler.source_count());
+
+    let (tx, mut rx) = mpsc::channel(256);
+    let collector = tokio::spawn(scheduler.run_until_signal(tx));
+
+    let signal_config = SignalConfig::default();
+    let mut aggregator = SentimentAggregator::default();
+    let mut filter = SignalFilter::default();
+
+    while let Some(article) = rx.recv().await {
+        let sentiment = analyze_sentiment(&article)?;
+        println!(
+            "[{}] {} (+{:.2} / -{:.2})",
+            article.source, article.title, sentiment.positive, sentiment.negative
+        );
+
+        let now = Utc::now().timestamp();
+        for symbol in aggregator.observe_article(&article, &sentiment) {
+            let Some(aggregate) = aggregator.aggregate(&symbol, now) else { continue };
+            let raw = generate_aggregate_signal(&aggregate, &signal_config)?;
+            if let Some(signal) = filter.process(raw) {
+                println!("  {}", dashboard::format_signal(&signal));
+            }
+        }
+    }
+
+    println!("\n📊 Collection stats:");
+    for stats in collector.await? {
+        println!(
+            "  {}: {} polls, {} new, {} duplicates, {} errors",
+            stats.name, stats.polls, stats.new_articles, stats.duplicates, stats.errors
+        );
+    }
+    println!("  {} signal flips suppressed", filter.suppressed().len());
+
+    Ok(())
+}
@@ -5,19 +5,19 @@ fn main() -> Result<()> {
     println!("=== Sentiment Analysis Trading - Example ===\n");
 
     // Create sample articles
-    let articles = vec![
-        Article {
-            title: "Bitcoin Surges to New Highs".to_string(),
-            content: "Bitcoin reaches unprecedented levels as institutional adoption grows.".to_string(),
-            source: "CryptoNews".to_string(),
-            timestamp: 1696435200,
-        },
-        Article {
-            title: "Market Correction Expected".to_string(),
-            content: "Analysts warn of potential market downturn amid regulatory concerns.".to_string(),
-            source: "FinanceTimes".to_string(),
-            timestamp: 1696435300,
-        },
+    let articles = [
+        Article::new(
+            "Bitcoin Surges to New Highs",
+            "Bitcoin reaches unprecedented levels as institutional adoption grows.",
+            "CryptoNews",
+            1696435200,
+        ),
+        Article::new(
+            "Market Correction Expected",
+            "Analysts warn of potential market downturn amid regulatory concerns.",
+            "FinanceTimes",
+            1696435300,
+        ),
     ];
 
     println!("📰 Analyzing {} articles...\n", articles.len());
@@ -27,7 +27,7 @@ fn main() -> Result<()> {
         println!("  Title: {}", article.title);
         println!("  Source: {}", article.source);
 
-        let sentiment = analyze_sentiment(&article)?;
+        let sentiment = analyze_sentiment(article)?;
 
         println!("  Sentiment:");
         println!("    Positive: {}", sentiment.positive);
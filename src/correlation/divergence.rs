@@ -0,0 +1,355 @@
+//! Sentiment-price divergence detection
+//!
+//! A bearish divergence is a price making a higher swing high while sentiment
+//! makes a lower high; a bullish divergence is a lower price low against a
+//! higher sentiment low. Swings are pivots: points strictly above (or below)
+//! the `swing_window` points before them and at least as high (low) as the
+//! `swing_window` points after them, so the latest swing is only confirmed
+//! once `swing_window` newer points exist.
+//!
+//! Sentiment is compared as net sentiment (`positive - negative`). Raw
+//! per-article scores are noisy; pass a smoothed series such as hourly means
+//! from [`SentimentAggregator::buckets`](crate::signals::SentimentAggregator::buckets).
+
+use super::PricePoint;
+use crate::types::{SentimentScore, Signal, SignalType};
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Swing detection and divergence thresholds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DivergenceConfig {
+    /// Points on each side a swing must dominate
+    pub swing_window: usize,
+    /// Only swings within this many seconds before `now` are considered
+    pub lookback_secs: i64,
+    /// Maximum time between a price swing and its matching sentiment swing
+    pub max_offset_secs: i64,
+    /// Minimum relative price move between the two swings, e.g. 0.01 = 1%
+    pub min_price_change: Decimal,
+    /// Minimum net sentiment move between the two swings
+    pub min_sentiment_change: Decimal,
+    /// Divergences lasting this long or longer get the full duration weight
+    pub full_duration_secs: i64,
+}
+
+impl Default for DivergenceConfig {
+    fn default() -> Self {
+        Self {
+            swing_window: 3,
+            lookback_secs: 7 * 86_400,
+            max_offset_secs: 6 * 3600,
+            min_price_change: dec!(0.01),
+            min_sentiment_change: dec!(0.1),
+            full_duration_secs: 2 * 86_400,
+        }
+    }
+}
+
+impl DivergenceConfig {
+    pub fn with_swing_window(mut self, swing_window: usize) -> Self {
+        self.swing_window = swing_window;
+        self
+    }
+
+    pub fn with_lookback_secs(mut self, lookback_secs: i64) -> Self {
+        self.lookback_secs = lookback_secs;
+        self
+    }
+
+    /// Sets the minimum price and net sentiment moves
+    pub fn with_min_changes(mut self, price: Decimal, sentiment: Decimal) -> Self {
+        self.min_price_change = price;
+        self.min_sentiment_change = sentiment;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.swing_window == 0 {
+            bail!("swing_window must be at least 1");
+        }
+        if self.lookback_secs <= 0 || self.max_offset_secs < 0 || self.full_duration_secs <= 0 {
+            bail!("lookback_secs and full_duration_secs must be positive and max_offset_secs not negative");
+        }
+        if self.min_price_change <= Decimal::ZERO || self.min_sentiment_change <= Decimal::ZERO {
+            bail!("min_price_change and min_sentiment_change must be positive");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+/// A local extreme of a series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Swing {
+    pub kind: SwingKind,
+    pub timestamp: i64,
+    pub value: Decimal,
+}
+
+/// Finds swing highs and lows in a time-ordered series
+pub fn find_swings(series: &[(i64, Decimal)], window: usize) -> Vec<Swing> {
+    if window == 0 || series.len() < 2 * window + 1 {
+        return Vec::new();
+    }
+    let mut swings = Vec::new();
+    for i in window..series.len() - window {
+        let (timestamp, value) = series[i];
+        let before = &series[i - window..i];
+        let after = &series[i + 1..=i + window];
+        if before.iter().all(|(_, v)| *v < value) && after.iter().all(|(_, v)| *v <= value) {
+            swings.push(Swing { kind: SwingKind::High, timestamp, value });
+        } else if before.iter().all(|(_, v)| *v > value) && after.iter().all(|(_, v)| *v >= value) {
+            swings.push(Swing { kind: SwingKind::Low, timestamp, value });
+        }
+    }
+    swings
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DivergenceKind {
+    /// Price lower low, sentiment higher low
+    Bullish,
+    /// Price higher high, sentiment lower high
+    Bearish,
+}
+
+/// A divergence between two consecutive price swings of the same kind
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    /// Price swings the divergence spans
+    pub first: Swing,
+    pub second: Swing,
+    /// Relative price move between the swings
+    pub price_change: Decimal,
+    /// Net sentiment move between the matching sentiment swings
+    pub sentiment_change: Decimal,
+    /// Timestamp of the second sentiment swing
+    pub sentiment_timestamp: i64,
+    pub confidence: Decimal,
+}
+
+impl Divergence {
+    pub fn duration_secs(&self) -> i64 {
+        self.second.timestamp - self.first.timestamp
+    }
+}
+
+fn net(sentiment: &SentimentScore) -> Decimal {
+    sentiment.positive - sentiment.negative
+}
+
+/// Nearest swing of `kind` within `max_offset` seconds of `timestamp`
+fn matching(swings: &[Swing], kind: SwingKind, timestamp: i64, max_offset: i64) -> Option<Swing> {
+    swings
+        .iter()
+        .filter(|s| s.kind == kind && (s.timestamp - timestamp).abs() <= max_offset)
+        .min_by_key(|s| (s.timestamp - timestamp).abs())
+        .copied()
+}
+
+/// Confidence from the size of both moves relative to their minimums and the
+/// divergence duration: `(1 - 1 / (2 * size)) * (0.5 + 0.5 * duration / full)`
+fn confidence(price_change: Decimal, sentiment_change: Decimal, duration: i64, config: &DivergenceConfig) -> Decimal {
+    let size = (price_change.abs() / config.min_price_change + sentiment_change.abs() / config.min_sentiment_change) / dec!(2);
+    let size_factor = Decimal::ONE - Decimal::ONE / (dec!(2) * size);
+    let duration_factor = (Decimal::from(duration) / Decimal::from(config.full_duration_secs)).min(Decimal::ONE);
+    (size_factor * (dec!(0.5) + dec!(0.5) * duration_factor)).round_dp(4)
+}
+
+/// Finds every divergence whose swings fall within the lookback before `now`,
+/// oldest first. Both series must be ordered by timestamp. Fails if `config`
+/// is invalid.
+pub fn detect_divergences(
+    prices: &[PricePoint],
+    sentiments: &[(i64, SentimentScore)],
+    config: &DivergenceConfig,
+    now: i64,
+) -> Result<Vec<Divergence>> {
+    config.validate()?;
+    let in_range = |ts: i64| ts <= now && ts > now - config.lookback_secs;
+    let price_series: Vec<(i64, Decimal)> = prices
+        .iter()
+        .filter(|p| in_range(p.timestamp))
+        .map(|p| (p.timestamp, p.price))
+        .collect();
+    let sentiment_series: Vec<(i64, Decimal)> = sentiments
+        .iter()
+        .filter(|(ts, _)| in_range(*ts))
+        .map(|(ts, s)| (*ts, net(s)))
+        .collect();
+    let price_swings = find_swings(&price_series, config.swing_window);
+    let sentiment_swings = find_swings(&sentiment_series, config.swing_window);
+
+    let mut divergences = Vec::new();
+    for kind in [SwingKind::High, SwingKind::Low] {
+        let of_kind: Vec<&Swing> = price_swings.iter().filter(|s| s.kind == kind).collect();
+        for pair in of_kind.windows(2) {
+            let (first, second) = (*pair[0], *pair[1]);
+            if first.value.is_zero() {
+                continue;
+            }
+            let (Some(s1), Some(s2)) = (
+                matching(&sentiment_swings, kind, first.timestamp, config.max_offset_secs),
+                matching(&sentiment_swings, kind, second.timestamp, config.max_offset_secs),
+            ) else {
+                continue;
+            };
+            if s1.timestamp >= s2.timestamp {
+                continue;
+            }
+
+            let price_change = (second.value - first.value) / first.value;
+            let sentiment_change = s2.value - s1.value;
+            let divergence_kind = match kind {
+                SwingKind::High
+                    if price_change >= config.min_price_change && sentiment_change <= -config.min_sentiment_change =>
+                {
+                    DivergenceKind::Bearish
+                }
+                SwingKind::Low
+                    if price_change <= -config.min_price_change && sentiment_change >= config.min_sentiment_change =>
+                {
+                    DivergenceKind::Bullish
+                }
+                _ => continue,
+            };
+            let duration = second.timestamp - first.timestamp;
+            divergences.push(Divergence {
+                kind: divergence_kind,
+                first,
+                second,
+                price_change: price_change.round_dp(6),
+                sentiment_change,
+                sentiment_timestamp: s2.timestamp,
+                confidence: confidence(price_change, sentiment_change, duration, config),
+            });
+        }
+    }
+    divergences.sort_by_key(|d| d.second.timestamp);
+    Ok(divergences)
+}
+
+/// Signal from the most recent divergence: BUY for bullish, SELL for bearish.
+/// The signal carries the sentiment score at the second sentiment swing.
+pub fn divergence_signal(
+    symbol: &str,
+    prices: &[PricePoint],
+    sentiments: &[(i64, SentimentScore)],
+    config: &DivergenceConfig,
+    now: i64,
+) -> Result<Option<Signal>> {
+    let Some(divergence) = detect_divergences(prices, sentiments, config, now)?.pop() else {
+        return Ok(None);
+    };
+    let action = match divergence.kind {
+        DivergenceKind::Bullish => SignalType::Buy,
+        DivergenceKind::Bearish => SignalType::Sell,
+    };
+    let sentiment = sentiments
+        .iter()
+        .find(|(ts, _)| *ts == divergence.sentiment_timestamp)
+        .map(|(_, s)| s.clone());
+    Ok(sentiment.map(|sentiment| Signal::new(symbol, action, sentiment, divergence.confidence, now)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3600;
+
+    fn prices(values: &[Decimal]) -> Vec<PricePoint> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, price)| PricePoint { timestamp: i as i64 * HOUR, price: *price, volume: None })
+            .collect()
+    }
+
+    fn sentiments(nets: &[Decimal]) -> Vec<(i64, SentimentScore)> {
+        nets.iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let positive = (dec!(0.4) + *n / dec!(2)).round_dp(4);
+                let negative = positive - *n;
+                (i as i64 * HOUR, SentimentScore { positive, negative, neutral: Decimal::ONE - positive - negative })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_swings() {
+        let series: Vec<(i64, Decimal)> = [1, 3, 3, 1, 0, 2, 1]
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i as i64, Decimal::from(*v)))
+            .collect();
+        let swings = find_swings(&series, 1);
+        assert_eq!(
+            swings,
+            [
+                Swing { kind: SwingKind::High, timestamp: 1, value: dec!(3) },
+                Swing { kind: SwingKind::Low, timestamp: 4, value: dec!(0) },
+                Swing { kind: SwingKind::High, timestamp: 5, value: dec!(2) },
+            ]
+        );
+        assert!(find_swings(&series[..2], 1).is_empty());
+    }
+
+    #[test]
+    fn test_bearish_divergence() {
+        let config = DivergenceConfig::default().with_swing_window(2);
+        let prices = prices(&[dec!(100), dec!(104), dec!(110), dec!(104), dec!(100), dec!(106), dec!(115), dec!(108), dec!(103)]);
+        let sentiments = sentiments(&[dec!(0.1), dec!(0.3), dec!(0.6), dec!(0.3), dec!(0.1), dec!(0.2), dec!(0.3), dec!(0.2), dec!(0.1)]);
+
+        let found = detect_divergences(&prices, &sentiments, &config, 8 * HOUR).unwrap();
+        assert_eq!(found.len(), 1);
+        let divergence = &found[0];
+        assert_eq!(divergence.kind, DivergenceKind::Bearish);
+        assert_eq!(divergence.duration_secs(), 4 * HOUR);
+        assert_eq!(divergence.sentiment_change, dec!(-0.3));
+
+        let signal = divergence_signal("BTC", &prices, &sentiments, &config, 8 * HOUR).unwrap().unwrap();
+        assert_eq!(signal.action, SignalType::Sell);
+        assert_eq!(signal.confidence, divergence.confidence);
+        assert_eq!(signal.sentiment, sentiments[6].1);
+
+        // A longer divergence of the same size is more confident
+        let short = confidence(dec!(0.05), dec!(-0.3), 4 * HOUR, &config);
+        assert!(confidence(dec!(0.05), dec!(-0.3), 40 * HOUR, &config) > short);
+        assert!(confidence(dec!(0.10), dec!(-0.6), 4 * HOUR, &config) > short);
+
+        // Outside the lookback nothing is found
+        let narrow = config.clone().with_lookback_secs(3 * HOUR);
+        assert!(detect_divergences(&prices, &sentiments, &narrow, 8 * HOUR).unwrap().is_empty());
+
+        // A zero bound would divide by zero in the confidence
+        let unbounded = DivergenceConfig { min_price_change: Decimal::ZERO, ..config };
+        assert!(detect_divergences(&prices, &sentiments, &unbounded, 8 * HOUR).is_err());
+        assert!(divergence_signal("BTC", &prices, &sentiments, &unbounded, 8 * HOUR).is_err());
+    }
+
+    #[test]
+    fn test_bullish_divergence_and_confirmation() {
+        let config = DivergenceConfig::default().with_swing_window(2);
+        let prices = prices(&[dec!(100), dec!(96), dec!(90), dec!(96), dec!(100), dec!(94), dec!(85), dec!(92), dec!(97)]);
+        let rising = sentiments(&[dec!(-0.1), dec!(-0.3), dec!(-0.6), dec!(-0.3), dec!(-0.1), dec!(-0.2), dec!(-0.3), dec!(-0.2), dec!(-0.1)]);
+        let signal = divergence_signal("ETH", &prices, &rising, &config, 8 * HOUR).unwrap().unwrap();
+        assert_eq!(signal.action, SignalType::Buy);
+
+        // Sentiment confirming the lower low is not a divergence
+        let falling = sentiments(&[dec!(-0.1), dec!(-0.2), dec!(-0.3), dec!(-0.2), dec!(-0.1), dec!(-0.3), dec!(-0.6), dec!(-0.3), dec!(-0.1)]);
+        assert!(divergence_signal("ETH", &prices, &falling, &config, 8 * HOUR).unwrap().is_none());
+        assert!(DivergenceConfig::default().with_swing_window(0).validate().is_err());
+    }
+}
@@ -1,7 +1,12 @@
 //! Correlation Module - Sentiment-Price Correlation Analysis
 //!
 //! This module analyzes the correlation between sentiment scores and price movements.
-//! It helps understand how sentiment affects market prices over time.
+//! It helps understand how sentiment affects market prices over time, and
+//! detects divergences between the two (see [`divergence`]).
+
+pub mod divergence;
+
+pub use divergence::{detect_divergences, divergence_signal, Divergence, DivergenceConfig, DivergenceKind};
 
 use crate::types::SentimentScore;
 use anyhow::Result;
@@ -3,8 +3,8 @@
 //! This module provides utilities for displaying sentiment analysis results,
 //! signals, and analytics in a readable format.
 
-use crate::types::{Article, SentimentScore, Signal};
-use crate::signals::SignalType;
+use crate::signals::{calculate_signal_strength, SignalStrength};
+use crate::types::{Article, SentimentScore, Signal, SignalType};
 
 /// Formats sentiment score for display
 pub fn format_sentiment(sentiment: &SentimentScore) -> String {
@@ -16,27 +16,51 @@ pub fn format_sentiment(sentiment: &SentimentScore) -> String {
     )
 }
 
+/// Formats directional score and conviction for display
+pub fn format_strength(strength: &SignalStrength) -> String {
+    let sign = if strength.direction > rust_decimal::Decimal::ZERO { "+" } else { "" };
+    format!(
+        "🧭 Direction: {}{:.2} | Conviction: {:.2}%",
+        sign,
+        strength.direction,
+        strength.conviction
+    )
+}
+
 /// Formats a signal for display
-pub fn format_signal(signal: &Signal, signal_type: &SignalType) -> String {
-    let emoji = match signal_type {
+pub fn format_signal(signal: &Signal) -> String {
+    let emoji = match signal.action {
         SignalType::Buy => "🟢",
         SignalType::Sell => "🔴",
         SignalType::Hold => "🟡",
     };
     
-    let action = match signal_type {
-        SignalType::Buy => "BUY",
-        SignalType::Sell => "SELL",
-        SignalType::Hold => "HOLD",
-    };
+    let articles = if signal.article_count == 1 { "article" } else { "articles" };
     
-    format!(
-        "{} {} Signal for {} (Confidence: {:.1}%)",
+    let mut line = format!(
+        "{} {} Signal for {} (Confidence: {:.1}%, {} {})",
         emoji,
-        action,
+        signal.action.as_str(),
         signal.symbol,
-        signal.confidence * rust_decimal_macros::dec!(100)
-    )
+        signal.confidence * rust_decimal_macros::dec!(100),
+        signal.article_count,
+        articles
+    );
+    if !signal.spillover_from.is_empty() {
+        line.push_str(&format!(" [Spillover from {}]", signal.spillover_from.join(", ")));
+    }
+    if let Some(regime) = signal.regime {
+        line.push_str(&format!(" [Regime: {}]", regime.as_str()));
+    }
+    if let Some(levels) = &signal.levels {
+        line.push_str(&format!(
+            " [Stop: {}, Target: {}, R/R: {}]",
+            levels.stop_loss.round_dp(2),
+            levels.take_profit.round_dp(2),
+            levels.risk_reward
+        ));
+    }
+    line
 }
 
 /// Formats an article summary for display
@@ -53,7 +77,7 @@ pub fn format_article(article: &Article) -> String {
 pub fn create_dashboard(
     articles: &[Article],
     sentiments: &[SentimentScore],
-    signals: &[(Signal, SignalType)],
+    signals: &[Signal],
 ) -> String {
     let mut output = String::new();
     
@@ -61,7 +85,7 @@ pub fn create_dashboard(
     output.push_str("║     Sentiment Analysis Trading Dashboard                  ║\n");
     output.push_str("╚════════════════════════════════════════════════════════════╝\n\n");
     
-    output.push_str(&format!("📊 Summary:\n"));
+    output.push_str("📊 Summary:\n");
     output.push_str(&format!("  Articles Analyzed: {}\n", articles.len()));
     output.push_str(&format!("  Signals Generated: {}\n\n", signals.len()));
     
@@ -76,18 +100,28 @@ pub fn create_dashboard(
         
         output.push_str("📈 Average Sentiment:\n");
         output.push_str(&format!("  Positive: {:.1}%\n", avg_positive * rust_decimal_macros::dec!(100)));
-        output.push_str(&format!("  Negative: {:.1}%\n\n", avg_negative * rust_decimal_macros::dec!(100)));
+        output.push_str(&format!("  Negative: {:.1}%\n", avg_negative * rust_decimal_macros::dec!(100)));
+        let avg_neutral = sentiments.iter()
+            .map(|s| s.neutral)
+            .sum::<rust_decimal::Decimal>() / rust_decimal::Decimal::from(sentiments.len());
+        let average = SentimentScore {
+            positive: avg_positive,
+            negative: avg_negative,
+            neutral: avg_neutral,
+        };
+        output.push_str(&format!("  {}\n\n", format_strength(&calculate_signal_strength(&average))));
     }
     
     // Show signals
     if !signals.is_empty() {
         output.push_str("🎯 Active Signals:\n");
-        for (signal, signal_type) in signals {
-            output.push_str(&format!("  {}\n", format_signal(signal, signal_type)));
+        for signal in signals {
+            output.push_str(&format!("  {}\n", format_signal(signal)));
+            output.push_str(&format!("    {}\n", format_strength(&calculate_signal_strength(&signal.sentiment))));
         }
     }
     
-    output.push_str("\n");
+    output.push('\n');
     output.push_str("════════════════════════════════════════════════════════════\n");
     
     output
@@ -131,30 +165,36 @@ mod tests {
     
     #[test]
     fn test_format_signal() {
-        let signal = Signal {
-            symbol: "BTC".to_string(),
-            sentiment: SentimentScore {
-                positive: dec!(0.85),
-                negative: dec!(0.05),
-                neutral: dec!(0.10),
-            },
-            confidence: dec!(0.85),
+        let sentiment = SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
         };
+        let signal = Signal::new("BTC", SignalType::Buy, sentiment, dec!(0.85), 123456);
         
-        let formatted = format_signal(&signal, &SignalType::Buy);
+        let formatted = format_signal(&signal);
         assert!(formatted.contains("BUY"));
         assert!(formatted.contains("BTC"));
         assert!(formatted.contains("85"));
+        assert!(formatted.contains("1 article)"));
+        assert!(format_signal(&signal.clone().with_article_count(4)).contains("4 articles"));
+
+        let levels = crate::types::ProtectiveLevels {
+            entry: dec!(100),
+            stop_loss: dec!(96),
+            take_profit: dec!(108),
+            trailing: None,
+            risk_reward: dec!(2),
+        };
+        assert!(format_signal(&signal.clone().with_levels(levels)).contains("[Stop: 96, Target: 108, R/R: 2]"));
+        assert!(format_signal(&signal.clone().with_spillover_from(&["btc", "eth"])).contains("[Spillover from BTC, ETH]"));
+        let ranging = format_signal(&signal.with_regime(crate::types::Regime::Ranging));
+        assert!(ranging.contains("[Regime: ranging]"));
     }
     
     #[test]
     fn test_format_article() {
-        let article = Article {
-            title: "Test Title".to_string(),
-            content: "Test Content".to_string(),
-            source: "TestSource".to_string(),
-            timestamp: 123456,
-        };
+        let article = Article::new("Test Title", "Test Content", "TestSource", 123456);
         
         let formatted = format_article(&article);
         assert!(formatted.contains("Test Title"));
@@ -164,12 +204,7 @@ mod tests {
     #[test]
     fn test_create_dashboard() {
         let articles = vec![
-            Article {
-                title: "Test".to_string(),
-                content: "Content".to_string(),
-                source: "Source".to_string(),
-                timestamp: 123,
-            },
+            Article::new("Test", "Content", "Source", 123),
         ];
         
         let sentiments = vec![
@@ -181,16 +216,23 @@ mod tests {
         ];
         
         let signals = vec![
-            (Signal {
-                symbol: "BTC".to_string(),
-                sentiment: sentiments[0].clone(),
-                confidence: dec!(0.85),
-            }, SignalType::Buy),
+            Signal::new("BTC", SignalType::Buy, sentiments[0].clone(), dec!(0.85), 123),
         ];
         
         let dashboard = create_dashboard(&articles, &sentiments, &signals);
         assert!(dashboard.contains("Dashboard"));
         assert!(dashboard.contains("Articles Analyzed: 1"));
+        assert!(dashboard.contains("Direction: +70.00 | Conviction: 41.83%"));
+    }
+    
+    #[test]
+    fn test_format_strength() {
+        let bullish = SignalStrength { direction: dec!(80), conviction: dec!(52.83) };
+        assert_eq!(format_strength(&bullish), "🧭 Direction: +80.00 | Conviction: 52.83%");
+        let bearish = SignalStrength { direction: dec!(-35.5), conviction: dec!(10) };
+        assert_eq!(format_strength(&bearish), "🧭 Direction: -35.50 | Conviction: 10.00%");
+        let flat = SignalStrength { direction: dec!(0), conviction: dec!(0) };
+        assert!(format_strength(&flat).starts_with("🧭 Direction: 0.00"));
     }
     
     #[test]
@@ -0,0 +1,192 @@
+//! Indicator conditions that confirm or block sentiment signals
+//!
+//! A [`ConfirmationFilter`] holds the conditions a BUY and a SELL must meet.
+//! A signal whose conditions fail, or cannot be evaluated for lack of price
+//! history, is turned into a HOLD:
+//!
+//! ```
+//! use sentiment_analysis_trading::indicators::{Condition, ConfirmationFilter};
+//! use rust_decimal_macros::dec;
+//!
+//! // Only BUY above the 50-period EMA and while RSI(14) is below 70
+//! let filter = ConfirmationFilter::default()
+//!     .require_for_buy(Condition::PriceAboveEma { period: 50 })
+//!     .require_for_buy(Condition::RsiBelow { period: 14, level: dec!(70) });
+//! assert_eq!(filter.buy.len(), 2);
+//! ```
+
+use super::{atr, bollinger, closes, ema, latest, macd, rsi, sma, vwap};
+use crate::correlation::PricePoint;
+use crate::types::{Signal, SignalType};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// A test on the latest point of a price series
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "indicator", rename_all = "snake_case")]
+pub enum Condition {
+    PriceAboveSma { period: usize },
+    PriceBelowSma { period: usize },
+    PriceAboveEma { period: usize },
+    PriceBelowEma { period: usize },
+    RsiBelow { period: usize, level: Decimal },
+    RsiAbove { period: usize, level: Decimal },
+    MacdAboveSignal { fast: usize, slow: usize, signal: usize },
+    MacdBelowSignal { fast: usize, slow: usize, signal: usize },
+    /// Price at or below the upper Bollinger band
+    BelowUpperBand { period: usize, k: Decimal },
+    /// Price at or above the lower Bollinger band
+    AboveLowerBand { period: usize, k: Decimal },
+    PriceAboveVwap { period: usize },
+    PriceBelowVwap { period: usize },
+    /// ATR as a fraction of price at or below `max`, e.g. 0.05 = 5%
+    MaxAtrRatio { period: usize, max: Decimal },
+}
+
+impl Condition {
+    /// Whether the condition holds at the last point of `prices`, or `None`
+    /// if the series is too short
+    pub fn check(&self, prices: &[PricePoint]) -> Option<bool> {
+        let price = prices.last()?.price;
+        let values = closes(prices);
+        Some(match self {
+            Condition::PriceAboveSma { period } => price > latest(&sma(&values, *period))?,
+            Condition::PriceBelowSma { period } => price < latest(&sma(&values, *period))?,
+            Condition::PriceAboveEma { period } => price > latest(&ema(&values, *period))?,
+            Condition::PriceBelowEma { period } => price < latest(&ema(&values, *period))?,
+            Condition::RsiBelow { period, level } => latest(&rsi(&values, *period))? < *level,
+            Condition::RsiAbove { period, level } => latest(&rsi(&values, *period))? > *level,
+            Condition::MacdAboveSignal { fast, slow, signal } => {
+                let m = latest(&macd(&values, *fast, *slow, *signal))?;
+                m.macd > m.signal
+            }
+            Condition::MacdBelowSignal { fast, slow, signal } => {
+                let m = latest(&macd(&values, *fast, *slow, *signal))?;
+                m.macd < m.signal
+            }
+            Condition::BelowUpperBand { period, k } => price <= latest(&bollinger(&values, *period, *k))?.upper,
+            Condition::AboveLowerBand { period, k } => price >= latest(&bollinger(&values, *period, *k))?.lower,
+            Condition::PriceAboveVwap { period } => price > latest(&vwap(prices, *period))?,
+            Condition::PriceBelowVwap { period } => price < latest(&vwap(prices, *period))?,
+            Condition::MaxAtrRatio { period, max } => {
+                if price.is_zero() {
+                    return None;
+                }
+                latest(&atr(prices, *period))? / price <= *max
+            }
+        })
+    }
+}
+
+/// Conditions BUY and SELL signals must meet before they are acted on
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfirmationFilter {
+    pub buy: Vec<Condition>,
+    pub sell: Vec<Condition>,
+}
+
+impl ConfirmationFilter {
+    pub fn require_for_buy(mut self, condition: Condition) -> Self {
+        self.buy.push(condition);
+        self
+    }
+
+    pub fn require_for_sell(mut self, condition: Condition) -> Self {
+        self.sell.push(condition);
+        self
+    }
+
+    /// Conditions for `action` that fail or cannot be evaluated on `prices`
+    pub fn failed(&self, action: SignalType, prices: &[PricePoint]) -> Vec<&Condition> {
+        let conditions = match action {
+            SignalType::Buy => &self.buy,
+            SignalType::Sell => &self.sell,
+            SignalType::Hold => return Vec::new(),
+        };
+        conditions
+            .iter()
+            .filter(|c| c.check(prices) != Some(true))
+            .collect()
+    }
+
+    /// Returns the signal unchanged if its conditions hold on `prices`,
+    /// otherwise the same signal as a HOLD
+    pub fn confirm(&self, signal: Signal, prices: &[PricePoint]) -> Signal {
+        let failed = self.failed(signal.action, prices);
+        if failed.is_empty() {
+            return signal;
+        }
+        log::debug!("{} {} not confirmed: {:?}", signal.symbol, signal.action.as_str(), failed);
+        let mut held = Signal { action: SignalType::Hold, levels: None, ..signal };
+        held.id = held.compute_id();
+        held
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SentimentScore;
+    use rust_decimal_macros::dec;
+
+    fn trend(start: i64, step: i64, len: usize) -> Vec<PricePoint> {
+        (0..len as i64)
+            .map(|i| PricePoint {
+                timestamp: i * 3600,
+                price: Decimal::from(start + step * i),
+                volume: Some(dec!(10)),
+            })
+            .collect()
+    }
+
+    fn buy() -> Signal {
+        let sentiment = SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
+        };
+        Signal::new("BTC", SignalType::Buy, sentiment, dec!(0.85), 0)
+    }
+
+    #[test]
+    fn test_conditions() {
+        let up = trend(100, 1, 60);
+        assert_eq!(Condition::PriceAboveEma { period: 50 }.check(&up), Some(true));
+        assert_eq!(Condition::PriceBelowSma { period: 20 }.check(&up), Some(false));
+        assert_eq!(Condition::RsiBelow { period: 14, level: dec!(70) }.check(&up), Some(false));
+        assert_eq!(Condition::PriceAboveVwap { period: 10 }.check(&up), Some(true));
+        assert_eq!(Condition::MaxAtrRatio { period: 14, max: dec!(0.05) }.check(&up), Some(true));
+        assert_eq!(Condition::BelowUpperBand { period: 20, k: dec!(2) }.check(&up), Some(true));
+        assert_eq!(Condition::PriceAboveEma { period: 50 }.check(&up[..10]), None);
+    }
+
+    #[test]
+    fn test_confirmation_filter() {
+        let filter = ConfirmationFilter::default()
+            .require_for_buy(Condition::PriceAboveEma { period: 50 })
+            .require_for_buy(Condition::RsiBelow { period: 14, level: dec!(70) });
+
+        // Downtrend: BUY is blocked
+        let down = trend(200, -1, 60);
+        let blocked = filter.confirm(buy(), &down);
+        assert_eq!(blocked.action, SignalType::Hold);
+        assert_eq!(blocked.id, blocked.compute_id());
+        assert_eq!(filter.failed(SignalType::Buy, &down).len(), 1);
+
+        // Uptrend that pulled back: above the EMA with RSI below 70
+        let mut pullback = trend(100, 1, 60);
+        for (i, p) in pullback.iter_mut().skip(55).enumerate() {
+            p.price = dec!(150) - Decimal::from(2 * i as i64);
+        }
+        assert!(Condition::MacdBelowSignal { fast: 12, slow: 26, signal: 9 }.check(&pullback).unwrap());
+        assert_eq!(filter.confirm(buy(), &pullback).action, SignalType::Buy);
+
+        // Not enough history is not a confirmation
+        assert_eq!(filter.confirm(buy(), &pullback[..20]).action, SignalType::Hold);
+
+        let json = serde_json::to_string(&filter).unwrap();
+        assert!(json.contains("\"indicator\":\"price_above_ema\""));
+        assert_eq!(serde_json::from_str::<ConfirmationFilter>(&json).unwrap(), filter);
+    }
+}
@@ -0,0 +1,261 @@
+//! Indicators Module - Technical Indicators over Price Series
+//!
+//! Classic indicators computed with exact `Decimal` arithmetic. Every
+//! function returns a series aligned with its input, with `None` until
+//! enough points exist. [`confirm`] turns them into conditions that confirm
+//! or block sentiment signals.
+//!
+//! `PricePoint` only carries a close price, so ATR uses the close-to-close
+//! true range, and VWAP skips points without a volume.
+
+pub mod confirm;
+
+pub use confirm::{Condition, ConfirmationFilter};
+
+use crate::correlation::PricePoint;
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// MACD line, its signal line and their difference
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Macd {
+    pub macd: Decimal,
+    pub signal: Decimal,
+    pub histogram: Decimal,
+}
+
+/// Bollinger bands around a simple moving average
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bands {
+    pub lower: Decimal,
+    pub middle: Decimal,
+    pub upper: Decimal,
+}
+
+/// Close prices of a series
+pub fn closes(prices: &[PricePoint]) -> Vec<Decimal> {
+    prices.iter().map(|p| p.price).collect()
+}
+
+/// Simple moving average over `period` values
+pub fn sma(values: &[Decimal], period: usize) -> Vec<Option<Decimal>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 {
+        return out;
+    }
+    let mut sum = Decimal::ZERO;
+    for (i, value) in values.iter().enumerate() {
+        sum += *value;
+        if i >= period {
+            sum -= values[i - period];
+        }
+        if i + 1 >= period {
+            out[i] = Some(sum / Decimal::from(period));
+        }
+    }
+    out
+}
+
+/// Exponential moving average with `alpha = 2 / (period + 1)`, seeded with
+/// the simple average of the first `period` values
+pub fn ema(values: &[Decimal], period: usize) -> Vec<Option<Decimal>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 || values.len() < period {
+        return out;
+    }
+    let alpha = dec!(2) / Decimal::from(period + 1);
+    let mut current = values[..period].iter().sum::<Decimal>() / Decimal::from(period);
+    out[period - 1] = Some(current);
+    for i in period..values.len() {
+        current += alpha * (values[i] - current);
+        out[i] = Some(current);
+    }
+    out
+}
+
+/// Relative strength index (0-100) with Wilder smoothing
+pub fn rsi(values: &[Decimal], period: usize) -> Vec<Option<Decimal>> {
+    let mut out = vec![None; values.len()];
+    if period == 0 || values.len() <= period {
+        return out;
+    }
+    let change = |i: usize| values[i] - values[i - 1];
+    let n = Decimal::from(period);
+    let mut gain = (1..=period).map(|i| change(i).max(Decimal::ZERO)).sum::<Decimal>() / n;
+    let mut loss = (1..=period).map(|i| (-change(i)).max(Decimal::ZERO)).sum::<Decimal>() / n;
+    let index = |gain: Decimal, loss: Decimal| {
+        if loss.is_zero() {
+            dec!(100)
+        } else {
+            dec!(100) - dec!(100) / (Decimal::ONE + gain / loss)
+        }
+    };
+    out[period] = Some(index(gain, loss));
+    for (i, slot) in out.iter_mut().enumerate().skip(period + 1) {
+        gain = (gain * (n - Decimal::ONE) + change(i).max(Decimal::ZERO)) / n;
+        loss = (loss * (n - Decimal::ONE) + (-change(i)).max(Decimal::ZERO)) / n;
+        *slot = Some(index(gain, loss));
+    }
+    out
+}
+
+/// MACD: fast EMA minus slow EMA, with an EMA of that as the signal line
+pub fn macd(values: &[Decimal], fast: usize, slow: usize, signal: usize) -> Vec<Option<Macd>> {
+    let fast_ema = ema(values, fast);
+    let slow_ema = ema(values, slow);
+    let lines: Vec<Option<Decimal>> = fast_ema
+        .iter()
+        .zip(&slow_ema)
+        .map(|(f, s)| Some((*f)? - (*s)?))
+        .collect();
+    let start = lines.iter().position(Option::is_some);
+    let mut out = vec![None; values.len()];
+    if let Some(start) = start {
+        let defined: Vec<Decimal> = lines[start..].iter().flatten().copied().collect();
+        for (offset, signal_line) in ema(&defined, signal).into_iter().enumerate() {
+            if let Some(signal_line) = signal_line {
+                let line = defined[offset];
+                out[start + offset] = Some(Macd {
+                    macd: line,
+                    signal: signal_line,
+                    histogram: line - signal_line,
+                });
+            }
+        }
+    }
+    out
+}
+
+/// Bollinger bands: SMA plus and minus `k` population standard deviations
+pub fn bollinger(values: &[Decimal], period: usize, k: Decimal) -> Vec<Option<Bands>> {
+    sma(values, period)
+        .into_iter()
+        .enumerate()
+        .map(|(i, middle)| {
+            let middle = middle?;
+            let window = &values[i + 1 - period..=i];
+            let variance = window.iter().map(|v| (*v - middle) * (*v - middle)).sum::<Decimal>() / Decimal::from(period);
+            let width = k * variance.sqrt()?;
+            Some(Bands {
+                lower: middle - width,
+                middle,
+                upper: middle + width,
+            })
+        })
+        .collect()
+}
+
+/// Average true range with Wilder smoothing, using the close-to-close move
+/// as the true range since `PricePoint` has no high or low
+pub fn atr(prices: &[PricePoint], period: usize) -> Vec<Option<Decimal>> {
+    let mut out = vec![None; prices.len()];
+    if period == 0 || prices.len() <= period {
+        return out;
+    }
+    let range = |i: usize| (prices[i].price - prices[i - 1].price).abs();
+    let n = Decimal::from(period);
+    let mut current = (1..=period).map(range).sum::<Decimal>() / n;
+    out[period] = Some(current);
+    for (i, slot) in out.iter_mut().enumerate().skip(period + 1) {
+        current = (current * (n - Decimal::ONE) + range(i)) / n;
+        *slot = Some(current);
+    }
+    out
+}
+
+/// Volume-weighted average price over the last `period` points. Points
+/// without a volume are skipped; `None` when the window has no volume.
+pub fn vwap(prices: &[PricePoint], period: usize) -> Vec<Option<Decimal>> {
+    (0..prices.len())
+        .map(|i| {
+            if period == 0 || i + 1 < period {
+                return None;
+            }
+            let (value, volume) = prices[i + 1 - period..=i]
+                .iter()
+                .filter_map(|p| p.volume.map(|v| (p.price * v, v)))
+                .fold((Decimal::ZERO, Decimal::ZERO), |(pv, vol), (a, b)| (pv + a, vol + b));
+            (!volume.is_zero()).then(|| value / volume)
+        })
+        .collect()
+}
+
+/// Last defined value of an indicator series
+pub fn latest<T: Copy>(series: &[Option<T>]) -> Option<T> {
+    series.last().copied().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(raw: &[i64]) -> Vec<Decimal> {
+        raw.iter().map(|v| Decimal::from(*v)).collect()
+    }
+
+    fn points(raw: &[(i64, Option<i64>)]) -> Vec<PricePoint> {
+        raw.iter()
+            .enumerate()
+            .map(|(i, (price, volume))| PricePoint {
+                timestamp: i as i64,
+                price: Decimal::from(*price),
+                volume: volume.map(Decimal::from),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sma_and_ema() {
+        let series = values(&[1, 2, 3, 4, 5]);
+        assert_eq!(sma(&series, 3), [None, None, Some(dec!(2)), Some(dec!(3)), Some(dec!(4))]);
+        // alpha = 0.5, seeded with 2
+        assert_eq!(ema(&series, 3), [None, None, Some(dec!(2)), Some(dec!(3)), Some(dec!(4))]);
+        assert_eq!(ema(&values(&[2, 4, 6, 2]), 3)[3], Some(dec!(3)));
+        assert_eq!(latest(&sma(&series, 6)), None);
+    }
+
+    #[test]
+    fn test_rsi() {
+        let rising = values(&[1, 2, 3, 4, 5]);
+        assert_eq!(latest(&rsi(&rising, 3)), Some(dec!(100)));
+
+        let mixed = values(&[10, 12, 11, 13, 12]);
+        let series = rsi(&mixed, 2);
+        // gains 2, 0 / losses 0, 1 -> avg 1 / 0.5 -> RS 2
+        assert_eq!(series[2].map(|v| v.round_dp(4)), Some(dec!(66.6667)));
+        // next: gain (1 + 2) / 2 = 1.5, loss (0.5 + 0) / 2 = 0.25 -> RS 6
+        assert_eq!(series[3].map(|v| v.round_dp(4)), Some(dec!(85.7143)));
+        assert_eq!(series[..2], [None, None]);
+    }
+
+    #[test]
+    fn test_macd_and_bollinger() {
+        let series = values(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        let lines = macd(&series, 2, 4, 2);
+        // Slow EMA starts at index 3, the signal line one point later
+        assert!(lines[..4].iter().all(Option::is_none));
+        let last = lines[7].unwrap();
+        assert_eq!(last.histogram, last.macd - last.signal);
+        assert!(last.macd > Decimal::ZERO);
+
+        let bands = bollinger(&values(&[2, 4, 4, 4, 5, 5, 7, 9]), 8, dec!(2));
+        let last = latest(&bands).unwrap();
+        assert_eq!(last.middle, dec!(5));
+        assert_eq!(last.upper, dec!(9));
+        assert_eq!(last.lower, dec!(1));
+    }
+
+    #[test]
+    fn test_atr_and_vwap() {
+        let prices = points(&[(10, Some(1)), (12, None), (11, Some(3)), (14, Some(2))]);
+        // ranges 2, 1, 3
+        assert_eq!(atr(&prices, 2), [None, None, Some(dec!(1.5)), Some(dec!(2.25))]);
+
+        let vw = vwap(&prices, 2);
+        assert_eq!(vw[1], Some(dec!(10)));
+        assert_eq!(vw[2], Some(dec!(11)));
+        assert_eq!(vw[3], Some(dec!(12.2)));
+        assert_eq!(vwap(&points(&[(10, None), (11, None)]), 2)[1], None);
+    }
+}
@@ -9,6 +9,8 @@
 //! - `signals` - Trading signal generation
 //! - `scrapers` - Web scraping for data collection
 //! - `correlation` - Price correlation analysis
+//! - `indicators` - Technical indicators and confirmation filters
+//! - `risk` - Position sizing and risk controls
 //! - `dashboard` - Data visualization
 //! - `types` - Core data types
 
@@ -16,6 +18,8 @@ pub mod scrapers;
 pub mod nlp;
 pub mod signals;
 pub mod correlation;
+pub mod indicators;
+pub mod risk;
 pub mod dashboard;
 pub mod types;
 
@@ -28,14 +32,36 @@ pub use nlp::{analyze_sentiment, preprocess_text, extract_entities};
 // Re-export signal functions
 pub use signals::{
     generate_signal, 
-    generate_signal_with_type, 
+    generate_signal_at, 
+    generate_aggregate_signal, 
+    generate_signal_in_regime, 
+    generate_aggregate_signal_in_regime, 
+    MultiHorizonGenerator, 
+    HorizonSpec, 
+    SpilloverModel, 
+    AssetGraph, 
+    classify_regime, 
+    RegimeConfig, 
+    Regime, 
+    SentimentAggregator, 
+    AggregatorConfig, 
+    MomentumConfig, 
+    momentum_signal, 
+    zscore_signal, 
+    volume_spike_signal, 
+    SignalFilter, 
+    FilterConfig, 
+    Strategy, 
+    MarketEvent, 
     SignalType, 
+    SignalConfig,
     calculate_signal_strength, 
+    SignalStrength, 
     is_signal_actionable
 };
 
 // Re-export scraper utilities
-pub use scrapers::{NewsScraper, MockDataProvider, RateLimiter};
+pub use scrapers::{ArticleSource, NewsScraper, MockDataProvider, RateLimiter, CrawlerConfig, PoliteFetcher, ReplaySource, Scheduler};
 
 // Re-export correlation types and functions
 pub use correlation::{
@@ -47,6 +73,9 @@ pub use correlation::{
     calculate_price_change,
     predict_price_direction,
     calculate_price_target,
+    detect_divergences,
+    divergence_signal,
+    DivergenceConfig,
 };
 
 
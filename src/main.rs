@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use sentiment_analysis_trading::*;
 use sentiment_analysis_trading::scrapers::MockDataProvider;
 use sentiment_analysis_trading::dashboard;
@@ -13,13 +13,25 @@ async fn main() -> Result<()> {
     
     println!("🔍 Collecting sample articles...\n");
     
+    // Signal thresholds, optionally from a TOML/JSON file
+    let config = match std::env::var("SIGNAL_CONFIG") {
+        Ok(path) => SignalConfig::load(&path)?,
+        Err(_) => SignalConfig::default(),
+    };
+    
+    // Optional append-only record of every emitted signal
+    let mut audit = match std::env::var("SIGNAL_AUDIT_LOG") {
+        Ok(path) => Some(signals::AuditLog::open(&path)?),
+        Err(_) => None,
+    };
+    
     // Get sample articles
     let articles = MockDataProvider::get_sample_articles();
     println!("📰 Found {} articles\n", articles.len());
     
     // Analyze sentiment for each article
     let mut sentiments = Vec::new();
-    let mut signals_with_types = Vec::new();
+    let mut signals = Vec::new();
     
     for (i, article) in articles.iter().enumerate() {
         println!("─────────────────────────────────────────────────────────────");
@@ -44,30 +56,74 @@ async fn main() -> Result<()> {
             "MARKET".to_string()
         };
         
-        let (signal, signal_type) = generate_signal_with_type(&sentiment, &symbol)?;
-        println!("\n{}", dashboard::format_signal(&signal, &signal_type));
-        println!("Signal Strength: {}/100", calculate_signal_strength(&sentiment));
+        let signal = generate_signal(&sentiment, &symbol, &config)?.with_source_articles(&[&article.id]);
+        if let Some(audit) = audit.as_mut() {
+            let inputs = signals::SignalInputs::Sentiment {
+                sentiment: sentiment.clone(),
+                article_ids: vec![article.id.clone()],
+            };
+            audit.record(&signal, inputs, &config)?;
+        }
+        println!("\n{}", dashboard::format_signal(&signal));
+        println!("{}", dashboard::format_strength(&calculate_signal_strength(&sentiment)));
         
         sentiments.push(sentiment);
-        signals_with_types.push((signal, signal_type));
+        signals.push(signal);
         
         println!();
     }
     
+    // Aggregate per symbol so one headline does not decide the signal
+    let mut aggregator = SentimentAggregator::default();
+    for (article, sentiment) in articles.iter().zip(&sentiments) {
+        aggregator.observe_article(article, sentiment);
+    }
+    let now = articles.iter().map(|a| a.timestamp).max().unwrap_or_default();
+    println!("🧮 Aggregated signals ({}h window):", aggregator.config().window_secs / 3600);
+    for aggregate in aggregator.snapshot(now) {
+        let signal = generate_aggregate_signal(&aggregate, &config)?;
+        println!("  {}", dashboard::format_signal(&signal));
+        if let Some(audit) = audit.as_mut() {
+            let inputs = signals::SignalInputs::Aggregate { aggregate, horizon_secs: None };
+            audit.record(&signal, inputs, &config)?;
+        }
+    }
+    println!();
+    
+    // The same articles read over intraday, swing and position horizons
+    let mut horizons = MultiHorizonGenerator::default();
+    for (article, sentiment) in articles.iter().zip(&sentiments) {
+        horizons.observe_article(article, sentiment);
+    }
+    println!("⏱️  Signals by horizon:");
+    for symbol in aggregator.symbols() {
+        for horizon_signal in horizons.generate(&symbol, now)? {
+            println!("  {:<8} {}", horizon_signal.horizon, dashboard::format_signal(&horizon_signal.signal));
+            if let Some(audit) = audit.as_mut() {
+                let spec = horizons
+                    .horizons()
+                    .find(|spec| spec.name == horizon_signal.horizon)
+                    .ok_or_else(|| anyhow!("unknown horizon {}", horizon_signal.horizon))?;
+                audit.record(&horizon_signal.signal, (&horizon_signal).into(), &spec.thresholds)?;
+            }
+        }
+    }
+    println!();
+    
     // Display dashboard summary
     println!("\n");
-    println!("{}", dashboard::create_dashboard(&articles, &sentiments, &signals_with_types));
+    println!("{}", dashboard::create_dashboard(&articles, &sentiments, &signals));
     
     // Show correlation insights
     println!("💡 Insights:");
-    let buy_signals = signals_with_types.iter()
-        .filter(|(_, t)| *t == SignalType::Buy)
+    let buy_signals = signals.iter()
+        .filter(|s| s.action == SignalType::Buy)
         .count();
-    let sell_signals = signals_with_types.iter()
-        .filter(|(_, t)| *t == SignalType::Sell)
+    let sell_signals = signals.iter()
+        .filter(|s| s.action == SignalType::Sell)
         .count();
-    let hold_signals = signals_with_types.iter()
-        .filter(|(_, t)| *t == SignalType::Hold)
+    let hold_signals = signals.iter()
+        .filter(|s| s.action == SignalType::Hold)
         .count();
     
     println!("  🟢 Buy Signals: {}", buy_signals);
@@ -84,6 +140,7 @@ async fn main() -> Result<()> {
     
     println!("\n✅ Analysis complete!");
     println!("\nRun 'cargo run --example sentiment_analysis' for more examples");
+    println!("Run 'cargo run --example live_collection' to collect articles continuously");
     
     Ok(())
 }
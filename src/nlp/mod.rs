@@ -27,12 +27,12 @@ use rust_decimal_macros::dec;
 /// use sentiment_analysis_trading::{Article, analyze_sentiment};
 /// use rust_decimal_macros::dec;
 ///
-/// let article = Article {
-///     title: "Bitcoin surges to new highs".to_string(),
-///     content: "Bitcoin reaches unprecedented levels".to_string(),
-///     source: "CryptoNews".to_string(),
-///     timestamp: 1696435200,
-/// };
+/// let article = Article::new(
+///     "Bitcoin surges to new highs",
+///     "Bitcoin reaches unprecedented levels",
+///     "CryptoNews",
+///     1696435200,
+/// );
 ///
 /// let sentiment = analyze_sentiment(&article).unwrap();
 /// assert!(sentiment.positive > dec!(0.5));
@@ -122,12 +122,12 @@ mod tests {
     
     #[test]
     fn test_positive_sentiment() {
-        let article = Article {
-            title: "Bitcoin surges to record high".to_string(),
-            content: "Great gains as bullish trend continues".to_string(),
-            source: "Test".to_string(),
-            timestamp: 123,
-        };
+        let article = Article::new(
+            "Bitcoin surges to record high",
+            "Great gains as bullish trend continues",
+            "Test",
+            123,
+        );
         
         let sentiment = analyze_sentiment(&article).unwrap();
         assert!(sentiment.positive > sentiment.negative);
@@ -136,12 +136,12 @@ mod tests {
     
     #[test]
     fn test_negative_sentiment() {
-        let article = Article {
-            title: "Market crashes amid fears".to_string(),
-            content: "Bearish concerns as losses mount".to_string(),
-            source: "Test".to_string(),
-            timestamp: 123,
-        };
+        let article = Article::new(
+            "Market crashes amid fears",
+            "Bearish concerns as losses mount",
+            "Test",
+            123,
+        );
         
         let sentiment = analyze_sentiment(&article).unwrap();
         assert!(sentiment.negative > sentiment.positive);
@@ -150,12 +150,12 @@ mod tests {
     
     #[test]
     fn test_neutral_sentiment() {
-        let article = Article {
-            title: "Market analysis report".to_string(),
-            content: "The market shows mixed signals today".to_string(),
-            source: "Test".to_string(),
-            timestamp: 123,
-        };
+        let article = Article::new(
+            "Market analysis report",
+            "The market shows mixed signals today",
+            "Test",
+            123,
+        );
         
         let sentiment = analyze_sentiment(&article).unwrap();
         assert!(sentiment.neutral > dec!(0.5));
@@ -179,12 +179,7 @@ mod tests {
     
     #[test]
     fn test_sentiment_scores_sum_to_one() {
-        let article = Article {
-            title: "Test".to_string(),
-            content: "Content".to_string(),
-            source: "Test".to_string(),
-            timestamp: 123,
-        };
+        let article = Article::new("Test", "Content", "Test", 123);
         
         let sentiment = analyze_sentiment(&article).unwrap();
         let sum = sentiment.positive + sentiment.negative + sentiment.neutral;
@@ -0,0 +1,15 @@
+//! Risk Module - Position Sizing and Risk Controls
+//!
+//! Turns actionable signals into positions that downstream order logic can
+//! consume. [`sizing`] converts a signal's confidence, the account equity and
+//! recent volatility into a capped target quantity; [`stops`] attaches
+//! stop-loss, take-profit and trailing levels and rejects signals with a poor
+//! risk/reward.
+
+pub mod sizing;
+pub mod stops;
+
+pub use sizing::{
+    realized_volatility, size_position, PositionSize, SizeCap, SizingConfig, SizingInput, SizingMethod,
+};
+pub use stops::{trail_stop, RiskUnit, StopConfig, TrailingConfig};
@@ -0,0 +1,316 @@
+//! Position sizing from signal confidence and volatility
+//!
+//! [`size_position`] turns a signal, the account equity and recent volatility
+//! into a signed target quantity (positive long, negative short, zero for
+//! HOLD). The sizing method picks a fraction of equity:
+//!
+//! - **fixed fractional**: `fraction * confidence`
+//! - **volatility target**: `target_volatility / volatility * confidence`
+//! - **fractional Kelly**: `kelly_fraction * (p - (1 - p) / payoff_ratio)`,
+//!   using the signal confidence as the win probability `p`
+//!
+//! The fraction is then capped by `max_position` and by the room left under
+//! `max_gross_exposure`, both as fractions of equity.
+
+use crate::correlation::PricePoint;
+use crate::types::{Signal, SignalType};
+use anyhow::{anyhow, bail, Result};
+use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// How the fraction of equity to commit is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SizingMethod {
+    FixedFractional { fraction: Decimal },
+    /// `target_volatility` uses the same period as the volatility passed in
+    VolatilityTarget { target_volatility: Decimal },
+    FractionalKelly { kelly_fraction: Decimal, payoff_ratio: Decimal },
+}
+
+/// Sizing method, caps and rounding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizingConfig {
+    pub method: SizingMethod,
+    /// Largest position as a fraction of equity
+    pub max_position: Decimal,
+    /// Largest total notional across all positions as a fraction of equity
+    pub max_gross_exposure: Decimal,
+    /// Quantities are rounded down to a multiple of this (0 = no rounding)
+    #[serde(default)]
+    pub lot_size: Decimal,
+}
+
+impl Default for SizingConfig {
+    fn default() -> Self {
+        Self {
+            method: SizingMethod::FixedFractional { fraction: dec!(0.1) },
+            max_position: dec!(0.2),
+            max_gross_exposure: dec!(1),
+            lot_size: Decimal::ZERO,
+        }
+    }
+}
+
+impl SizingConfig {
+    pub fn new(method: SizingMethod) -> Self {
+        Self {
+            method,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the per-position and gross exposure caps
+    pub fn with_caps(mut self, max_position: Decimal, max_gross_exposure: Decimal) -> Self {
+        self.max_position = max_position;
+        self.max_gross_exposure = max_gross_exposure;
+        self
+    }
+
+    pub fn with_lot_size(mut self, lot_size: Decimal) -> Self {
+        self.lot_size = lot_size;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let positive = |name: &str, value: Decimal| {
+            if value <= Decimal::ZERO {
+                bail!("{} must be positive, got {}", name, value);
+            }
+            Ok(())
+        };
+        match self.method {
+            SizingMethod::FixedFractional { fraction } => positive("fraction", fraction)?,
+            SizingMethod::VolatilityTarget { target_volatility } => positive("target_volatility", target_volatility)?,
+            SizingMethod::FractionalKelly { kelly_fraction, payoff_ratio } => {
+                positive("kelly_fraction", kelly_fraction)?;
+                positive("payoff_ratio", payoff_ratio)?;
+            }
+        }
+        positive("max_position", self.max_position)?;
+        positive("max_gross_exposure", self.max_gross_exposure)?;
+        if self.lot_size < Decimal::ZERO {
+            bail!("lot_size must not be negative, got {}", self.lot_size);
+        }
+        Ok(())
+    }
+}
+
+/// Account and market state the size depends on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SizingInput {
+    pub equity: Decimal,
+    pub price: Decimal,
+    /// Standard deviation of returns per period, e.g. from [`realized_volatility`]
+    pub volatility: Decimal,
+    /// Notional already committed to other positions
+    #[serde(default)]
+    pub gross_exposure: Decimal,
+}
+
+/// Which cap reduced the position
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeCap {
+    MaxPosition,
+    GrossExposure,
+}
+
+/// Target position for a signal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositionSize {
+    pub symbol: String,
+    pub signal_id: String,
+    /// Signed target quantity: positive long, negative short
+    pub quantity: Decimal,
+    /// Absolute value of `quantity * price`
+    pub notional: Decimal,
+    /// `notional / equity`
+    pub fraction_of_equity: Decimal,
+    pub capped_by: Option<SizeCap>,
+}
+
+/// Fraction of equity the method asks for, before caps. Expects a method
+/// that passed [`SizingConfig::validate`].
+pub(crate) fn raw_fraction(method: &SizingMethod, confidence: Decimal, volatility: Decimal) -> Result<Decimal> {
+    Ok(match *method {
+        SizingMethod::FixedFractional { fraction } => fraction * confidence,
+        SizingMethod::VolatilityTarget { target_volatility } => {
+            if volatility <= Decimal::ZERO {
+                bail!("volatility must be positive for volatility targeting, got {}", volatility);
+            }
+            target_volatility
+                .checked_div(volatility)
+                .and_then(|ratio| ratio.checked_mul(confidence))
+                .ok_or_else(|| anyhow!("volatility {} is too small to target {}", volatility, target_volatility))?
+        }
+        SizingMethod::FractionalKelly { kelly_fraction, payoff_ratio } => {
+            let kelly = confidence - (Decimal::ONE - confidence) / payoff_ratio;
+            kelly_fraction * kelly.max(Decimal::ZERO)
+        }
+    })
+}
+
+/// Sizes the position for `signal`. HOLD signals get a zero quantity.
+pub fn size_position(signal: &Signal, input: &SizingInput, config: &SizingConfig) -> Result<PositionSize> {
+    config.validate()?;
+    if input.equity <= Decimal::ZERO || input.price <= Decimal::ZERO {
+        bail!("equity and price must be positive, got {} and {}", input.equity, input.price);
+    }
+
+    let direction = match signal.action {
+        SignalType::Buy => Decimal::ONE,
+        SignalType::Sell => -Decimal::ONE,
+        SignalType::Hold => Decimal::ZERO,
+    };
+    let mut capped_by = None;
+    let mut fraction = if direction.is_zero() {
+        Decimal::ZERO
+    } else {
+        raw_fraction(&config.method, signal.confidence, input.volatility)?
+    };
+    if fraction > config.max_position {
+        fraction = config.max_position;
+        capped_by = Some(SizeCap::MaxPosition);
+    }
+
+    let room = (config.max_gross_exposure * input.equity - input.gross_exposure).max(Decimal::ZERO);
+    let mut notional = fraction * input.equity;
+    if notional > room {
+        notional = room;
+        capped_by = Some(SizeCap::GrossExposure);
+    }
+
+    let mut quantity = notional / input.price;
+    if config.lot_size > Decimal::ZERO {
+        quantity = (quantity / config.lot_size).floor() * config.lot_size;
+    }
+    let notional = quantity * input.price;
+
+    Ok(PositionSize {
+        symbol: signal.symbol.clone(),
+        signal_id: signal.id.clone(),
+        quantity: (quantity * direction).normalize(),
+        notional: notional.normalize(),
+        fraction_of_equity: (notional / input.equity).round_dp(6),
+        capped_by,
+    })
+}
+
+/// Population standard deviation of simple returns over the last `lookback`
+/// returns. `None` with fewer than two returns or a zero price.
+pub fn realized_volatility(prices: &[PricePoint], lookback: usize) -> Option<Decimal> {
+    let start = prices.len().saturating_sub(lookback + 1);
+    let window = &prices[start..];
+    let returns: Vec<Decimal> = window
+        .windows(2)
+        .map(|w| {
+            if w[0].price.is_zero() {
+                None
+            } else {
+                Some((w[1].price - w[0].price) / w[0].price)
+            }
+        })
+        .collect::<Option<_>>()?;
+    if returns.len() < 2 {
+        return None;
+    }
+    let n = Decimal::from(returns.len());
+    let mean = returns.iter().sum::<Decimal>() / n;
+    let variance = returns.iter().map(|r| (*r - mean) * (*r - mean)).sum::<Decimal>() / n;
+    variance.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SentimentScore;
+
+    fn signal(action: SignalType, confidence: Decimal) -> Signal {
+        let sentiment = SentimentScore {
+            positive: dec!(0.8),
+            negative: dec!(0.1),
+            neutral: dec!(0.1),
+        };
+        Signal::new("BTC", action, sentiment, confidence, 0)
+    }
+
+    fn input() -> SizingInput {
+        SizingInput {
+            equity: dec!(100000),
+            price: dec!(50000),
+            volatility: dec!(0.04),
+            gross_exposure: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_methods() {
+        let fixed = SizingConfig::new(SizingMethod::FixedFractional { fraction: dec!(0.1) });
+        let size = size_position(&signal(SignalType::Buy, dec!(0.8)), &input(), &fixed).unwrap();
+        assert_eq!(size.notional, dec!(8000));
+        assert_eq!(size.quantity, dec!(0.16));
+        assert_eq!(size.capped_by, None);
+
+        let vol = SizingConfig::new(SizingMethod::VolatilityTarget { target_volatility: dec!(0.005) });
+        let size = size_position(&signal(SignalType::Sell, dec!(0.8)), &input(), &vol).unwrap();
+        assert_eq!(size.fraction_of_equity, dec!(0.1));
+        assert_eq!(size.quantity, dec!(-0.2));
+
+        // p = 0.6, b = 2 -> Kelly 0.4, half Kelly 0.2
+        let kelly = SizingConfig::new(SizingMethod::FractionalKelly { kelly_fraction: dec!(0.5), payoff_ratio: dec!(2) })
+            .with_caps(dec!(0.5), dec!(1));
+        let size = size_position(&signal(SignalType::Buy, dec!(0.6)), &input(), &kelly).unwrap();
+        assert_eq!(size.fraction_of_equity, dec!(0.2));
+        // No edge, no position
+        let size = size_position(&signal(SignalType::Buy, dec!(0.3)), &input(), &kelly).unwrap();
+        assert!(size.quantity.is_zero());
+
+        let hold = size_position(&signal(SignalType::Hold, dec!(0.9)), &input(), &fixed).unwrap();
+        assert!(hold.quantity.is_zero());
+    }
+
+    #[test]
+    fn test_caps_and_lots() {
+        let config = SizingConfig::new(SizingMethod::FixedFractional { fraction: dec!(0.5) })
+            .with_caps(dec!(0.25), dec!(1))
+            .with_lot_size(dec!(0.1));
+        let size = size_position(&signal(SignalType::Buy, dec!(0.9)), &input(), &config).unwrap();
+        assert_eq!(size.capped_by, Some(SizeCap::MaxPosition));
+        assert_eq!(size.quantity, dec!(0.5));
+
+        let nearly_full = SizingInput { gross_exposure: dec!(90000), ..input() };
+        let size = size_position(&signal(SignalType::Buy, dec!(0.9)), &nearly_full, &config).unwrap();
+        assert_eq!(size.capped_by, Some(SizeCap::GrossExposure));
+        assert_eq!(size.quantity, dec!(0.2));
+        assert_eq!(size.notional, dec!(10000));
+
+        let no_vol = SizingInput { volatility: Decimal::ZERO, ..input() };
+        let vol = SizingConfig::new(SizingMethod::VolatilityTarget { target_volatility: dec!(0.01) });
+        assert!(size_position(&signal(SignalType::Buy, dec!(0.9)), &no_vol, &vol).is_err());
+        // The ratio would overflow Decimal
+        let tiny_vol = SizingInput { volatility: Decimal::new(1, 28), ..input() };
+        let big_target = SizingConfig::new(SizingMethod::VolatilityTarget { target_volatility: dec!(1000) });
+        assert!(size_position(&signal(SignalType::Buy, dec!(0.9)), &tiny_vol, &big_target).is_err());
+    }
+
+    #[test]
+    fn test_invalid_method_is_rejected() {
+        let kelly = SizingConfig::new(SizingMethod::FractionalKelly { kelly_fraction: dec!(0.5), payoff_ratio: Decimal::ZERO });
+        assert!(size_position(&signal(SignalType::Buy, dec!(0.6)), &input(), &kelly).is_err());
+    }
+
+    #[test]
+    fn test_realized_volatility() {
+        let prices: Vec<PricePoint> = [dec!(100), dec!(110), dec!(99), dec!(108.9)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, price)| PricePoint { timestamp: i as i64, price, volume: None })
+            .collect();
+        // Returns +10%, -10%, +10%: mean 1/30, variance 0.08 / 9
+        let vol = realized_volatility(&prices, 3).unwrap();
+        assert_eq!(vol.round_dp(6), dec!(0.094281));
+        assert_eq!(realized_volatility(&prices, 1), None);
+    }
+}
@@ -0,0 +1,287 @@
+//! Stop-loss, take-profit and trailing stop levels for signals
+//!
+//! Levels are placed in multiples of a risk unit `R`, either the ATR or the
+//! realized volatility of returns times the price:
+//!
+//! - stop-loss at `stop_multiple * R` against the position
+//! - take-profit at `take_profit_multiple * R` in its favour, scaled by the
+//!   signal confidence when `scale_by_confidence` is set, so weaker signals
+//!   aim lower and need a wider risk/reward margin to pass
+//! - an optional trailing stop that activates after `activation_multiple * R`
+//!   and trails by `trail_multiple * R`
+//!
+//! [`StopConfig::apply`] attaches the levels to a BUY or SELL signal and
+//! rejects it when the risk/reward is below `min_risk_reward`.
+
+use super::sizing::realized_volatility;
+use crate::correlation::PricePoint;
+use crate::indicators::{atr, latest};
+use crate::types::{ProtectiveLevels, Signal, SignalType, TrailingStop};
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Price distance the stop and target multiples apply to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "unit", rename_all = "snake_case")]
+pub enum RiskUnit {
+    /// Average true range over `period` points
+    Atr { period: usize },
+    /// Standard deviation of returns over `lookback` returns, times the price
+    Volatility { lookback: usize },
+}
+
+impl RiskUnit {
+    /// The unit in price terms at the last point of `prices`
+    pub fn measure(&self, prices: &[PricePoint]) -> Option<Decimal> {
+        let unit = match *self {
+            RiskUnit::Atr { period } => latest(&atr(prices, period))?,
+            RiskUnit::Volatility { lookback } => realized_volatility(prices, lookback)? * prices.last()?.price,
+        };
+        (unit > Decimal::ZERO).then_some(unit)
+    }
+}
+
+/// Trailing stop distances in risk units
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrailingConfig {
+    pub activation_multiple: Decimal,
+    pub trail_multiple: Decimal,
+}
+
+/// How protective levels are placed and which signals are kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StopConfig {
+    pub unit: RiskUnit,
+    pub stop_multiple: Decimal,
+    pub take_profit_multiple: Decimal,
+    /// Scale the take-profit distance by the signal confidence
+    pub scale_by_confidence: bool,
+    #[serde(default)]
+    pub trailing: Option<TrailingConfig>,
+    pub min_risk_reward: Decimal,
+}
+
+impl Default for StopConfig {
+    fn default() -> Self {
+        Self {
+            unit: RiskUnit::Atr { period: 14 },
+            stop_multiple: dec!(2),
+            take_profit_multiple: dec!(4),
+            scale_by_confidence: true,
+            trailing: None,
+            min_risk_reward: dec!(1.5),
+        }
+    }
+}
+
+impl StopConfig {
+    pub fn new(unit: RiskUnit) -> Self {
+        Self {
+            unit,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the stop-loss and take-profit multiples
+    pub fn with_multiples(mut self, stop_multiple: Decimal, take_profit_multiple: Decimal) -> Self {
+        self.stop_multiple = stop_multiple;
+        self.take_profit_multiple = take_profit_multiple;
+        self
+    }
+
+    pub fn with_trailing(mut self, activation_multiple: Decimal, trail_multiple: Decimal) -> Self {
+        self.trailing = Some(TrailingConfig {
+            activation_multiple,
+            trail_multiple,
+        });
+        self
+    }
+
+    pub fn with_min_risk_reward(mut self, min_risk_reward: Decimal) -> Self {
+        self.min_risk_reward = min_risk_reward;
+        self
+    }
+
+    pub fn with_scale_by_confidence(mut self, scale: bool) -> Self {
+        self.scale_by_confidence = scale;
+        self
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.stop_multiple <= Decimal::ZERO || self.take_profit_multiple <= Decimal::ZERO {
+            bail!("stop and take-profit multiples must be positive");
+        }
+        if let Some(trailing) = &self.trailing {
+            if trailing.activation_multiple < Decimal::ZERO || trailing.trail_multiple <= Decimal::ZERO {
+                bail!("trailing activation must not be negative and trail distance must be positive");
+            }
+        }
+        if self.min_risk_reward < Decimal::ZERO {
+            bail!("min_risk_reward must not be negative, got {}", self.min_risk_reward);
+        }
+        match self.unit {
+            RiskUnit::Atr { period: 0 } | RiskUnit::Volatility { lookback: 0 } => bail!("risk unit window must be positive"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Levels for `signal` entered at the last price of `prices`. `None` for
+    /// HOLD signals, an invalid config or when the risk unit cannot be measured.
+    pub fn levels(&self, signal: &Signal, prices: &[PricePoint]) -> Option<ProtectiveLevels> {
+        if let Err(e) = self.validate() {
+            log::warn!("no protective levels for {}: {}", signal.symbol, e);
+            return None;
+        }
+        let side = match signal.action {
+            SignalType::Buy => Decimal::ONE,
+            SignalType::Sell => -Decimal::ONE,
+            SignalType::Hold => return None,
+        };
+        let entry = prices.last()?.price;
+        let unit = self.unit.measure(prices)?;
+
+        let risk = self.stop_multiple * unit;
+        let mut reward = self.take_profit_multiple * unit;
+        if self.scale_by_confidence {
+            reward *= signal.confidence;
+        }
+        let trailing = self.trailing.map(|t| TrailingStop {
+            activation_price: entry + side * t.activation_multiple * unit,
+            trail_distance: t.trail_multiple * unit,
+        });
+
+        Some(ProtectiveLevels {
+            entry,
+            stop_loss: entry - side * risk,
+            take_profit: entry + side * reward,
+            trailing,
+            risk_reward: (reward / risk).round_dp(4),
+        })
+    }
+
+    /// Attaches levels to a BUY or SELL signal. HOLD signals pass through
+    /// unchanged; actionable signals without levels (including under an
+    /// invalid config), or with a risk/reward below the minimum, are rejected.
+    pub fn apply(&self, signal: Signal, prices: &[PricePoint]) -> Option<Signal> {
+        if signal.action == SignalType::Hold {
+            return Some(signal);
+        }
+        let Some(levels) = self.levels(&signal, prices) else {
+            log::debug!("{} {} rejected: no protective levels", signal.symbol, signal.action.as_str());
+            return None;
+        };
+        if levels.risk_reward < self.min_risk_reward {
+            log::debug!(
+                "{} {} rejected: risk/reward {} below {}",
+                signal.symbol,
+                signal.action.as_str(),
+                levels.risk_reward,
+                self.min_risk_reward
+            );
+            return None;
+        }
+        Some(signal.with_levels(levels))
+    }
+}
+
+/// Stop level after the price reached `best_price` (highest since entry for a
+/// BUY, lowest for a SELL). The stop only moves in the position's favour.
+pub fn trail_stop(action: SignalType, levels: &ProtectiveLevels, best_price: Decimal) -> Decimal {
+    let Some(trailing) = levels.trailing else {
+        return levels.stop_loss;
+    };
+    match action {
+        SignalType::Buy if best_price >= trailing.activation_price => {
+            levels.stop_loss.max(best_price - trailing.trail_distance)
+        }
+        SignalType::Sell if best_price <= trailing.activation_price => {
+            levels.stop_loss.min(best_price + trailing.trail_distance)
+        }
+        _ => levels.stop_loss,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SentimentScore;
+
+    fn signal(action: SignalType, confidence: Decimal) -> Signal {
+        let sentiment = SentimentScore {
+            positive: dec!(0.8),
+            negative: dec!(0.1),
+            neutral: dec!(0.1),
+        };
+        Signal::new("BTC", action, sentiment, confidence, 0)
+    }
+
+    /// Alternating moves of 2, so the ATR is exactly 2
+    fn prices() -> Vec<PricePoint> {
+        (0..20)
+            .map(|i| PricePoint {
+                timestamp: i * 3600,
+                price: if i % 2 == 0 { dec!(100) } else { dec!(102) },
+                volume: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_levels() {
+        let config = StopConfig::new(RiskUnit::Atr { period: 5 })
+            .with_trailing(dec!(1), dec!(1.5))
+            .with_scale_by_confidence(false);
+
+        let buy = config.levels(&signal(SignalType::Buy, dec!(0.8)), &prices()).unwrap();
+        assert_eq!(buy.entry, dec!(102));
+        assert_eq!(buy.stop_loss, dec!(98));
+        assert_eq!(buy.take_profit, dec!(110));
+        assert_eq!(buy.risk_reward, dec!(2));
+        assert_eq!(buy.trailing.unwrap().activation_price, dec!(104));
+
+        let sell = config.levels(&signal(SignalType::Sell, dec!(0.8)), &prices()).unwrap();
+        assert_eq!(sell.stop_loss, dec!(106));
+        assert_eq!(sell.take_profit, dec!(94));
+
+        assert_eq!(config.levels(&signal(SignalType::Hold, dec!(0.8)), &prices()), None);
+        assert_eq!(config.levels(&signal(SignalType::Buy, dec!(0.8)), &prices()[..3]), None);
+
+        // Trailing stop ratchets up after activation and never moves back
+        assert_eq!(trail_stop(SignalType::Buy, &buy, dec!(103)), dec!(98));
+        assert_eq!(trail_stop(SignalType::Buy, &buy, dec!(108)), dec!(105));
+        assert_eq!(trail_stop(SignalType::Sell, &sell, dec!(96)), dec!(99));
+    }
+
+    #[test]
+    fn test_apply_rejects_low_risk_reward() {
+        // 4R target scaled by confidence against a 2R stop: 0.8 -> 1.6, 0.6 -> 1.2
+        let config = StopConfig::new(RiskUnit::Atr { period: 5 });
+        let kept = config.apply(signal(SignalType::Buy, dec!(0.8)), &prices()).unwrap();
+        assert_eq!(kept.levels.unwrap().risk_reward, dec!(1.6));
+        assert_eq!(kept.id, signal(SignalType::Buy, dec!(0.8)).id);
+
+        assert!(config.apply(signal(SignalType::Buy, dec!(0.6)), &prices()).is_none());
+        assert!(config.apply(signal(SignalType::Sell, dec!(0.9)), &prices()[..3]).is_none());
+
+        // A zero stop multiple would divide by zero; it rejects instead
+        let zero_stop = StopConfig { stop_multiple: Decimal::ZERO, ..config.clone() };
+        assert!(zero_stop.levels(&signal(SignalType::Buy, dec!(0.8)), &prices()).is_none());
+        assert!(zero_stop.apply(signal(SignalType::Buy, dec!(0.8)), &prices()).is_none());
+
+        let hold = config.apply(signal(SignalType::Hold, dec!(0.5)), &prices()).unwrap();
+        assert_eq!(hold.levels, None);
+
+        let json = serde_json::to_string(&kept).unwrap();
+        assert_eq!(serde_json::from_str::<Signal>(&json).unwrap(), kept);
+        assert!(!serde_json::to_string(&hold).unwrap().contains("levels"));
+    }
+
+    #[test]
+    fn test_volatility_unit() {
+        let unit = RiskUnit::Volatility { lookback: 10 }.measure(&prices()).unwrap();
+        assert!(unit > dec!(1.9) && unit < dec!(2.1));
+        assert!(StopConfig::new(RiskUnit::Atr { period: 0 }).validate().is_err());
+    }
+}
@@ -0,0 +1,698 @@
+//! Sitemap and paginated archive crawler for news sites
+//!
+//! Backfills history from sites that have no feed. Article URLs come from
+//! `sitemap.xml` files (following sitemap indexes) and/or numbered archive
+//! pages; each article page is then reduced to an `Article` using the CSS
+//! selectors in the site's `SiteConfig`. Requests go through the shared
+//! `RateLimiter` and a `PoliteFetcher`, so robots.txt and crawl delays apply.
+
+use super::html::html_to_text;
+use super::robots::{CrawlerConfig, PoliteFetcher};
+use super::{ArticleSource, RateLimiter};
+use crate::types::Article;
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use log::{info, warn};
+use reqwest::Url;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Maximum nesting of sitemap indexes that will be followed
+const MAX_SITEMAP_DEPTH: usize = 3;
+
+/// Numbered archive pages listing article links
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveConfig {
+    /// Page URL with a `{page}` placeholder, absolute or relative to the site
+    pub url_template: String,
+    /// Selector for the article links on an archive page
+    pub link_selector: String,
+    #[serde(default = "default_first_page")]
+    pub first_page: u32,
+    #[serde(default = "default_max_pages")]
+    pub max_pages: u32,
+}
+
+fn default_first_page() -> u32 {
+    1
+}
+
+fn default_max_pages() -> u32 {
+    50
+}
+
+/// Where to find articles on a site and how to extract them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SiteConfig {
+    /// Used as the article source
+    pub name: String,
+    pub base_url: String,
+    pub title_selector: String,
+    /// All matches are concatenated, one paragraph each
+    pub body_selector: String,
+    #[serde(default)]
+    pub date_selector: Option<String>,
+    /// Attribute holding the date (e.g. `datetime`); falls back to the element text
+    #[serde(default)]
+    pub date_attribute: Option<String>,
+    /// chrono format tried before the built-in formats
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// Sitemap URLs. When empty and no archive is configured, sitemaps are
+    /// discovered from robots.txt, falling back to `/sitemap.xml`.
+    #[serde(default)]
+    pub sitemaps: Vec<String>,
+    #[serde(default)]
+    pub archive: Option<ArchiveConfig>,
+    /// Substring article URLs must contain, e.g. `/news/`
+    #[serde(default)]
+    pub url_pattern: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl SiteConfig {
+    /// Creates a config with the required selectors and no date selector
+    pub fn new(name: &str, base_url: &str, title_selector: &str, body_selector: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            base_url: base_url.to_string(),
+            title_selector: title_selector.to_string(),
+            body_selector: body_selector.to_string(),
+            date_selector: None,
+            date_attribute: None,
+            date_format: None,
+            sitemaps: Vec::new(),
+            archive: None,
+            url_pattern: None,
+            language: None,
+        }
+    }
+
+    /// Reads the publication date from `selector`, preferring `attribute` when present
+    pub fn with_date_selector(mut self, selector: &str, attribute: Option<&str>) -> Self {
+        self.date_selector = Some(selector.to_string());
+        self.date_attribute = attribute.map(String::from);
+        self
+    }
+
+    pub fn with_sitemap(mut self, url: &str) -> Self {
+        self.sitemaps.push(url.to_string());
+        self
+    }
+
+    pub fn with_archive(mut self, archive: ArchiveConfig) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    pub fn with_url_pattern(mut self, pattern: &str) -> Self {
+        self.url_pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// Parses a JSON array of site configs and checks their selectors
+    pub fn list_from_json(json: &str) -> Result<Vec<SiteConfig>> {
+        let sites: Vec<SiteConfig> = serde_json::from_str(json).context("invalid site config JSON")?;
+        for site in &sites {
+            site.validate()?;
+        }
+        Ok(sites)
+    }
+
+    /// Checks that the base URL and every selector parse
+    pub fn validate(&self) -> Result<()> {
+        Url::parse(&self.base_url).with_context(|| format!("{}: invalid base URL", self.name))?;
+        selector(&self.title_selector)?;
+        selector(&self.body_selector)?;
+        if let Some(date) = &self.date_selector {
+            selector(date)?;
+        }
+        if let Some(archive) = &self.archive {
+            selector(&archive.link_selector)?;
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, url: &str) -> Result<Url> {
+        let base = Url::parse(&self.base_url).with_context(|| format!("{}: invalid base URL", self.name))?;
+        base.join(url).with_context(|| format!("invalid URL: {}", url))
+    }
+
+    fn wants(&self, url: &Url) -> bool {
+        let same_site = Url::parse(&self.base_url).is_ok_and(|base| base.origin() == url.origin());
+        same_site
+            && self
+                .url_pattern
+                .as_deref()
+                .is_none_or(|pattern| url.as_str().contains(pattern))
+    }
+
+    /// Extracts an article from a page. `fallback_timestamp` (e.g. a sitemap
+    /// `lastmod`) is used when the page has no readable date.
+    pub fn extract(&self, url: &str, html: &str, fallback_timestamp: Option<i64>) -> Result<Article> {
+        let document = Html::parse_document(html);
+
+        let title = document
+            .select(&selector(&self.title_selector)?)
+            .map(|e| collapse_whitespace(&e.text().collect::<String>()))
+            .find(|t| !t.is_empty())
+            .with_context(|| format!("no title matching `{}` in {}", self.title_selector, url))?;
+
+        let body = document
+            .select(&selector(&self.body_selector)?)
+            .map(|e| html_to_text(&e.inner_html()))
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if body.is_empty() {
+            bail!("no body matching `{}` in {}", self.body_selector, url);
+        }
+
+        let page_date = match &self.date_selector {
+            Some(date_selector) => document.select(&selector(date_selector)?).find_map(|e| {
+                let value = self
+                    .date_attribute
+                    .as_deref()
+                    .and_then(|attr| e.value().attr(attr))
+                    .map(String::from)
+                    .unwrap_or_else(|| collapse_whitespace(&e.text().collect::<String>()));
+                parse_date(&value, self.date_format.as_deref())
+            }),
+            None => None,
+        };
+        let timestamp = page_date
+            .or(fallback_timestamp)
+            .with_context(|| format!("no publication date in {}", url))?;
+
+        let mut article = Article::new(&title, &body, &self.name, timestamp)
+            .with_url(url)
+            .with_detected_symbols();
+        if let Some(language) = &self.language {
+            article = article.with_language(language);
+        }
+        Ok(article)
+    }
+}
+
+fn selector(css: &str) -> Result<Selector> {
+    Selector::parse(css).map_err(|e| anyhow!("invalid selector `{}`: {}", css, e))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Parses the date formats commonly found in sitemaps and article pages.
+/// Dates without a time zone are taken as UTC.
+pub fn parse_date(value: &str, format: Option<&str>) -> Option<i64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    let with_zone = ["%Y-%m-%dT%H:%M%:z", "%Y-%m-%d %H:%M:%S %z"];
+    let naive = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"];
+    let dates = ["%Y-%m-%d", "%B %d, %Y", "%b %d, %Y", "%d %B %Y", "%d %b %Y", "%Y/%m/%d"];
+
+    let parse = |fmt: &str| {
+        DateTime::parse_from_str(value, fmt)
+            .map(|d| d.timestamp())
+            .or_else(|_| NaiveDateTime::parse_from_str(value, fmt).map(|d| d.and_utc().timestamp()))
+            .or_else(|_| {
+                NaiveDate::parse_from_str(value, fmt)
+                    .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp())
+            })
+            .ok()
+    };
+
+    format
+        .and_then(parse)
+        .or_else(|| DateTime::parse_from_rfc3339(value).ok().map(|d| d.timestamp()))
+        .or_else(|| DateTime::parse_from_rfc2822(value).ok().map(|d| d.timestamp()))
+        .or_else(|| with_zone.iter().chain(&naive).chain(&dates).find_map(|fmt| parse(fmt)))
+}
+
+/// A `<url>` or `<sitemap>` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    pub loc: String,
+    /// `news:publication_date` if present, otherwise `lastmod`
+    pub lastmod: Option<i64>,
+}
+
+/// A parsed sitemap document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sitemap {
+    /// A `<sitemapindex>` pointing at further sitemaps
+    Index(Vec<SitemapEntry>),
+    /// A `<urlset>` listing pages
+    UrlSet(Vec<SitemapEntry>),
+}
+
+/// Parses a sitemap or sitemap index
+pub fn parse_sitemap(xml: &str) -> Result<Sitemap> {
+    let (tag, is_index) = if xml.contains("<sitemapindex") {
+        ("sitemap", true)
+    } else if xml.contains("<urlset") {
+        ("url", false)
+    } else {
+        bail!("not a sitemap: no <urlset> or <sitemapindex> element");
+    };
+
+    let entries = elements(xml, tag)
+        .into_iter()
+        .filter_map(|block| {
+            let loc = element_text(block, "loc")?;
+            let lastmod = element_text(block, "news:publication_date")
+                .or_else(|| element_text(block, "lastmod"))
+                .and_then(|d| parse_date(&d, None));
+            Some(SitemapEntry { loc, lastmod })
+        })
+        .collect();
+
+    Ok(if is_index {
+        Sitemap::Index(entries)
+    } else {
+        Sitemap::UrlSet(entries)
+    })
+}
+
+/// Inner contents of every `<tag>...</tag>` element (not matching longer tag names)
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let is_tag = after.starts_with('>') || after.starts_with(|c: char| c.is_whitespace());
+        if !is_tag {
+            rest = after;
+            continue;
+        }
+        let Some(content_start) = after.find('>') else {
+            break;
+        };
+        let content = &after[content_start + 1..];
+        let Some(end) = content.find(&close) else {
+            break;
+        };
+        found.push(&content[..end]);
+        rest = &content[end + close.len()..];
+    }
+    found
+}
+
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let raw = elements(xml, tag).into_iter().next()?.trim();
+    let text = match raw.strip_prefix("<![CDATA[").and_then(|r| r.strip_suffix("]]>")) {
+        Some(cdata) => cdata.to_string(),
+        None => raw
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    };
+    let text = text.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Crawls one site through its sitemaps and archive pages
+pub struct SiteCrawler {
+    site: SiteConfig,
+    fetcher: PoliteFetcher,
+    limiter: RateLimiter,
+    start: Option<i64>,
+    end: Option<i64>,
+    max_articles: Option<usize>,
+}
+
+impl SiteCrawler {
+    /// Creates a crawler limited to one request per second
+    pub fn new(site: SiteConfig, config: CrawlerConfig) -> Result<Self> {
+        site.validate()?;
+        Ok(Self {
+            site,
+            fetcher: PoliteFetcher::new(config)?,
+            limiter: RateLimiter::new(1),
+            start: None,
+            end: None,
+            max_articles: None,
+        })
+    }
+
+    /// Shares a rate limiter, e.g. across all crawlers hitting the same site
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Keeps only articles published in `[start, end)`; either bound may be open
+    pub fn with_date_range(mut self, start: Option<i64>, end: Option<i64>) -> Self {
+        self.start = start;
+        self.end = end;
+        self
+    }
+
+    /// Stops after this many articles
+    pub fn with_max_articles(mut self, max: usize) -> Self {
+        self.max_articles = Some(max);
+        self
+    }
+
+    pub fn site(&self) -> &SiteConfig {
+        &self.site
+    }
+
+    fn in_range(&self, timestamp: i64) -> bool {
+        self.start.is_none_or(|s| timestamp >= s) && self.end.is_none_or(|e| timestamp < e)
+    }
+
+    async fn get(&self, url: &str) -> Result<String> {
+        self.limiter.acquire().await;
+        self.fetcher.get_text(url).await
+    }
+
+    async fn sitemap_roots(&self) -> Result<Vec<Url>> {
+        if !self.site.sitemaps.is_empty() {
+            return self.site.sitemaps.iter().map(|s| self.site.resolve(s)).collect();
+        }
+        let base = self.site.resolve("/")?;
+        let robots = self.fetcher.robots().rules_for(&base).await?;
+        if robots.sitemaps().is_empty() {
+            return Ok(vec![self.site.resolve("/sitemap.xml")?]);
+        }
+        robots.sitemaps().iter().map(|s| self.site.resolve(s)).collect()
+    }
+
+    /// Walks the sitemaps and returns article URLs within the date range.
+    /// Index entries last modified before the range start are not fetched;
+    /// entries without a date are kept and filtered after extraction.
+    pub async fn sitemap_entries(&self) -> Result<Vec<SitemapEntry>> {
+        let mut pending: Vec<(Url, usize)> = self.sitemap_roots().await?.into_iter().map(|u| (u, 0)).collect();
+        pending.reverse();
+        let mut visited = HashSet::new();
+        let mut entries = Vec::new();
+
+        while let Some((url, depth)) = pending.pop() {
+            if !visited.insert(url.to_string()) {
+                continue;
+            }
+            let xml = match self.get(url.as_str()).await {
+                Ok(xml) => xml,
+                Err(e) if depth > 0 => {
+                    warn!("Skipping sitemap {}: {:#}", url, e);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            match parse_sitemap(&xml).with_context(|| format!("invalid sitemap {}", url))? {
+                Sitemap::Index(children) => {
+                    if depth >= MAX_SITEMAP_DEPTH {
+                        warn!("Sitemap index {} nested too deeply", url);
+                        continue;
+                    }
+                    for child in children.into_iter().rev() {
+                        let stale = matches!((child.lastmod, self.start), (Some(m), Some(s)) if m < s);
+                        if let (false, Ok(child_url)) = (stale, url.join(&child.loc)) {
+                            pending.push((child_url, depth + 1));
+                        }
+                    }
+                }
+                Sitemap::UrlSet(pages) => {
+                    for page in pages {
+                        let Ok(page_url) = url.join(&page.loc) else {
+                            continue;
+                        };
+                        if self.site.wants(&page_url) && page.lastmod.is_none_or(|m| self.in_range(m)) {
+                            entries.push(SitemapEntry {
+                                loc: page_url.to_string(),
+                                lastmod: page.lastmod,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Follows the archive pages and returns the article links found, stopping
+    /// at the first page that is missing or adds no new links
+    pub async fn archive_links(&self) -> Result<Vec<String>> {
+        let Some(archive) = &self.site.archive else {
+            return Ok(Vec::new());
+        };
+        let link_selector = selector(&archive.link_selector)?;
+        let mut seen = HashSet::new();
+        let mut links = Vec::new();
+
+        for page in archive.first_page..archive.first_page.saturating_add(archive.max_pages) {
+            let page_url = self.site.resolve(&archive.url_template.replace("{page}", &page.to_string()))?;
+            let html = match self.get(page_url.as_str()).await {
+                Ok(html) => html,
+                Err(e) if page > archive.first_page => {
+                    info!("Archive of {} ends at page {}: {:#}", self.site.name, page, e);
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let document = Html::parse_document(&html);
+            let before = links.len();
+            for href in document.select(&link_selector).filter_map(|a| a.value().attr("href")) {
+                let Ok(url) = page_url.join(href) else {
+                    continue;
+                };
+                if self.site.wants(&url) && seen.insert(url.to_string()) {
+                    links.push(url.to_string());
+                }
+            }
+            if links.len() == before {
+                break;
+            }
+        }
+
+        Ok(links)
+    }
+
+    /// Collects article URLs from sitemaps and archive pages, fetches each
+    /// page and returns the articles in the date range, oldest first. Pages
+    /// that fail to fetch or extract are logged and skipped.
+    pub async fn crawl(&self) -> Result<Vec<Article>> {
+        let mut candidates: Vec<(String, Option<i64>)> = Vec::new();
+        if !self.site.sitemaps.is_empty() || self.site.archive.is_none() {
+            candidates.extend(self.sitemap_entries().await?.into_iter().map(|e| (e.loc, e.lastmod)));
+        }
+        candidates.extend(self.archive_links().await?.into_iter().map(|url| (url, None)));
+
+        let mut seen = HashSet::new();
+        let mut articles = Vec::new();
+        for (url, lastmod) in candidates {
+            if self.max_articles.is_some_and(|max| articles.len() >= max) {
+                break;
+            }
+            if !seen.insert(url.clone()) {
+                continue;
+            }
+            let article = match self.get(&url).await {
+                Ok(html) => self.site.extract(&url, &html, lastmod),
+                Err(e) => Err(e),
+            };
+            match article {
+                Ok(article) if self.in_range(article.timestamp) => articles.push(article),
+                Ok(_) => {}
+                Err(e) => warn!("Skipping {}: {:#}", url, e),
+            }
+        }
+
+        articles.sort_by_key(|a| a.timestamp);
+        Ok(articles)
+    }
+}
+
+#[async_trait]
+impl ArticleSource for SiteCrawler {
+    fn name(&self) -> &str {
+        &self.site.name
+    }
+
+    async fn fetch(&mut self) -> Result<Vec<Article>> {
+        self.crawl().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrapers::test_support::{Route, TestServer};
+    use std::time::Duration;
+
+    const ROBOTS: &str = include_str!("../../tests/fixtures/sitemap/robots.txt");
+    const INDEX: &str = include_str!("../../tests/fixtures/sitemap/sitemap_index.xml");
+    const URLSET: &str = include_str!("../../tests/fixtures/sitemap/sitemap_2023_10.xml");
+    const BITCOIN: &str = include_str!("../../tests/fixtures/sitemap/article_bitcoin.html");
+    const ETHEREUM: &str = include_str!("../../tests/fixtures/sitemap/article_ethereum.html");
+    const OLD: &str = include_str!("../../tests/fixtures/sitemap/article_old.html");
+    const ARCHIVE_1: &str = include_str!("../../tests/fixtures/sitemap/archive_page1.html");
+    const ARCHIVE_2: &str = include_str!("../../tests/fixtures/sitemap/archive_page2.html");
+
+    const OCT_1: i64 = 1696118400;
+    const NOV_1: i64 = 1698796800;
+
+    fn site(base_url: &str) -> SiteConfig {
+        SiteConfig::new("ExampleWire", base_url, "h1.headline", ".story-body")
+            .with_date_selector("time.published, span.date", Some("datetime"))
+            .with_url_pattern("/news/")
+    }
+
+    fn config() -> CrawlerConfig {
+        CrawlerConfig {
+            min_delay: Duration::ZERO,
+            ..CrawlerConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_sitemap() {
+        let Sitemap::Index(children) = parse_sitemap(INDEX).unwrap() else {
+            panic!("expected a sitemap index");
+        };
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].loc, "/sitemaps/2023-10.xml");
+        assert_eq!(children[0].lastmod, Some(1698793200));
+
+        let Sitemap::UrlSet(pages) = parse_sitemap(URLSET).unwrap() else {
+            panic!("expected a urlset");
+        };
+        assert_eq!(pages.len(), 5);
+        assert_eq!(pages[0].loc, "/news/bitcoin-etf-hopes?ref=sitemap&v=1");
+        assert_eq!(pages[0].lastmod, Some(1697466600));
+        assert_eq!(pages[1].lastmod, Some(1697760000));
+        assert_eq!(pages[4].lastmod, None);
+
+        assert!(parse_sitemap("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_date_formats() {
+        assert_eq!(parse_date("2023-10-16T14:30:00Z", None), Some(1697466600));
+        assert_eq!(parse_date("2023-10-16T16:30+02:00", None), Some(1697466600));
+        assert_eq!(parse_date("Mon, 16 Oct 2023 14:30:00 +0000", None), Some(1697466600));
+        assert_eq!(parse_date("2023-10-16 14:30:00", None), Some(1697466600));
+        assert_eq!(parse_date("October 16, 2023", None), Some(1697414400));
+        assert_eq!(parse_date("16 Oct 2023", None), Some(1697414400));
+        assert_eq!(parse_date("16.10.2023", Some("%d.%m.%Y")), Some(1697414400));
+        assert_eq!(parse_date("yesterday", None), None);
+    }
+
+    #[test]
+    fn test_extract_article() {
+        let site = site("https://wire.example.com");
+        let article = site
+            .extract("https://wire.example.com/news/bitcoin-etf-hopes", BITCOIN, None)
+            .unwrap();
+        assert_eq!(article.title, "Bitcoin jumps on ETF hopes");
+        assert!(article.content.starts_with("Bitcoin rallied"));
+        assert!(article.content.contains("BTC gained 7%"));
+        assert!(!article.content.contains("Subscribe"));
+        assert_eq!(article.timestamp, 1697466600);
+        assert_eq!(article.source, "ExampleWire");
+        assert!(article.symbols.contains(&"BTC".to_string()));
+
+        // Text date when the attribute is missing
+        let article = site.extract("https://wire.example.com/news/eth", ETHEREUM, None).unwrap();
+        assert_eq!(article.timestamp, 1697760000);
+
+        let no_date = SiteConfig::new("x", "https://wire.example.com", "h1", "p");
+        assert!(no_date.extract("u", ETHEREUM, None).is_err());
+        assert_eq!(no_date.extract("u", ETHEREUM, Some(42)).unwrap().timestamp, 42);
+        assert!(site.extract("u", "<html><body></body></html>", Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_site_configs_from_json() {
+        let json = r#"[{
+            "name": "ExampleWire",
+            "base_url": "https://wire.example.com",
+            "title_selector": "h1.headline",
+            "body_selector": ".story-body p",
+            "date_selector": "time",
+            "date_attribute": "datetime",
+            "archive": {"url_template": "/archive?page={page}", "link_selector": "a.story-link"}
+        }]"#;
+        let sites = SiteConfig::list_from_json(json).unwrap();
+        assert_eq!(sites[0].archive.as_ref().unwrap().max_pages, 50);
+        assert!(sites[0].sitemaps.is_empty());
+
+        let bad = json.replace("h1.headline", "h1[");
+        assert!(SiteConfig::list_from_json(&bad).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_crawl_sitemaps_from_robots() {
+        let server = TestServer::start(vec![
+            ("/robots.txt", Route::ok("text/plain", ROBOTS)),
+            ("/sitemap_index.xml", Route::ok("application/xml", INDEX)),
+            ("/sitemaps/2023-10.xml", Route::ok("application/xml", URLSET)),
+            ("/news/bitcoin-etf-hopes?ref=sitemap&v=1", Route::ok("text/html", BITCOIN)),
+            ("/news/ethereum-fees-fall", Route::ok("text/html", ETHEREUM)),
+            ("/news/old-recap", Route::ok("text/html", OLD)),
+        ])
+        .await;
+
+        let crawler = SiteCrawler::new(site(&server.base_url), config())
+            .unwrap()
+            .with_rate_limiter(RateLimiter::new(0))
+            .with_date_range(Some(OCT_1), Some(NOV_1));
+        let articles = crawler.crawl().await.unwrap();
+
+        let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, ["Bitcoin jumps on ETF hopes", "Ethereum fees fall to yearly low"]);
+        assert_eq!(articles[0].url.as_deref(), Some(server.url("/news/bitcoin-etf-hopes?ref=sitemap&v=1").as_str()));
+
+        // The stale index entry, the out-of-range page and the disallowed page are never fetched
+        assert_eq!(server.hits("/sitemaps/2022-01.xml"), 0);
+        assert_eq!(server.hits("/news/old-recap"), 0);
+        assert_eq!(server.hits("/private/draft"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_archive_pages() {
+        let server = TestServer::start(vec![
+            ("/archive?page=1", Route::ok("text/html", ARCHIVE_1)),
+            ("/archive?page=2", Route::ok("text/html", ARCHIVE_2)),
+            ("/news/bitcoin-etf-hopes", Route::ok("text/html", BITCOIN)),
+            ("/news/ethereum-fees-fall", Route::ok("text/html", ETHEREUM)),
+            ("/news/old-recap", Route::ok("text/html", OLD)),
+        ])
+        .await;
+
+        let site = site(&server.base_url).with_archive(ArchiveConfig {
+            url_template: "/archive?page={page}".to_string(),
+            link_selector: "a.story-link".to_string(),
+            first_page: 1,
+            max_pages: 10,
+        });
+        let crawler = SiteCrawler::new(site, config())
+            .unwrap()
+            .with_rate_limiter(RateLimiter::new(0))
+            .with_date_range(Some(OCT_1), None);
+
+        let links = crawler.archive_links().await.unwrap();
+        assert_eq!(links.len(), 3);
+        assert!(links.iter().all(|l| l.starts_with(&server.base_url)));
+        assert_eq!(server.hits("/archive?page=3"), 1);
+
+        let articles = crawler.crawl().await.unwrap();
+        assert_eq!(articles.len(), 2);
+        assert!(articles.iter().all(|a| a.timestamp >= OCT_1));
+        // No sitemap lookups when only an archive is configured
+        assert_eq!(server.hits("/sitemap.xml"), 0);
+    }
+}
@@ -0,0 +1,720 @@
+//! SEC EDGAR filing source
+//!
+//! Parses EDGAR complete submission text files (`<accession>.txt`) and the
+//! submissions JSON index (`data.sec.gov/submissions/CIK##########.json`) into
+//! articles. Filings are split into their item sections (e.g. 8-K Item 2.02,
+//! 10-Q Part II Item 1A Risk Factors) so each section can be scored on its own,
+//! and `EX-99` exhibits such as earnings press releases become sections too.
+
+use super::html::html_to_text;
+use super::robots::{CrawlerConfig, PoliteFetcher};
+use crate::types::Article;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Public EDGAR host used for links back to filings
+const SEC_URL: &str = "https://www.sec.gov";
+
+/// Builds the URL of a complete submission text file
+fn filing_url(base_url: &str, cik: u64, accession_number: &str) -> String {
+    format!(
+        "{}/Archives/edgar/data/{}/{}/{}.txt",
+        base_url,
+        cik,
+        accession_number.replace('-', ""),
+        accession_number
+    )
+}
+
+/// Returns the official title of a Form 8-K item code
+pub fn describe_8k_item(item: &str) -> Option<&'static str> {
+    let title = match item {
+        "1.01" => "Entry into a Material Definitive Agreement",
+        "1.02" => "Termination of a Material Definitive Agreement",
+        "1.03" => "Bankruptcy or Receivership",
+        "1.05" => "Material Cybersecurity Incidents",
+        "2.01" => "Completion of Acquisition or Disposition of Assets",
+        "2.02" => "Results of Operations and Financial Condition",
+        "2.03" => "Creation of a Direct Financial Obligation",
+        "2.05" => "Costs Associated with Exit or Disposal Activities",
+        "2.06" => "Material Impairments",
+        "3.01" => "Notice of Delisting or Failure to Satisfy a Continued Listing Rule",
+        "4.02" => "Non-Reliance on Previously Issued Financial Statements",
+        "5.02" => "Departure or Appointment of Directors or Officers",
+        "5.07" => "Submission of Matters to a Vote of Security Holders",
+        "7.01" => "Regulation FD Disclosure",
+        "8.01" => "Other Events",
+        "9.01" => "Financial Statements and Exhibits",
+        _ => return None,
+    };
+    Some(title)
+}
+
+/// Maps SEC central index keys to exchange tickers
+#[derive(Debug, Clone, Default)]
+pub struct CikTickerMap {
+    by_cik: HashMap<u64, String>,
+    by_ticker: HashMap<String, u64>,
+}
+
+#[derive(Deserialize)]
+struct CompanyTicker {
+    cik_str: u64,
+    ticker: String,
+}
+
+impl CikTickerMap {
+    /// Parses the SEC `company_tickers.json` file. The first ticker listed for a CIK wins.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let entries: HashMap<String, CompanyTicker> =
+            serde_json::from_str(json).context("invalid company_tickers.json")?;
+        let mut entries: Vec<(usize, CompanyTicker)> = entries
+            .into_iter()
+            .map(|(index, entry)| (index.parse().unwrap_or(usize::MAX), entry))
+            .collect();
+        entries.sort_by_key(|(index, _)| *index);
+
+        let mut map = Self::default();
+        for (_, entry) in entries {
+            map.insert(entry.cik_str, &entry.ticker);
+        }
+        Ok(map)
+    }
+
+    /// Adds a mapping, keeping any ticker already registered for the CIK
+    pub fn insert(&mut self, cik: u64, ticker: &str) {
+        let ticker = ticker.to_uppercase();
+        self.by_ticker.entry(ticker.clone()).or_insert(cik);
+        self.by_cik.entry(cik).or_insert(ticker);
+    }
+
+    /// Returns the ticker registered for a CIK
+    pub fn ticker(&self, cik: u64) -> Option<&str> {
+        self.by_cik.get(&cik).map(|t| t.as_str())
+    }
+
+    /// Returns the CIK registered for a ticker
+    pub fn cik(&self, ticker: &str) -> Option<u64> {
+        self.by_ticker.get(&ticker.to_uppercase()).copied()
+    }
+}
+
+/// Header fields of an EDGAR complete submission file
+#[derive(Debug, Clone)]
+pub struct FilingHeader {
+    pub accession_number: String,
+    pub form_type: String,
+    pub company_name: String,
+    pub cik: u64,
+    pub filed_date: NaiveDate,
+    pub period_of_report: Option<NaiveDate>,
+}
+
+/// One scoreable section of a filing
+#[derive(Debug, Clone)]
+pub struct FilingSection {
+    /// Item code ("2.02", "1A") or exhibit type ("EX-99.1")
+    pub item: String,
+    /// Part of a 10-Q/10-K the item belongs to ("I", "II")
+    pub part: Option<String>,
+    pub title: String,
+    pub text: String,
+}
+
+/// A parsed EDGAR filing split into sections
+#[derive(Debug, Clone)]
+pub struct Filing {
+    pub header: FilingHeader,
+    pub ticker: Option<String>,
+    pub sections: Vec<FilingSection>,
+}
+
+fn header_value<'a>(header: &'a str, key: &str) -> Option<&'a str> {
+    header.lines().find_map(|line| {
+        let (k, v) = line.trim().split_once(':')?;
+        (k.trim() == key).then(|| v.trim())
+    })
+}
+
+fn parse_compact_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y%m%d").ok()
+}
+
+/// Splits a complete submission file into (type, text) documents
+fn documents(full_text: &str) -> Vec<(String, String)> {
+    let mut docs = Vec::new();
+    for chunk in full_text.split("<DOCUMENT>").skip(1) {
+        let chunk = chunk.split("</DOCUMENT>").next().unwrap_or_default();
+        let doc_type = chunk
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("<TYPE>"))
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let text = match (chunk.find("<TEXT>"), chunk.rfind("</TEXT>")) {
+            (Some(start), Some(end)) if end > start => &chunk[start + "<TEXT>".len()..end],
+            _ => continue,
+        };
+        docs.push((doc_type, text.to_string()));
+    }
+    docs
+}
+
+fn document_text(raw: &str) -> String {
+    let lower = raw.to_ascii_lowercase();
+    if lower.contains("<html") || lower.contains("<body") || lower.contains("<p") {
+        html_to_text(raw)
+    } else {
+        raw.lines()
+            .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Recognises "PART I", "PART II." etc. and returns the roman numeral and the rest of the line
+fn parse_part_prefix(line: &str) -> Option<(String, &str)> {
+    let rest = line.get(..5).filter(|p| p.eq_ignore_ascii_case("part "))?;
+    let rest = &line[rest.len()..];
+    let numeral: String = rest.chars().take_while(|c| matches!(c, 'I' | 'V' | 'X')).collect();
+    if numeral.is_empty() {
+        return None;
+    }
+    let after = &rest[numeral.len()..];
+    if !(after.is_empty() || after.starts_with(['.', ' ', ':', '-', '—'])) {
+        return None;
+    }
+    Some((numeral, after.trim_start_matches(['.', ':', '-', '—']).trim()))
+}
+
+/// Recognises an item heading such as "Item 2.02 Results of..." or "ITEM 1A. RISK FACTORS"
+fn parse_item_heading(line: &str) -> Option<(String, String)> {
+    let prefix = line.get(..5).filter(|p| p.eq_ignore_ascii_case("item "))?;
+    let rest = line[prefix.len()..].trim_start();
+    let code: String = rest
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '.')
+        .collect();
+    let code = code.trim_end_matches('.');
+    if !code.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let title = rest[code.len()..]
+        .trim_start_matches(['.', ':', '-', '—'])
+        .trim()
+        .trim_end_matches('.')
+        .to_string();
+    // Long lines are prose mentioning an item, not headings
+    if title.len() > 150 {
+        return None;
+    }
+    Some((code.to_uppercase(), title))
+}
+
+/// Closes a section, keeping the longer body when the item was already seen
+fn finish_section(section: Option<FilingSection>, sections: &mut Vec<FilingSection>) {
+    let Some(mut section) = section else { return };
+    section.text = section.text.trim().to_string();
+    match sections
+        .iter_mut()
+        .find(|s| s.item == section.item && s.part == section.part)
+    {
+        Some(existing) if existing.text.len() >= section.text.len() => {}
+        Some(existing) => *existing = section,
+        None => sections.push(section),
+    }
+}
+
+/// Splits a document's text into item sections. Text before the first item
+/// (the cover page) and after the signature block is dropped. When an item
+/// appears more than once (table of contents and body), the longest body wins.
+fn split_items(text: &str, form_type: &str) -> Vec<FilingSection> {
+    let mut sections: Vec<FilingSection> = Vec::new();
+    let mut current: Option<FilingSection> = None;
+    let mut part: Option<String> = None;
+
+    for line in text.lines() {
+        let mut line = line.trim();
+        if line.eq_ignore_ascii_case("signature") || line.eq_ignore_ascii_case("signatures") {
+            finish_section(current.take(), &mut sections);
+            continue;
+        }
+        if let Some((numeral, rest)) = parse_part_prefix(line) {
+            finish_section(current.take(), &mut sections);
+            part = Some(numeral);
+            line = rest;
+        }
+        if let Some((item, heading)) = parse_item_heading(line) {
+            finish_section(current.take(), &mut sections);
+            let title = match (heading.is_empty(), form_type.starts_with("8-K")) {
+                (true, true) => describe_8k_item(&item).unwrap_or_default().to_string(),
+                _ => heading,
+            };
+            current = Some(FilingSection {
+                item,
+                part: part.clone(),
+                title,
+                text: String::new(),
+            });
+            continue;
+        }
+        if let Some(section) = current.as_mut() {
+            if !line.is_empty() {
+                section.text.push_str(line);
+                section.text.push('\n');
+            }
+        }
+    }
+    finish_section(current.take(), &mut sections);
+
+    sections.retain(|s| !s.text.is_empty());
+    sections
+}
+
+impl Filing {
+    /// Parses an EDGAR complete submission text file
+    pub fn parse(full_text: &str) -> Result<Self> {
+        let header_text = full_text
+            .split("</SEC-HEADER>")
+            .next()
+            .filter(|_| full_text.contains("</SEC-HEADER>"))
+            .context("missing <SEC-HEADER> block in EDGAR filing")?;
+
+        let form_type = header_value(header_text, "CONFORMED SUBMISSION TYPE")
+            .context("missing CONFORMED SUBMISSION TYPE")?
+            .to_string();
+        let filed = header_value(header_text, "FILED AS OF DATE").context("missing FILED AS OF DATE")?;
+        let cik = header_value(header_text, "CENTRAL INDEX KEY").context("missing CENTRAL INDEX KEY")?;
+
+        let header = FilingHeader {
+            accession_number: header_value(header_text, "ACCESSION NUMBER")
+                .unwrap_or_default()
+                .to_string(),
+            company_name: header_value(header_text, "COMPANY CONFORMED NAME")
+                .unwrap_or_default()
+                .to_string(),
+            cik: cik.parse().with_context(|| format!("invalid CIK '{}'", cik))?,
+            filed_date: parse_compact_date(filed)
+                .with_context(|| format!("invalid filing date '{}'", filed))?,
+            period_of_report: header_value(header_text, "CONFORMED PERIOD OF REPORT")
+                .and_then(parse_compact_date),
+            form_type,
+        };
+
+        let mut sections = Vec::new();
+        for (doc_type, raw) in documents(full_text) {
+            if doc_type == header.form_type {
+                sections.extend(split_items(&document_text(&raw), &header.form_type));
+            } else if doc_type.starts_with("EX-99") {
+                let text = document_text(&raw);
+                let title = text.lines().next().unwrap_or_default().to_string();
+                sections.push(FilingSection {
+                    item: doc_type,
+                    part: None,
+                    title,
+                    text,
+                });
+            }
+        }
+
+        Ok(Self {
+            header,
+            ticker: None,
+            sections,
+        })
+    }
+
+    /// Fills in the ticker from a CIK-to-ticker map
+    pub fn with_tickers(mut self, tickers: &CikTickerMap) -> Self {
+        self.ticker = tickers.ticker(self.header.cik).map(|t| t.to_string());
+        self
+    }
+
+    /// Returns the first section with the given item code or exhibit type
+    pub fn section(&self, item: &str) -> Option<&FilingSection> {
+        self.sections.iter().find(|s| s.item.eq_ignore_ascii_case(item))
+    }
+
+    /// Builds one article per section, timestamped at midnight UTC of the filing date
+    pub fn to_articles(&self) -> Vec<Article> {
+        let timestamp = self
+            .header
+            .filed_date
+            .and_hms_opt(0, 0, 0)
+            .map(|dt| dt.and_utc().timestamp())
+            .unwrap_or_default();
+        let company = self.ticker.as_deref().unwrap_or(&self.header.company_name);
+
+        self.sections
+            .iter()
+            .map(|section| {
+                let label = if section.item.starts_with("EX-") {
+                    section.item.clone()
+                } else {
+                    match &section.part {
+                        Some(part) => format!("Part {} Item {}", part, section.item),
+                        None => format!("Item {}", section.item),
+                    }
+                };
+                let mut article = Article::new(
+                    &format!("{} {} {}: {}", company, self.header.form_type, label, section.title),
+                    &section.text,
+                    &format!("sec-edgar/{}", self.header.form_type),
+                    timestamp,
+                )
+                .with_url(&filing_url(SEC_URL, self.header.cik, &self.header.accession_number))
+                .with_author(&self.header.company_name)
+                .with_language("en")
+                .with_tag("sec-filing")
+                .with_metadata("cik", self.header.cik)
+                .with_metadata("form_type", self.header.form_type.clone())
+                .with_metadata("accession_number", self.header.accession_number.clone())
+                .with_metadata("item", section.item.clone());
+                if let Some(part) = &section.part {
+                    article = article.with_metadata("part", part.clone());
+                }
+                if let Some(ticker) = &self.ticker {
+                    article = article.with_symbols(&[ticker]).with_metadata("ticker", ticker.clone());
+                }
+                article
+            })
+            .collect()
+    }
+}
+
+/// A filing listed in a company's submissions index
+#[derive(Debug, Clone)]
+pub struct FilingRef {
+    pub accession_number: String,
+    pub form: String,
+    pub filing_date: NaiveDate,
+    pub report_date: Option<NaiveDate>,
+    pub primary_document: String,
+    /// 8-K item codes, empty for other forms
+    pub items: Vec<String>,
+}
+
+/// A company's submissions index
+#[derive(Debug, Clone)]
+pub struct CompanySubmissions {
+    pub cik: u64,
+    pub name: String,
+    pub tickers: Vec<String>,
+    pub filings: Vec<FilingRef>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmissionsJson {
+    cik: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    tickers: Vec<String>,
+    filings: SubmissionsFilings,
+}
+
+#[derive(Deserialize)]
+struct SubmissionsFilings {
+    recent: RecentFilings,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecentFilings {
+    accession_number: Vec<String>,
+    filing_date: Vec<String>,
+    #[serde(default)]
+    report_date: Vec<String>,
+    form: Vec<String>,
+    #[serde(default)]
+    items: Vec<String>,
+    #[serde(default)]
+    primary_document: Vec<String>,
+}
+
+impl CompanySubmissions {
+    /// Parses a `CIK##########.json` submissions document
+    pub fn parse(json: &str) -> Result<Self> {
+        let raw: SubmissionsJson = serde_json::from_str(json).context("invalid EDGAR submissions JSON")?;
+        let recent = raw.filings.recent;
+
+        let mut filings = Vec::new();
+        for (i, accession) in recent.accession_number.iter().enumerate() {
+            let filing_date = recent.filing_date.get(i).map(String::as_str).unwrap_or_default();
+            filings.push(FilingRef {
+                accession_number: accession.clone(),
+                form: recent.form.get(i).cloned().unwrap_or_default(),
+                filing_date: NaiveDate::parse_from_str(filing_date, "%Y-%m-%d")
+                    .with_context(|| format!("invalid filing date '{}'", filing_date))?,
+                report_date: recent
+                    .report_date
+                    .get(i)
+                    .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+                primary_document: recent.primary_document.get(i).cloned().unwrap_or_default(),
+                items: recent
+                    .items
+                    .get(i)
+                    .map(|items| {
+                        items
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|s| !s.is_empty())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            });
+        }
+
+        Ok(Self {
+            cik: raw.cik.parse().with_context(|| format!("invalid CIK '{}'", raw.cik))?,
+            name: raw.name,
+            tickers: raw.tickers,
+            filings,
+        })
+    }
+
+    /// Returns filings whose form is one of `forms` (all filings if empty)
+    pub fn filings_of<'a>(&'a self, forms: &'a [&str]) -> impl Iterator<Item = &'a FilingRef> + 'a {
+        self.filings
+            .iter()
+            .filter(move |f| forms.is_empty() || forms.contains(&f.form.as_str()))
+    }
+
+    /// Builds one headline article per filing, describing 8-K items by name
+    pub fn to_articles(&self, forms: &[&str]) -> Vec<Article> {
+        let company = self.tickers.first().unwrap_or(&self.name);
+        self.filings_of(forms)
+            .map(|filing| {
+                let content = if filing.items.is_empty() {
+                    format!("{} filed a {} report.", self.name, filing.form)
+                } else {
+                    let items: Vec<String> = filing
+                        .items
+                        .iter()
+                        .map(|code| match describe_8k_item(code) {
+                            Some(title) => format!("Item {} {}", code, title),
+                            None => format!("Item {}", code),
+                        })
+                        .collect();
+                    format!("{} filed a {} report: {}.", self.name, filing.form, items.join("; "))
+                };
+                let timestamp = filing
+                    .filing_date
+                    .and_hms_opt(0, 0, 0)
+                    .map(|dt| dt.and_utc().timestamp())
+                    .unwrap_or_default();
+                Article::new(
+                    &format!("{} files {} ({})", company, filing.form, filing.accession_number),
+                    &content,
+                    &format!("sec-edgar/{}", filing.form),
+                    timestamp,
+                )
+                .with_url(&filing_url(SEC_URL, self.cik, &filing.accession_number))
+                .with_author(&self.name)
+                .with_language("en")
+                .with_tag("sec-filing")
+                .with_symbols(&self.tickers)
+                .with_metadata("cik", self.cik)
+                .with_metadata("form_type", filing.form.clone())
+                .with_metadata("accession_number", filing.accession_number.clone())
+                .with_metadata("items", filing.items.clone())
+            })
+            .collect()
+    }
+}
+
+/// Fetches filings and indexes from EDGAR.
+///
+/// The SEC requires automated clients to declare a contact address in their
+/// user agent, so the crawler configuration must set one.
+pub struct EdgarClient {
+    fetcher: PoliteFetcher,
+    archives_url: String,
+    data_url: String,
+}
+
+impl EdgarClient {
+    /// Creates a client; fails if `config` has no contact address
+    pub fn new(config: CrawlerConfig) -> Result<Self> {
+        if config.contact.is_none() {
+            anyhow::bail!("SEC EDGAR requires a contact address in the crawler user agent");
+        }
+        Ok(Self {
+            fetcher: PoliteFetcher::new(config)?,
+            archives_url: SEC_URL.to_string(),
+            data_url: "https://data.sec.gov".to_string(),
+        })
+    }
+
+    /// Overrides the `www.sec.gov` and `data.sec.gov` base URLs (useful for tests)
+    pub fn with_base_urls(mut self, archives_url: &str, data_url: &str) -> Self {
+        self.archives_url = archives_url.trim_end_matches('/').to_string();
+        self.data_url = data_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fetches the SEC CIK-to-ticker mapping
+    pub async fn fetch_ticker_map(&self) -> Result<CikTickerMap> {
+        let body = self
+            .fetcher
+            .get_text(&format!("{}/files/company_tickers.json", self.archives_url))
+            .await?;
+        CikTickerMap::from_json(&body)
+    }
+
+    /// Fetches a company's submissions index
+    pub async fn fetch_submissions(&self, cik: u64) -> Result<CompanySubmissions> {
+        let body = self
+            .fetcher
+            .get_text(&format!("{}/submissions/CIK{:010}.json", self.data_url, cik))
+            .await?;
+        CompanySubmissions::parse(&body)
+    }
+
+    /// Fetches and parses a complete submission text file
+    pub async fn fetch_filing(&self, cik: u64, accession_number: &str) -> Result<Filing> {
+        let url = filing_url(&self.archives_url, cik, accession_number);
+        let body = self.fetcher.get_text(&url).await?;
+        Filing::parse(&body).with_context(|| format!("failed to parse {}", url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrapers::test_support::{Route, TestServer};
+    use std::time::Duration;
+
+    const FILING_8K: &str = include_str!("../../tests/fixtures/edgar/0000320193-23-000104.txt");
+    const FILING_10Q: &str = include_str!("../../tests/fixtures/edgar/0000789019-23-000091.txt");
+    const SUBMISSIONS: &str = include_str!("../../tests/fixtures/edgar/submissions_CIK0000320193.json");
+    const TICKERS: &str = include_str!("../../tests/fixtures/edgar/company_tickers.json");
+
+    #[test]
+    fn test_parse_8k_sections() {
+        let filing = Filing::parse(FILING_8K).unwrap();
+        assert_eq!(filing.header.form_type, "8-K");
+        assert_eq!(filing.header.cik, 320193);
+        assert_eq!(filing.header.accession_number, "0000320193-23-000104");
+        assert_eq!(filing.header.filed_date, NaiveDate::from_ymd_opt(2023, 11, 2).unwrap());
+
+        let items: Vec<&str> = filing.sections.iter().map(|s| s.item.as_str()).collect();
+        assert_eq!(items, ["2.02", "9.01", "EX-99.1"]);
+
+        let results = filing.section("2.02").unwrap();
+        assert_eq!(results.title, "Results of Operations and Financial Condition");
+        assert!(results.text.contains("fourth fiscal quarter"));
+        // The signature block is not part of the last item
+        assert!(!filing.section("9.01").unwrap().text.contains("Pursuant"));
+        assert!(filing.section("EX-99.1").unwrap().text.contains("all-time high"));
+    }
+
+    #[test]
+    fn test_parse_10q_parts_and_toc() {
+        let filing = Filing::parse(FILING_10Q).unwrap();
+        let risk = filing
+            .sections
+            .iter()
+            .find(|s| s.item == "1A" && s.part.as_deref() == Some("II"))
+            .unwrap();
+        assert_eq!(risk.title, "RISK FACTORS");
+        assert!(risk.text.contains("cybersecurity"));
+
+        // The table of contents entry for Item 2 is replaced by the real section body
+        let mdna = filing
+            .sections
+            .iter()
+            .find(|s| s.item == "2" && s.part.as_deref() == Some("I"))
+            .unwrap();
+        assert!(mdna.text.contains("Azure"));
+
+        let legal = filing
+            .sections
+            .iter()
+            .find(|s| s.item == "1" && s.part.as_deref() == Some("II"))
+            .unwrap();
+        assert!(legal.text.contains("legal proceedings"));
+    }
+
+    #[test]
+    fn test_articles_carry_ticker_and_form() {
+        let tickers = CikTickerMap::from_json(TICKERS).unwrap();
+        assert_eq!(tickers.ticker(789019), Some("MSFT"));
+        assert_eq!(tickers.cik("tsla"), Some(1318605));
+
+        let filing = Filing::parse(FILING_8K).unwrap().with_tickers(&tickers);
+        assert_eq!(filing.ticker.as_deref(), Some("AAPL"));
+
+        let articles = filing.to_articles();
+        assert_eq!(articles.len(), 3);
+        assert_eq!(
+            articles[0].title,
+            "AAPL 8-K Item 2.02: Results of Operations and Financial Condition"
+        );
+        assert_eq!(articles[0].source, "sec-edgar/8-K");
+        assert_eq!(articles[0].timestamp, 1698883200);
+        assert_eq!(articles[0].symbols, ["AAPL"]);
+        assert_eq!(articles[0].metadata["form_type"], "8-K");
+        assert_eq!(articles[0].metadata["item"], "2.02");
+        assert_eq!(articles[0].metadata["cik"], 320193);
+        assert_eq!(
+            articles[0].url.as_deref(),
+            Some("https://www.sec.gov/Archives/edgar/data/320193/000032019323000104/0000320193-23-000104.txt")
+        );
+    }
+
+    #[test]
+    fn test_parse_submissions_index() {
+        let submissions = CompanySubmissions::parse(SUBMISSIONS).unwrap();
+        assert_eq!(submissions.cik, 320193);
+        assert_eq!(submissions.filings.len(), 3);
+        assert_eq!(submissions.filings[1].items, ["2.02", "9.01"]);
+
+        let eight_ks = submissions.to_articles(&["8-K"]);
+        assert_eq!(eight_ks.len(), 1);
+        assert!(eight_ks[0].content.contains("Item 2.02 Results of Operations"));
+        assert_eq!(submissions.filings_of(&[]).count(), 3);
+    }
+
+    #[test]
+    fn test_rejects_non_filing_text() {
+        assert!(Filing::parse("just some text").is_err());
+        assert!(EdgarClient::new(CrawlerConfig::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_client_fetches_filing_and_index() {
+        let server = TestServer::start(vec![
+            (
+                "/Archives/edgar/data/320193/000032019323000104/0000320193-23-000104.txt",
+                Route::ok("text/plain", FILING_8K),
+            ),
+            (
+                "/submissions/CIK0000320193.json",
+                Route::ok("application/json", SUBMISSIONS),
+            ),
+        ])
+        .await;
+
+        let config = CrawlerConfig {
+            min_delay: Duration::ZERO,
+            ..CrawlerConfig::new("SentimentBot/0.1", Some("ops@example.com"))
+        };
+        let client = EdgarClient::new(config)
+            .unwrap()
+            .with_base_urls(&server.base_url, &server.base_url);
+
+        let submissions = client.fetch_submissions(320193).await.unwrap();
+        let filing = client
+            .fetch_filing(submissions.cik, &submissions.filings[1].accession_number)
+            .await
+            .unwrap();
+        assert_eq!(filing.sections.len(), 3);
+    }
+}
@@ -0,0 +1,75 @@
+//! HTML helpers shared by scrapers
+
+use scraper::{ElementRef, Html, Node};
+
+/// Elements that start a new line when converted to text
+const BLOCK_TAGS: &[&str] = &[
+    "address", "article", "blockquote", "br", "dd", "div", "dl", "dt", "footer", "h1", "h2",
+    "h3", "h4", "h5", "h6", "header", "hr", "li", "ol", "p", "pre", "section", "table", "tr",
+    "ul",
+];
+
+/// Elements whose content is never visible text
+const SKIPPED_TAGS: &[&str] = &["head", "script", "style", "noscript", "template"];
+
+/// Converts an HTML document or fragment to plain text.
+///
+/// Block elements become line breaks, table cells are separated by spaces, entities
+/// are decoded and whitespace is collapsed. Empty lines are dropped.
+pub fn html_to_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut raw = String::new();
+    collect_text(document.root_element(), &mut raw);
+
+    raw.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn collect_text(element: ElementRef, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(el) => {
+                let name = el.name();
+                if SKIPPED_TAGS.contains(&name) {
+                    continue;
+                }
+                let separator = if BLOCK_TAGS.contains(&name) {
+                    Some('\n')
+                } else if name == "td" || name == "th" {
+                    Some(' ')
+                } else {
+                    None
+                };
+
+                out.extend(separator);
+                if let Some(child_element) = ElementRef::wrap(child) {
+                    collect_text(child_element, out);
+                }
+                out.extend(separator);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text_blocks_and_entities() {
+        let html = "<html><head><title>t</title><style>p{}</style></head><body>\
+            <p>First&#160;<b>bold</b> line</p><div>Second &amp; last</div>\
+            <table><tr><td>a</td><td>b</td></tr></table></body></html>";
+        assert_eq!(html_to_text(html), "First bold line\nSecond & last\na b");
+    }
+
+    #[test]
+    fn test_html_to_text_fragment() {
+        assert_eq!(html_to_text("plain <i>text</i><br>next"), "plain text\nnext");
+    }
+}
@@ -2,10 +2,65 @@
 //!
 //! This module provides functionality for collecting data from various sources.
 //! It includes example scrapers and utilities for web scraping.
+//!
+//! - `robots` - robots.txt compliance and per-host crawl politeness
+//! - `reddit` - Reddit JSON listing source
+//! - `social_export` - X/Twitter archive and Telegram export importers
+//! - `edgar` - SEC EDGAR filings and submissions index
+//! - `transcripts` - earnings call transcripts with speaker segmentation
+//! - `html` - HTML-to-text helpers shared by the scrapers
+//! - `synthetic` - seeded synthetic news and price generator
+//! - `replay` - replay of archived article streams (JSONL/CSV)
+//! - `scheduler` - periodic polling of several sources with deduplication
+//! - `crawler` - sitemap and paginated archive crawler for news sites
+//!
+//! Every source that yields `Article`s implements [`ArticleSource`], so live
+//! scrapers and archive replays feed the pipeline through the same code path.
+
+pub mod crawler;
+pub mod edgar;
+pub mod html;
+pub mod reddit;
+pub mod replay;
+pub mod robots;
+pub mod scheduler;
+pub mod social_export;
+pub mod synthetic;
+pub mod transcripts;
+#[cfg(test)]
+pub(crate) mod test_support;
+
+pub use crawler::{ArchiveConfig, SiteConfig, SiteCrawler, Sitemap, SitemapEntry};
+pub use edgar::{CikTickerMap, CompanySubmissions, EdgarClient, Filing, FilingSection};
+pub use reddit::{RedditItem, RedditKind, RedditListing, RedditSource};
+pub use replay::{ReplaySource, ReplaySpeed};
+pub use robots::{CrawlerConfig, PoliteFetcher, RobotsCache, RobotsDisallowed, RobotsTxt};
+pub use scheduler::{shutdown_signal, PollConfig, Scheduler, SourceStats};
+pub use social_export::{import_telegram_export, import_twitter_archive, SocialPlatform, SocialPost};
+pub use synthetic::{SentimentRegime, ShockEvent, SyntheticDataset, SyntheticNewsConfig, SyntheticPriceConfig};
+pub use transcripts::{CallSection, SpeakerRole, Transcript, TranscriptSegment};
 
 use crate::types::Article;
 use anyhow::Result;
-use std::time::{SystemTime, UNIX_EPOCH};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A source of articles that can be polled repeatedly
+#[async_trait]
+pub trait ArticleSource: Send {
+    /// Name used in logs and scheduler reports
+    fn name(&self) -> &str;
+
+    /// Returns the articles that are available now. Live sources may return
+    /// items seen in earlier calls; callers deduplicate by `Article::id`.
+    async fn fetch(&mut self) -> Result<Vec<Article>>;
+
+    /// Finite sources (e.g. archive replays) return true once drained
+    fn is_finished(&self) -> bool {
+        false
+    }
+}
 
 /// A simple mock news scraper for demonstration purposes.
 /// In production, this would connect to real news APIs or websites.
@@ -36,12 +91,18 @@ impl NewsScraper {
             .unwrap()
             .as_secs() as i64;
         
-        Article {
-            title: title.to_string(),
-            content: content.to_string(),
-            source: self.source.clone(),
-            timestamp,
-        }
+        Article::new(title, content, &self.source, timestamp)
+    }
+}
+
+#[async_trait]
+impl ArticleSource for NewsScraper {
+    fn name(&self) -> &str {
+        &self.source
+    }
+
+    async fn fetch(&mut self) -> Result<Vec<Article>> {
+        self.scrape().await
     }
 }
 
@@ -52,30 +113,30 @@ impl MockDataProvider {
     /// Returns a set of sample articles for testing
     pub fn get_sample_articles() -> Vec<Article> {
         vec![
-            Article {
-                title: "Bitcoin Surges to New All-Time High".to_string(),
-                content: "Bitcoin has reached unprecedented levels as institutional adoption continues to grow. Major companies announce BTC purchases.".to_string(),
-                source: "CryptoNews".to_string(),
-                timestamp: 1696435200,
-            },
-            Article {
-                title: "Ethereum Upgrade Boosts Network Performance".to_string(),
-                content: "The latest Ethereum upgrade shows promising results with improved transaction speeds and reduced gas fees.".to_string(),
-                source: "BlockchainDaily".to_string(),
-                timestamp: 1696435300,
-            },
-            Article {
-                title: "Market Correction Expected Amid Regulatory Concerns".to_string(),
-                content: "Analysts warn of potential market downturn as regulatory pressure increases. Investors show caution in recent trading.".to_string(),
-                source: "FinanceTimes".to_string(),
-                timestamp: 1696435400,
-            },
-            Article {
-                title: "DeFi Protocols Report Strong Growth".to_string(),
-                content: "Decentralized finance platforms continue to see increased adoption with total value locked reaching new highs.".to_string(),
-                source: "DeFiWatch".to_string(),
-                timestamp: 1696435500,
-            },
+            Article::new(
+                "Bitcoin Surges to New All-Time High",
+                "Bitcoin has reached unprecedented levels as institutional adoption continues to grow. Major companies announce BTC purchases.",
+                "CryptoNews",
+                1696435200,
+            ),
+            Article::new(
+                "Ethereum Upgrade Boosts Network Performance",
+                "The latest Ethereum upgrade shows promising results with improved transaction speeds and reduced gas fees.",
+                "BlockchainDaily",
+                1696435300,
+            ),
+            Article::new(
+                "Market Correction Expected Amid Regulatory Concerns",
+                "Analysts warn of potential market downturn as regulatory pressure increases. Investors show caution in recent trading.",
+                "FinanceTimes",
+                1696435400,
+            ),
+            Article::new(
+                "DeFi Protocols Report Strong Growth",
+                "Decentralized finance platforms continue to see increased adoption with total value locked reaching new highs.",
+                "DeFiWatch",
+                1696435500,
+            ),
         ]
     }
     
@@ -100,27 +161,77 @@ impl MockDataProvider {
             })
             .collect()
     }
+
+    /// Generates a reproducible synthetic article stream (and optional price
+    /// series) from a seed; see [`synthetic`] for the available regimes
+    pub fn generate(config: &SyntheticNewsConfig) -> SyntheticDataset {
+        synthetic::generate(config)
+    }
+
+    /// Shorthand for `count` seeded articles with the default configuration
+    pub fn generate_articles(seed: u64, count: usize) -> Vec<Article> {
+        synthetic::generate(&SyntheticNewsConfig::new(seed, count)).articles
+    }
 }
 
-/// Rate limiter for API requests
+/// Rate limiter for API requests.
+///
+/// Spaces requests evenly at `requests_per_second`; a rate of zero disables
+/// limiting. Clones share the same schedule.
+#[derive(Clone)]
 pub struct RateLimiter {
     requests_per_second: u32,
+    next_slot: Arc<Mutex<Option<Instant>>>,
 }
 
 impl RateLimiter {
     /// Creates a new rate limiter
     pub fn new(requests_per_second: u32) -> Self {
-        Self { requests_per_second }
+        Self {
+            requests_per_second,
+            next_slot: Arc::new(Mutex::new(None)),
+        }
     }
     
     /// Returns the configured rate limit
     pub fn get_rate(&self) -> u32 {
         self.requests_per_second
     }
+
+    /// Minimum spacing between two requests
+    pub fn interval(&self) -> Duration {
+        if self.requests_per_second == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(1) / self.requests_per_second
+        }
+    }
     
-    /// Checks if a request is allowed (mock implementation)
+    /// Takes a request slot if one is free right now, without waiting
     pub fn is_allowed(&self) -> bool {
-        true // In production, this would track actual request timing
+        let now = Instant::now();
+        let mut next = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        match *next {
+            Some(slot) if slot > now => false,
+            _ => {
+                *next = Some(now + self.interval());
+                true
+            }
+        }
+    }
+
+    /// Reserves the next request slot and sleeps until it arrives
+    pub async fn acquire(&self) {
+        let now = Instant::now();
+        let slot = {
+            let mut next = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = next.unwrap_or(now).max(now);
+            *next = Some(slot + self.interval());
+            slot
+        };
+        if slot > now {
+            tokio::time::sleep(slot - now).await;
+        }
     }
 }
 
@@ -155,7 +266,7 @@ mod tests {
     #[test]
     fn test_positive_articles_filter() {
         let articles = MockDataProvider::get_positive_articles();
-        assert!(articles.len() > 0);
+        assert!(!articles.is_empty());
         for article in articles {
             let text = format!("{} {}", article.title, article.content).to_lowercase();
             assert!(
@@ -169,7 +280,7 @@ mod tests {
     #[test]
     fn test_negative_articles_filter() {
         let articles = MockDataProvider::get_negative_articles();
-        assert!(articles.len() > 0);
+        assert!(!articles.is_empty());
         for article in articles {
             let text = format!("{} {}", article.title, article.content).to_lowercase();
             assert!(
@@ -185,6 +296,18 @@ mod tests {
         let limiter = RateLimiter::new(10);
         assert_eq!(limiter.get_rate(), 10);
         assert!(limiter.is_allowed());
+        assert!(!limiter.is_allowed());
+        assert!(RateLimiter::new(0).is_allowed());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_acquire_spaces_requests() {
+        let limiter = RateLimiter::new(50);
+        let started = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire().await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(60));
     }
     
     #[tokio::test]
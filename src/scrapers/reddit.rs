@@ -0,0 +1,455 @@
+//! Reddit JSON listing source
+//!
+//! Parses Reddit's public JSON listing format (`/r/<sub>/new.json`) and comment
+//! threads (`/comments/<id>.json`) into articles. Posts and top-level comments keep
+//! their author, score and comment count, and listings are paginated with `after` tokens.
+
+use super::robots::{CrawlerConfig, PoliteFetcher};
+use super::ArticleSource;
+use crate::types::Article;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Kind of Reddit item an article was built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedditKind {
+    /// A submission (`t3`)
+    Post,
+    /// A comment (`t1`)
+    Comment,
+}
+
+/// A Reddit post or comment together with the article built from it
+#[derive(Debug, Clone)]
+pub struct RedditItem {
+    /// Reddit fullname, e.g. "t3_abc001"
+    pub id: String,
+    pub kind: RedditKind,
+    pub subreddit: String,
+    pub author: String,
+    pub score: i64,
+    /// Number of comments on a post (always 0 for comments)
+    pub num_comments: u64,
+    pub permalink: String,
+    /// Fullname of the parent item for comments
+    pub parent_id: Option<String>,
+    pub article: Article,
+}
+
+/// One page of a Reddit listing
+#[derive(Debug, Clone)]
+pub struct RedditListing {
+    pub items: Vec<RedditItem>,
+    /// Token for the next page, `None` on the last page
+    pub after: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Thing {
+    kind: String,
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct ListingData {
+    #[serde(default)]
+    after: Option<String>,
+    #[serde(default)]
+    children: Vec<Thing>,
+}
+
+#[derive(Deserialize)]
+struct PostData {
+    id: String,
+    #[serde(default)]
+    subreddit: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    selftext: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    score: i64,
+    #[serde(default)]
+    num_comments: u64,
+    #[serde(default)]
+    created_utc: f64,
+    #[serde(default)]
+    permalink: String,
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct CommentData {
+    id: String,
+    #[serde(default)]
+    subreddit: String,
+    #[serde(default)]
+    parent_id: Option<String>,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    score: i64,
+    #[serde(default)]
+    created_utc: f64,
+    #[serde(default)]
+    permalink: String,
+}
+
+/// Base URL used to turn permalinks into links back to the original item
+const REDDIT_URL: &str = "https://www.reddit.com";
+
+fn source_name(subreddit: &str) -> String {
+    format!("reddit/r/{}", subreddit)
+}
+
+fn post_item(data: PostData) -> RedditItem {
+    // Link posts have no self text; fall back to the linked URL so content is never empty
+    let content = if data.selftext.is_empty() {
+        data.url.clone()
+    } else {
+        data.selftext
+    };
+
+    let id = format!("t3_{}", data.id);
+    let article = Article::new(
+        &data.title,
+        &content,
+        &source_name(&data.subreddit),
+        data.created_utc as i64,
+    )
+    .with_url(&format!("{}{}", REDDIT_URL, data.permalink))
+    .with_author(&data.author)
+    .with_tag("reddit")
+    .with_tag(&data.subreddit)
+    .with_detected_symbols()
+    .with_metadata("reddit_id", id.clone())
+    .with_metadata("kind", "post")
+    .with_metadata("score", data.score)
+    .with_metadata("num_comments", data.num_comments);
+
+    RedditItem {
+        id,
+        kind: RedditKind::Post,
+        article,
+        subreddit: data.subreddit,
+        author: data.author,
+        score: data.score,
+        num_comments: data.num_comments,
+        permalink: data.permalink,
+        parent_id: None,
+    }
+}
+
+fn comment_item(data: CommentData, post_title: &str) -> RedditItem {
+    let id = format!("t1_{}", data.id);
+    let mut article = Article::new(
+        &format!("Re: {}", post_title),
+        &data.body,
+        &source_name(&data.subreddit),
+        data.created_utc as i64,
+    )
+    .with_url(&format!("{}{}", REDDIT_URL, data.permalink))
+    .with_author(&data.author)
+    .with_tag("reddit")
+    .with_tag(&data.subreddit)
+    .with_detected_symbols()
+    .with_metadata("reddit_id", id.clone())
+    .with_metadata("kind", "comment")
+    .with_metadata("score", data.score)
+    .with_metadata("num_comments", 0);
+    if let Some(parent) = &data.parent_id {
+        article = article.with_metadata("parent_id", parent.clone());
+    }
+
+    RedditItem {
+        id,
+        kind: RedditKind::Comment,
+        article,
+        subreddit: data.subreddit,
+        author: data.author,
+        score: data.score,
+        num_comments: 0,
+        permalink: data.permalink,
+        parent_id: data.parent_id,
+    }
+}
+
+fn parse_listing_value(value: Value) -> Result<ListingData> {
+    let thing: Thing = serde_json::from_value(value).context("malformed Reddit listing")?;
+    if thing.kind != "Listing" {
+        anyhow::bail!("expected a Reddit Listing, found kind '{}'", thing.kind);
+    }
+    serde_json::from_value(thing.data).context("malformed Reddit listing data")
+}
+
+/// Parses a subreddit listing page into posts. Non-post children are skipped.
+pub fn parse_listing(json: &str) -> Result<RedditListing> {
+    let value: Value = serde_json::from_str(json).context("invalid Reddit listing JSON")?;
+    let listing = parse_listing_value(value)?;
+
+    let mut items = Vec::new();
+    for child in listing.children.into_iter().filter(|c| c.kind == "t3") {
+        let data: PostData = serde_json::from_value(child.data).context("malformed Reddit post")?;
+        items.push(post_item(data));
+    }
+
+    Ok(RedditListing {
+        items,
+        after: listing.after.filter(|a| !a.is_empty()),
+    })
+}
+
+/// Parses a comment thread response and returns up to `limit` top-level comments,
+/// highest score first. `more` placeholders and nested replies are skipped.
+pub fn parse_comments(json: &str, limit: usize) -> Result<Vec<RedditItem>> {
+    let value: Value = serde_json::from_str(json).context("invalid Reddit comments JSON")?;
+    let Value::Array(mut listings) = value else {
+        anyhow::bail!("expected a [post, comments] array from the Reddit comments endpoint");
+    };
+    if listings.len() < 2 {
+        anyhow::bail!("Reddit comments response has {} listings, expected 2", listings.len());
+    }
+
+    let comments = parse_listing_value(listings.remove(1))?;
+    let post = parse_listing_value(listings.remove(0))?;
+    let post_title = post
+        .children
+        .into_iter()
+        .find(|c| c.kind == "t3")
+        .and_then(|c| serde_json::from_value::<PostData>(c.data).ok())
+        .map(|p| p.title)
+        .unwrap_or_default();
+
+    let mut items = Vec::new();
+    for child in comments.children.into_iter().filter(|c| c.kind == "t1") {
+        let data: CommentData =
+            serde_json::from_value(child.data).context("malformed Reddit comment")?;
+        items.push(comment_item(data, &post_title));
+    }
+    items.sort_by_key(|item| std::cmp::Reverse(item.score));
+    items.truncate(limit);
+    Ok(items)
+}
+
+/// Collects posts (and optionally top comments) from a subreddit's JSON listing
+pub struct RedditSource {
+    fetcher: PoliteFetcher,
+    base_url: String,
+    subreddit: String,
+    page_size: u32,
+    top_comments: usize,
+}
+
+impl RedditSource {
+    /// Creates a source for `subreddit` using the given crawler identity
+    pub fn new(subreddit: &str, config: CrawlerConfig) -> Result<Self> {
+        Ok(Self {
+            fetcher: PoliteFetcher::new(config)?,
+            base_url: REDDIT_URL.to_string(),
+            subreddit: subreddit.to_string(),
+            page_size: 25,
+            top_comments: 0,
+        })
+    }
+
+    /// Overrides the Reddit base URL (useful for mirrors and tests)
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the number of posts requested per page
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Also collects up to `n` top-level comments for every post
+    pub fn with_top_comments(mut self, n: usize) -> Self {
+        self.top_comments = n;
+        self
+    }
+
+    /// Fetches one listing page, starting after the given token
+    pub async fn fetch_page(&self, after: Option<&str>) -> Result<RedditListing> {
+        let mut url = format!(
+            "{}/r/{}/new.json?limit={}&raw_json=1",
+            self.base_url, self.subreddit, self.page_size
+        );
+        if let Some(after) = after {
+            url.push_str(&format!("&after={}", after));
+        }
+        let body = self.fetcher.get_text(&url).await?;
+        parse_listing(&body).with_context(|| format!("failed to parse {}", url))
+    }
+
+    /// Fetches the top comments for a post id (without the `t3_` prefix)
+    pub async fn fetch_comments(&self, post_id: &str) -> Result<Vec<RedditItem>> {
+        let url = format!(
+            "{}/comments/{}.json?limit={}&sort=top&raw_json=1",
+            self.base_url, post_id, self.top_comments
+        );
+        let body = self.fetcher.get_text(&url).await?;
+        parse_comments(&body, self.top_comments).with_context(|| format!("failed to parse {}", url))
+    }
+
+    /// Follows `after` tokens for up to `max_pages` pages and returns every item found
+    pub async fn fetch(&self, max_pages: usize) -> Result<Vec<RedditItem>> {
+        let mut items = Vec::new();
+        let mut after: Option<String> = None;
+
+        for _ in 0..max_pages {
+            let page = self.fetch_page(after.as_deref()).await?;
+            for post in page.items {
+                let post_id = post.id.trim_start_matches("t3_").to_string();
+                let wants_comments = self.top_comments > 0 && post.num_comments > 0;
+                items.push(post);
+                if wants_comments {
+                    // A missing or locked thread should not abort the whole crawl
+                    match self.fetch_comments(&post_id).await {
+                        Ok(comments) => items.extend(comments),
+                        Err(e) => log::warn!("skipping comments for {}: {:#}", post_id, e),
+                    }
+                }
+            }
+
+            match page.after {
+                Some(token) => after = Some(token),
+                None => break,
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Same as `fetch`, returning only the articles
+    pub async fn fetch_articles(&self, max_pages: usize) -> Result<Vec<Article>> {
+        Ok(self
+            .fetch(max_pages)
+            .await?
+            .into_iter()
+            .map(|item| item.article)
+            .collect())
+    }
+}
+
+/// Polls the first page of the listing; the scheduler drops posts it has already seen
+#[async_trait]
+impl ArticleSource for RedditSource {
+    fn name(&self) -> &str {
+        &self.subreddit
+    }
+
+    async fn fetch(&mut self) -> Result<Vec<Article>> {
+        self.fetch_articles(1).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrapers::test_support::{Route, TestServer};
+    use std::time::Duration;
+
+    const PAGE1: &str = include_str!("../../tests/fixtures/reddit/listing_page1.json");
+    const PAGE2: &str = include_str!("../../tests/fixtures/reddit/listing_page2.json");
+    const COMMENTS: &str = include_str!("../../tests/fixtures/reddit/comments_abc003.json");
+
+    #[test]
+    fn test_parse_listing() {
+        let listing = parse_listing(PAGE1).unwrap();
+        assert_eq!(listing.after.as_deref(), Some("t3_abc002"));
+        assert_eq!(listing.items.len(), 2);
+
+        let post = &listing.items[0];
+        assert_eq!(post.id, "t3_abc001");
+        assert_eq!(post.kind, RedditKind::Post);
+        assert_eq!(post.author, "satoshi_fan");
+        assert_eq!(post.score, 1542);
+        assert_eq!(post.num_comments, 311);
+        assert_eq!(post.article.source, "reddit/r/CryptoCurrency");
+        assert_eq!(post.article.timestamp, 1696435200);
+        assert!(post.article.title.contains("BTC"));
+        assert_eq!(post.article.author.as_deref(), Some("satoshi_fan"));
+        assert_eq!(post.article.metadata["score"], 1542);
+        assert_eq!(post.article.metadata["num_comments"], 311);
+        assert_eq!(post.article.symbols, ["BTC"]);
+        assert_eq!(
+            post.article.url.as_deref(),
+            Some("https://www.reddit.com/r/CryptoCurrency/comments/abc001/btc_breaks_out/")
+        );
+    }
+
+    #[test]
+    fn test_link_post_falls_back_to_url() {
+        let listing = parse_listing(PAGE1).unwrap();
+        assert_eq!(listing.items[1].article.content, "https://news.example.com/exchange-hack");
+        assert!(parse_listing(PAGE2).unwrap().after.is_none());
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let comments = parse_comments(COMMENTS, 5).unwrap();
+        // Nested replies and `more` placeholders are not top-level comments
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].id, "t1_c001");
+        assert_eq!(comments[0].kind, RedditKind::Comment);
+        assert_eq!(comments[0].parent_id.as_deref(), Some("t3_abc003"));
+        assert_eq!(comments[0].article.title, "Re: ETH gas fees drop after upgrade");
+
+        assert_eq!(parse_comments(COMMENTS, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_rejects_non_listing() {
+        assert!(parse_listing("{\"kind\": \"t3\", \"data\": {}}").is_err());
+        assert!(parse_listing("not json").is_err());
+        assert!(parse_comments(PAGE1, 5).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_follows_after_tokens() {
+        let server = TestServer::start(vec![
+            (
+                "/r/CryptoCurrency/new.json?limit=2&raw_json=1",
+                Route::ok("application/json", PAGE1),
+            ),
+            (
+                "/r/CryptoCurrency/new.json?limit=2&raw_json=1&after=t3_abc002",
+                Route::ok("application/json", PAGE2),
+            ),
+            (
+                "/comments/abc003.json?limit=3&sort=top&raw_json=1",
+                Route::ok("application/json", COMMENTS),
+            ),
+        ])
+        .await;
+
+        let config = CrawlerConfig {
+            min_delay: Duration::ZERO,
+            ..CrawlerConfig::default()
+        };
+        let source = RedditSource::new("CryptoCurrency", config)
+            .unwrap()
+            .with_base_url(&server.base_url)
+            .with_page_size(2)
+            .with_top_comments(3);
+
+        let items = source.fetch(5).await.unwrap();
+        let posts = items.iter().filter(|i| i.kind == RedditKind::Post).count();
+        let comments = items.iter().filter(|i| i.kind == RedditKind::Comment).count();
+        assert_eq!(posts, 3);
+        // Only abc003 has a comment thread on the stand-in server
+        assert_eq!(comments, 2);
+
+        let articles = source.fetch_articles(1).await.unwrap();
+        assert_eq!(articles.len(), 2);
+    }
+}
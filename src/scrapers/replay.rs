@@ -0,0 +1,368 @@
+//! Replay of archived article streams
+//!
+//! Reads JSONL or CSV archives of `Article`s and plays them back in timestamp
+//! order, either as fast as possible or paced against a virtual clock that runs
+//! at a multiple of real time. `ReplaySource` implements `ArticleSource`, so a
+//! backtest drives exactly the same pipeline as the live scrapers.
+
+use super::ArticleSource;
+use crate::types::Article;
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// Longest wall-clock wait `next_due_in` reports; tiny speed factors saturate here
+const MAX_WAIT: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Playback speed of a replay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplaySpeed {
+    /// Articles are emitted with their original spacing
+    RealTime,
+    /// Archive time runs this many times faster than wall-clock time; a
+    /// non-positive factor behaves like `AsFastAsPossible`
+    Factor(f64),
+    /// Every article is due immediately
+    AsFastAsPossible,
+}
+
+impl ReplaySpeed {
+    fn factor(&self) -> Option<f64> {
+        match self {
+            ReplaySpeed::RealTime => Some(1.0),
+            ReplaySpeed::Factor(f) if *f > 0.0 && f.is_finite() => Some(*f),
+            ReplaySpeed::Factor(_) | ReplaySpeed::AsFastAsPossible => None,
+        }
+    }
+}
+
+/// Reads one `Article` per line; blank lines are skipped
+pub fn read_jsonl<R: Read>(reader: R) -> Result<Vec<Article>> {
+    let mut articles = Vec::new();
+    for (n, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let article: Article = serde_json::from_str(&line)
+            .with_context(|| format!("invalid article on line {}", n + 1))?;
+        articles.push(article);
+    }
+    Ok(articles)
+}
+
+/// Reads a CSV archive with a header row.
+///
+/// `title`, `content`, `source` and `timestamp` are required. `id`, `url`,
+/// `author` and `language` are optional, `tags` and `symbols` are
+/// `;`-separated lists and `metadata` is a JSON object. Empty cells are treated
+/// as missing; an empty `id` is recomputed from the content.
+pub fn read_csv<R: Read>(reader: R) -> Result<Vec<Article>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h.trim().eq_ignore_ascii_case(name));
+    let required = |name: &str| column(name).with_context(|| format!("CSV archive has no `{}` column", name));
+
+    let title = required("title")?;
+    let content = required("content")?;
+    let source = required("source")?;
+    let timestamp = required("timestamp")?;
+    let (id, url, author, language) = (column("id"), column("url"), column("author"), column("language"));
+    let (tags, symbols, metadata) = (column("tags"), column("symbols"), column("metadata"));
+
+    let mut articles = Vec::new();
+    for (n, record) in reader.records().enumerate() {
+        let record = record?;
+        let line = n + 2;
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let list = |index: Option<usize>| -> Vec<String> {
+            cell(index)
+                .map(|value| {
+                    value
+                        .split(';')
+                        .map(str::trim)
+                        .filter(|v| !v.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let ts: i64 = cell(Some(timestamp))
+            .with_context(|| format!("missing timestamp on line {}", line))?
+            .parse()
+            .with_context(|| format!("invalid timestamp on line {}", line))?;
+        let mut article = Article::new(
+            record.get(title).unwrap_or_default(),
+            record.get(content).unwrap_or_default(),
+            record.get(source).unwrap_or_default(),
+            ts,
+        );
+        if let Some(value) = cell(id) {
+            article.id = value.to_string();
+        }
+        article.url = cell(url).map(String::from);
+        article.author = cell(author).map(String::from);
+        article.language = cell(language).map(String::from);
+        article.tags = list(tags);
+        article.symbols = list(symbols);
+        if let Some(value) = cell(metadata) {
+            let map: BTreeMap<String, serde_json::Value> = serde_json::from_str(value)
+                .with_context(|| format!("invalid metadata JSON on line {}", line))?;
+            article.metadata = map;
+        }
+        articles.push(article);
+    }
+    Ok(articles)
+}
+
+/// Plays back an archive of articles in timestamp order
+pub struct ReplaySource {
+    name: String,
+    pending: VecDeque<Article>,
+    speed: ReplaySpeed,
+    /// Wall-clock instant and archive timestamp the virtual clock started at
+    clock: Option<(Instant, i64)>,
+}
+
+impl ReplaySource {
+    /// Creates a replay over `articles`, sorted by timestamp. Articles with the
+    /// same timestamp keep their archive order.
+    pub fn new(name: &str, mut articles: Vec<Article>) -> Self {
+        articles.sort_by_key(|a| a.timestamp);
+        Self {
+            name: name.to_string(),
+            pending: articles.into(),
+            speed: ReplaySpeed::AsFastAsPossible,
+            clock: None,
+        }
+    }
+
+    /// Loads a `.jsonl`/`.ndjson` or `.csv` archive, chosen by file extension
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("failed to open archive {}", path.display()))?;
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let articles = match extension.as_str() {
+            "jsonl" | "ndjson" => read_jsonl(file),
+            "csv" => read_csv(file),
+            other => bail!("unsupported archive format `{}` for {}", other, path.display()),
+        }
+        .with_context(|| format!("failed to read archive {}", path.display()))?;
+        Ok(Self::new(&path.display().to_string(), articles))
+    }
+
+    /// Sets the playback speed (default: as fast as possible)
+    pub fn with_speed(mut self, speed: ReplaySpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Drops articles published before `start` (inclusive bound)
+    pub fn with_start(mut self, start: i64) -> Self {
+        self.pending.retain(|a| a.timestamp >= start);
+        self
+    }
+
+    /// Drops articles published at or after `end` (exclusive bound)
+    pub fn with_end(mut self, end: i64) -> Self {
+        self.pending.retain(|a| a.timestamp < end);
+        self
+    }
+
+    /// Number of articles not yet emitted
+    pub fn remaining(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Wall-clock time until the next article is due, or `None` when drained.
+    /// Starts the virtual clock if it is not running yet. Waits are capped at
+    /// one year.
+    pub fn next_due_in(&mut self) -> Option<Duration> {
+        let next = self.pending.front()?.timestamp;
+        let Some(factor) = self.speed.factor() else {
+            return Some(Duration::ZERO);
+        };
+        let (started, origin) = *self.clock.get_or_insert((Instant::now(), next));
+        let offset = Duration::try_from_secs_f64((next - origin).max(0) as f64 / factor)
+            .unwrap_or(MAX_WAIT)
+            .min(MAX_WAIT);
+        let wait = started
+            .checked_add(offset)
+            .map_or(MAX_WAIT, |due| due.saturating_duration_since(Instant::now()));
+        Some(wait.min(MAX_WAIT))
+    }
+
+    /// Removes and returns every article that is due on the virtual clock
+    fn take_due(&mut self) -> Vec<Article> {
+        let mut due = Vec::new();
+        while matches!(self.next_due_in(), Some(wait) if wait.is_zero()) {
+            due.extend(self.pending.pop_front());
+        }
+        due
+    }
+
+    /// Emits every article into `tx`, sleeping between articles according to
+    /// the playback speed. Returns the number of articles sent; stops early if
+    /// the receiver is dropped.
+    pub async fn run(mut self, tx: mpsc::Sender<Article>) -> Result<usize> {
+        let mut sent = 0;
+        while let Some(wait) = self.next_due_in() {
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+            for article in self.take_due() {
+                if tx.send(article).await.is_err() {
+                    return Ok(sent);
+                }
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+}
+
+#[async_trait]
+impl ArticleSource for ReplaySource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the articles that became due since the last call; never blocks
+    async fn fetch(&mut self) -> Result<Vec<Article>> {
+        Ok(self.take_due())
+    }
+
+    fn is_finished(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSONL: &str = include_str!("../../tests/fixtures/replay/archive.jsonl");
+    const CSV: &str = include_str!("../../tests/fixtures/replay/archive.csv");
+
+    #[test]
+    fn test_read_jsonl() {
+        let articles = read_jsonl(JSONL.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 4);
+        assert_eq!(articles[0].id, "arch-0003");
+        // Legacy records without an id get the content hash
+        assert_eq!(
+            articles[1].id,
+            Article::content_id("CryptoNews", "Bitcoin surges to record high", "Strong inflows lift BTC.")
+        );
+        assert_eq!(articles[3].metadata["severity"], "high");
+
+        let err = read_jsonl("{\"title\": 1}\n".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn test_read_csv() {
+        let articles = read_csv(CSV.as_bytes()).unwrap();
+        assert_eq!(articles.len(), 3);
+
+        assert_eq!(articles[0].title, "ETH, SOL rally");
+        assert!(articles[0].content.contains("\"bullish\""));
+        assert_eq!(articles[0].symbols, ["ETH", "SOL"]);
+        assert_eq!(articles[0].url, None);
+
+        assert_eq!(articles[1].id, "csv-1");
+        assert!(articles[1].content.contains("await\nthe policy"));
+        assert_eq!(articles[1].author.as_deref(), Some("Jane Doe"));
+        assert_eq!(articles[1].tags, ["macro", "rates"]);
+        assert_eq!(articles[1].metadata["desk"], "macro");
+
+        assert_eq!(articles[2].id.len(), 16);
+
+        assert!(read_csv("title,content\na,b\n".as_bytes()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_orders_and_filters() {
+        let mut articles = read_jsonl(JSONL.as_bytes()).unwrap();
+        articles.extend(read_csv(CSV.as_bytes()).unwrap());
+
+        let mut source = ReplaySource::new("archive", articles)
+            .with_start(1696435260)
+            .with_end(1696435800);
+        assert_eq!(source.remaining(), 4);
+
+        let batch = source.fetch().await.unwrap();
+        let timestamps: Vec<i64> = batch.iter().map(|a| a.timestamp).collect();
+        assert_eq!(timestamps, [1696435260, 1696435300, 1696435320, 1696435500]);
+        assert!(source.is_finished());
+        assert!(source.fetch().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_paces_by_speed() {
+        let articles = vec![
+            Article::new("a", "first", "s", 1000),
+            Article::new("b", "second", "s", 1000),
+            Article::new("c", "third", "s", 1020),
+        ];
+        // 20 archive seconds at 200x is 100ms of wall-clock time
+        let mut source = ReplaySource::new("paced", articles.clone()).with_speed(ReplaySpeed::Factor(200.0));
+        assert_eq!(source.fetch().await.unwrap().len(), 2);
+        assert!(source.fetch().await.unwrap().is_empty());
+        assert!(source.next_due_in().unwrap() > Duration::from_millis(50));
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let started = Instant::now();
+        let sent = ReplaySource::new("paced", articles)
+            .with_speed(ReplaySpeed::Factor(200.0))
+            .run(tx)
+            .await
+            .unwrap();
+        assert_eq!(sent, 3);
+        assert!(started.elapsed() >= Duration::from_millis(100));
+
+        let mut titles = Vec::new();
+        while let Some(article) = rx.recv().await {
+            titles.push(article.title);
+        }
+        assert_eq!(titles, ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_tiny_speed_factor_saturates() {
+        let articles = vec![
+            Article::new("a", "first", "s", 1000),
+            Article::new("b", "second", "s", 1001),
+        ];
+        let mut source = ReplaySource::new("slow", articles).with_speed(ReplaySpeed::Factor(1e-300));
+        assert_eq!(source.next_due_in(), Some(Duration::ZERO));
+        assert_eq!(source.take_due().len(), 1);
+        assert!(source.next_due_in().unwrap() > MAX_WAIT - Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_open_rejects_unknown_extension() {
+        let path = std::env::temp_dir().join("replay_archive_test.xml");
+        std::fs::write(&path, "<articles/>").unwrap();
+        let err = ReplaySource::open(&path).err().unwrap();
+        assert!(err.to_string().contains("unsupported archive format"));
+        std::fs::remove_file(path).ok();
+        assert_eq!(ReplaySource::open("tests/fixtures/replay/archive.csv").unwrap().remaining(), 3);
+    }
+}
@@ -0,0 +1,613 @@
+//! robots.txt compliance and crawl politeness
+//!
+//! Fetches, parses and caches `robots.txt` per host and enforces its
+//! `Allow`, `Disallow` and `Crawl-delay` directives for the configured user agent.
+//! Rule precedence follows RFC 9309: the longest matching pattern wins and
+//! `Allow` wins ties. `*` wildcards and `$` end anchors are supported.
+
+use anyhow::{Context, Result};
+use reqwest::{Client, Url};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long an unreachable robots.txt is treated as "disallow all" before retrying
+const UNREACHABLE_RETRY: Duration = Duration::from_secs(300);
+
+/// Identity and politeness settings for outbound crawling
+#[derive(Debug, Clone)]
+pub struct CrawlerConfig {
+    /// User agent sent with every request, e.g. "SentimentBot/0.1"
+    pub user_agent: String,
+    /// Contact address (email or URL) appended to the user agent header
+    pub contact: Option<String>,
+    /// How long a fetched robots.txt is cached
+    pub robots_ttl: Duration,
+    /// Minimum delay between requests to the same host when robots.txt sets none
+    pub min_delay: Duration,
+    /// Upper bound on a robots.txt `Crawl-delay`, so one host cannot stall the crawler
+    pub max_delay: Duration,
+    /// Request timeout
+    pub timeout: Duration,
+}
+
+impl Default for CrawlerConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: format!("SentimentAnalysisTrading/{}", env!("CARGO_PKG_VERSION")),
+            contact: None,
+            robots_ttl: Duration::from_secs(24 * 60 * 60),
+            min_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl CrawlerConfig {
+    /// Creates a configuration with the given user agent and contact address
+    pub fn new(user_agent: &str, contact: Option<&str>) -> Self {
+        Self {
+            user_agent: user_agent.to_string(),
+            contact: contact.map(|c| c.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the full `User-Agent` header value, including the contact address
+    pub fn header_value(&self) -> String {
+        match &self.contact {
+            Some(contact) => format!("{} (+{})", self.user_agent, contact),
+            None => self.user_agent.clone(),
+        }
+    }
+
+    /// Returns the product token used to match `User-agent` lines in robots.txt
+    pub fn product_token(&self) -> String {
+        self.user_agent
+            .split(['/', ' '])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+
+    /// Builds an HTTP client that identifies itself with this configuration
+    pub fn build_client(&self) -> Result<Client> {
+        Client::builder()
+            .user_agent(self.header_value())
+            .timeout(self.timeout)
+            .build()
+            .context("failed to build HTTP client")
+    }
+}
+
+/// Error returned when robots.txt forbids fetching a URL
+#[derive(Debug, Clone, PartialEq)]
+pub struct RobotsDisallowed {
+    pub url: String,
+    pub user_agent: String,
+}
+
+impl fmt::Display for RobotsDisallowed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "robots.txt disallows fetching {} for user agent '{}'",
+            self.url, self.user_agent
+        )
+    }
+}
+
+impl std::error::Error for RobotsDisallowed {}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+/// A parsed robots.txt file
+#[derive(Debug, Clone, Default)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+    sitemaps: Vec<String>,
+}
+
+impl RobotsTxt {
+    /// Parses robots.txt content. Unknown directives and malformed lines are ignored.
+    pub fn parse(text: &str) -> Self {
+        let mut robots = RobotsTxt::default();
+        let mut current: Option<Group> = None;
+        let mut in_agent_block = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if !in_agent_block {
+                        if let Some(group) = current.take() {
+                            robots.groups.push(group);
+                        }
+                        current = Some(Group::default());
+                        in_agent_block = true;
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                }
+                "allow" | "disallow" => {
+                    in_agent_block = false;
+                    // An empty Disallow means "allow everything" and adds no rule
+                    if let (Some(group), false) = (current.as_mut(), value.is_empty()) {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    in_agent_block = false;
+                    if let (Some(group), Ok(secs)) = (current.as_mut(), value.parse::<f64>()) {
+                        // Delays too large for a Duration saturate; callers clamp them
+                        // to `CrawlerConfig::max_delay`
+                        if secs.is_finite() && secs >= 0.0 {
+                            group.crawl_delay =
+                                Some(Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX));
+                        }
+                    }
+                }
+                "sitemap" => robots.sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        if let Some(group) = current {
+            robots.groups.push(group);
+        }
+        robots
+    }
+
+    /// A robots.txt that permits everything (used when the file is missing)
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// A robots.txt that forbids everything (used when the file is unreachable)
+    pub fn disallow_all() -> Self {
+        Self {
+            groups: vec![Group {
+                agents: vec!["*".to_string()],
+                rules: vec![Rule {
+                    allow: false,
+                    pattern: "/".to_string(),
+                }],
+                crawl_delay: None,
+            }],
+            sitemaps: Vec::new(),
+        }
+    }
+
+    /// Returns the groups that apply to `product_token`, falling back to `*`
+    fn groups_for(&self, product_token: &str) -> Vec<&Group> {
+        let token = product_token.to_lowercase();
+        let specific: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|g| g.agents.contains(&token))
+            .collect();
+        if !specific.is_empty() {
+            return specific;
+        }
+        self.groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|a| a == "*"))
+            .collect()
+    }
+
+    /// Checks whether `path` (including any query string) may be fetched
+    pub fn is_allowed(&self, product_token: &str, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        let mut best: Option<(usize, bool)> = None;
+        for group in self.groups_for(product_token) {
+            for rule in &group.rules {
+                if !pattern_matches(&rule.pattern, path) {
+                    continue;
+                }
+                let len = rule.pattern.len();
+                best = match best {
+                    Some((best_len, best_allow))
+                        if best_len > len || (best_len == len && best_allow) =>
+                    {
+                        Some((best_len, best_allow))
+                    }
+                    _ => Some((len, rule.allow)),
+                };
+            }
+        }
+
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+
+    /// Returns the crawl delay requested for `product_token`, if any
+    pub fn crawl_delay(&self, product_token: &str) -> Option<Duration> {
+        self.groups_for(product_token)
+            .iter()
+            .filter_map(|g| g.crawl_delay)
+            .max()
+    }
+
+    /// Returns the sitemap URLs listed in the file
+    pub fn sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+}
+
+/// Matches a robots.txt path pattern supporting `*` and a trailing `$`
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let pattern = pattern.as_bytes();
+    let path = path.as_bytes();
+
+    // Iterative wildcard matching with single-star backtracking
+    let (mut p, mut s) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    loop {
+        if p == pattern.len() && (!anchored || s == path.len()) {
+            return true;
+        }
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, s));
+            p += 1;
+            continue;
+        }
+        if p < pattern.len() && s < path.len() && pattern[p] == path[s] {
+            p += 1;
+            s += 1;
+            continue;
+        }
+        match star {
+            Some((star_p, star_s)) if star_s < path.len() => {
+                star = Some((star_p, star_s + 1));
+                p = star_p + 1;
+                s = star_s + 1;
+            }
+            _ => return false,
+        }
+    }
+}
+
+struct CachedRobots {
+    robots: Arc<RobotsTxt>,
+    expires: Instant,
+}
+
+/// Fetches and caches robots.txt per host
+pub struct RobotsCache {
+    client: Client,
+    config: CrawlerConfig,
+    entries: Mutex<HashMap<String, CachedRobots>>,
+}
+
+impl RobotsCache {
+    /// Creates a cache that fetches robots.txt with the given crawler identity
+    pub fn new(config: CrawlerConfig) -> Result<Self> {
+        Ok(Self {
+            client: config.build_client()?,
+            config,
+            entries: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the crawler configuration
+    pub fn config(&self) -> &CrawlerConfig {
+        &self.config
+    }
+
+    /// Returns the robots.txt rules for the host of `url`, fetching them if needed
+    pub async fn rules_for(&self, url: &Url) -> Result<Arc<RobotsTxt>> {
+        let origin = url.origin().ascii_serialization();
+        if let Some(cached) = self.entries.lock().await.get(&origin) {
+            if cached.expires > Instant::now() {
+                return Ok(cached.robots.clone());
+            }
+        }
+
+        let (robots, ttl) = self.fetch(&origin).await;
+        let robots = Arc::new(robots);
+        self.entries.lock().await.insert(
+            origin,
+            CachedRobots {
+                robots: robots.clone(),
+                expires: Instant::now() + ttl,
+            },
+        );
+        Ok(robots)
+    }
+
+    async fn fetch(&self, origin: &str) -> (RobotsTxt, Duration) {
+        let robots_url = format!("{}/robots.txt", origin);
+        match self.client.get(&robots_url).send().await {
+            Ok(response) if response.status().is_success() => match response.text().await {
+                Ok(body) => (RobotsTxt::parse(&body), self.config.robots_ttl),
+                Err(e) => {
+                    log::warn!("failed to read {}: {}", robots_url, e);
+                    (RobotsTxt::disallow_all(), UNREACHABLE_RETRY)
+                }
+            },
+            Ok(response) if response.status().is_client_error() => {
+                (RobotsTxt::allow_all(), self.config.robots_ttl)
+            }
+            Ok(response) => {
+                log::warn!("{} returned {}", robots_url, response.status());
+                (RobotsTxt::disallow_all(), UNREACHABLE_RETRY)
+            }
+            Err(e) => {
+                log::warn!("failed to fetch {}: {}", robots_url, e);
+                (RobotsTxt::disallow_all(), UNREACHABLE_RETRY)
+            }
+        }
+    }
+
+    /// Returns an error wrapping `RobotsDisallowed` if `url` may not be fetched
+    pub async fn check(&self, url: &str) -> Result<()> {
+        let parsed = Url::parse(url).with_context(|| format!("invalid URL: {}", url))?;
+        let robots = self.rules_for(&parsed).await?;
+        let path = match parsed.query() {
+            Some(query) => format!("{}?{}", parsed.path(), query),
+            None => parsed.path().to_string(),
+        };
+
+        if robots.is_allowed(&self.config.product_token(), &path) {
+            Ok(())
+        } else {
+            Err(RobotsDisallowed {
+                url: url.to_string(),
+                user_agent: self.config.user_agent.clone(),
+            }
+            .into())
+        }
+    }
+
+    /// Returns the delay to keep between requests to the host of `url`,
+    /// clamped to the configured maximum
+    pub async fn delay_for(&self, url: &Url) -> Result<Duration> {
+        let robots = self.rules_for(url).await?;
+        Ok(robots
+            .crawl_delay(&self.config.product_token())
+            .unwrap_or_default()
+            .min(self.config.max_delay)
+            .max(self.config.min_delay))
+    }
+}
+
+/// HTTP fetcher that checks robots.txt and spaces out requests per host
+pub struct PoliteFetcher {
+    robots: RobotsCache,
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl PoliteFetcher {
+    /// Creates a fetcher with the given crawler identity
+    pub fn new(config: CrawlerConfig) -> Result<Self> {
+        Ok(Self {
+            robots: RobotsCache::new(config)?,
+            next_slot: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the underlying robots.txt cache
+    pub fn robots(&self) -> &RobotsCache {
+        &self.robots
+    }
+
+    /// Fetches `url` as text after checking robots.txt and waiting out the crawl delay
+    pub async fn get_text(&self, url: &str) -> Result<String> {
+        self.robots.check(url).await?;
+        let parsed = Url::parse(url).with_context(|| format!("invalid URL: {}", url))?;
+        self.wait_turn(&parsed).await?;
+
+        let response = self
+            .robots
+            .client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("request to {} failed", url))?
+            .error_for_status()
+            .with_context(|| format!("request to {} failed", url))?;
+        response
+            .text()
+            .await
+            .with_context(|| format!("failed to read body of {}", url))
+    }
+
+    /// Reserves the next request slot for the host of `url` and sleeps until it arrives
+    async fn wait_turn(&self, url: &Url) -> Result<()> {
+        let delay = self.robots.delay_for(url).await?;
+        let origin = url.origin().ascii_serialization();
+        let now = Instant::now();
+
+        let start = {
+            let mut slots = self.next_slot.lock().await;
+            let start = slots.get(&origin).copied().unwrap_or(now).max(now);
+            slots.insert(origin, start + delay);
+            start
+        };
+
+        if start > now {
+            tokio::time::sleep(start - now).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrapers::test_support::{Route, TestServer};
+
+    const ROBOTS: &str = "\
+# example robots.txt
+User-agent: *
+Disallow: /private/
+Allow: /private/public-note
+Crawl-delay: 2
+
+User-agent: SentimentBot
+User-agent: OtherBot
+Disallow: /news/*.pdf$
+Disallow: /search
+Crawl-delay: 0.5
+
+Sitemap: https://example.com/sitemap.xml
+";
+
+    #[test]
+    fn test_parse_wildcard_group() {
+        let robots = RobotsTxt::parse(ROBOTS);
+        assert!(!robots.is_allowed("somebot", "/private/data"));
+        assert!(robots.is_allowed("somebot", "/private/public-note"));
+        assert!(robots.is_allowed("somebot", "/news/today"));
+        assert_eq!(robots.crawl_delay("somebot"), Some(Duration::from_secs(2)));
+        assert_eq!(robots.sitemaps(), ["https://example.com/sitemap.xml"]);
+    }
+
+    #[test]
+    fn test_specific_group_overrides_wildcard() {
+        let robots = RobotsTxt::parse(ROBOTS);
+        // The specific group replaces the `*` group entirely
+        assert!(robots.is_allowed("sentimentbot", "/private/data"));
+        assert!(!robots.is_allowed("SentimentBot", "/news/report.pdf"));
+        assert!(robots.is_allowed("sentimentbot", "/news/report.pdf?download=1"));
+        assert!(!robots.is_allowed("otherbot", "/search?q=btc"));
+        assert_eq!(robots.crawl_delay("sentimentbot"), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_longest_match_and_allow_ties() {
+        let robots = RobotsTxt::parse(
+            "User-agent: *\nDisallow: /a\nAllow: /a/b\nDisallow: /a/b/c\nAllow: /x\nDisallow: /x\n",
+        );
+        assert!(!robots.is_allowed("bot", "/a/z"));
+        assert!(robots.is_allowed("bot", "/a/b/z"));
+        assert!(!robots.is_allowed("bot", "/a/b/c/d"));
+        assert!(robots.is_allowed("bot", "/x"));
+    }
+
+    #[test]
+    fn test_empty_disallow_and_defaults() {
+        let robots = RobotsTxt::parse("User-agent: *\nDisallow:\n");
+        assert!(robots.is_allowed("bot", "/anything"));
+        assert!(RobotsTxt::allow_all().is_allowed("bot", "/"));
+        assert!(!RobotsTxt::disallow_all().is_allowed("bot", "/page"));
+        assert!(RobotsTxt::disallow_all().is_allowed("bot", "/robots.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_huge_crawl_delay_is_clamped() {
+        let robots = RobotsTxt::parse("User-agent: *\nCrawl-delay: 1e30\n");
+        assert_eq!(robots.crawl_delay("bot"), Some(Duration::MAX));
+        let robots = RobotsTxt::parse("User-agent: *\nCrawl-delay: 86400000\n");
+        assert_eq!(robots.crawl_delay("bot"), Some(Duration::from_secs(86_400_000)));
+
+        for delay in ["1e30", "86400000"] {
+            let server = TestServer::start(vec![(
+                "/robots.txt",
+                Route::ok("text/plain", &format!("User-agent: *\nCrawl-delay: {}\n", delay)),
+            )])
+            .await;
+            let config = CrawlerConfig {
+                max_delay: Duration::from_secs(30),
+                ..CrawlerConfig::default()
+            };
+            let cache = RobotsCache::new(config).unwrap();
+            let url = Url::parse(&server.url("/a")).unwrap();
+            assert_eq!(cache.delay_for(&url).await.unwrap(), Duration::from_secs(30));
+        }
+    }
+
+    #[test]
+    fn test_crawler_config_header() {
+        let config = CrawlerConfig::new("SentimentBot/1.2", Some("ops@example.com"));
+        assert_eq!(config.header_value(), "SentimentBot/1.2 (+ops@example.com)");
+        assert_eq!(config.product_token(), "sentimentbot");
+    }
+
+    #[tokio::test]
+    async fn test_cache_fetches_once_and_refuses_blocked_urls() {
+        let server = TestServer::start(vec![
+            ("/robots.txt", Route::ok("text/plain", ROBOTS)),
+            ("/news/today", Route::ok("text/html", "<html>ok</html>")),
+        ])
+        .await;
+
+        let config = CrawlerConfig {
+            min_delay: Duration::ZERO,
+            ..CrawlerConfig::new("SentimentBot/0.1", Some("ops@example.com"))
+        };
+        let fetcher = PoliteFetcher::new(config).unwrap();
+
+        let body = fetcher.get_text(&server.url("/news/today")).await.unwrap();
+        assert!(body.contains("ok"));
+
+        let err = fetcher
+            .get_text(&server.url("/search?q=eth"))
+            .await
+            .unwrap_err();
+        let blocked = err.downcast_ref::<RobotsDisallowed>().unwrap();
+        assert!(blocked.url.ends_with("/search?q=eth"));
+        assert!(err.to_string().contains("robots.txt disallows"));
+
+        assert_eq!(server.hits("/robots.txt"), 1);
+        assert_eq!(server.hits("/search?q=eth"), 0);
+        let requests = server.requests();
+        assert!(requests
+            .iter()
+            .all(|r| r.user_agent.as_deref() == Some("SentimentBot/0.1 (+ops@example.com)")));
+    }
+
+    #[tokio::test]
+    async fn test_missing_robots_allows_and_crawl_delay_spaces_requests() {
+        let server = TestServer::start(vec![
+            ("/robots.txt", Route::ok("text/plain", "User-agent: *\nCrawl-delay: 0.2\n")),
+            ("/a", Route::ok("text/html", "a")),
+        ])
+        .await;
+        let missing = TestServer::start(vec![]).await;
+
+        let config = CrawlerConfig {
+            min_delay: Duration::ZERO,
+            ..CrawlerConfig::default()
+        };
+        let fetcher = PoliteFetcher::new(config).unwrap();
+
+        // 404 robots.txt means everything is allowed; the page itself is 404 too
+        assert!(fetcher.robots().check(&missing.url("/anything")).await.is_ok());
+
+        let start = Instant::now();
+        fetcher.get_text(&server.url("/a")).await.unwrap();
+        fetcher.get_text(&server.url("/a")).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+}
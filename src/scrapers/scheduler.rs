@@ -0,0 +1,442 @@
+//! Polling scheduler for continuous multi-source collection
+//!
+//! Each source is polled on its own interval plus a random jitter, so sources
+//! sharing an interval do not hit the network in lockstep. A global semaphore
+//! caps how many fetches run at once, articles already seen in earlier polls
+//! (by `Article::id`) are dropped, and new articles are pushed into a channel.
+//! `run_until_signal` stops cleanly on SIGINT/SIGTERM.
+
+use super::synthetic::SeededRng;
+use super::ArticleSource;
+use crate::types::Article;
+use log::{info, warn};
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, watch, Semaphore};
+use tokio::task::JoinSet;
+
+/// Default number of article ids remembered for deduplication
+pub const DEFAULT_SEEN_CAPACITY: usize = 100_000;
+
+/// How often a source is polled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollConfig {
+    pub interval: Duration,
+    /// Upper bound of the random delay added to each interval
+    pub jitter: Duration,
+}
+
+impl PollConfig {
+    /// Polls every `interval` without jitter
+    pub fn every(interval: Duration) -> Self {
+        Self {
+            interval,
+            jitter: Duration::ZERO,
+        }
+    }
+
+    /// Adds a random delay of up to `jitter` to every interval
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    fn next_delay(&self, rng: &mut SeededRng) -> Duration {
+        let jitter_ms = self.jitter.as_millis() as u64;
+        self.interval + Duration::from_millis(rng.below(jitter_ms + 1))
+    }
+}
+
+/// Per-source counters reported when the scheduler stops
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceStats {
+    pub name: String,
+    pub polls: u64,
+    pub errors: u64,
+    pub new_articles: u64,
+    pub duplicates: u64,
+}
+
+/// Bounded set of seen ids; the oldest ids are forgotten first
+struct SeenIds {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl SeenIds {
+    fn new(capacity: usize) -> Self {
+        Self {
+            ids: HashSet::new(),
+            order: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns true if `id` was not seen before
+    fn insert(&mut self, id: &str) -> bool {
+        if !self.ids.insert(id.to_string()) {
+            return false;
+        }
+        self.order.push_back(id.to_string());
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Polls a set of sources concurrently and forwards new articles
+pub struct Scheduler {
+    sources: Vec<(Box<dyn ArticleSource>, PollConfig)>,
+    max_concurrency: usize,
+    seen_capacity: usize,
+}
+
+impl Scheduler {
+    /// Creates a scheduler that runs at most `max_concurrency` fetches at a time
+    pub fn new(max_concurrency: usize) -> Self {
+        Self {
+            sources: Vec::new(),
+            max_concurrency: max_concurrency.max(1),
+            seen_capacity: DEFAULT_SEEN_CAPACITY,
+        }
+    }
+
+    /// Registers a source with its polling interval
+    pub fn add_source<S: ArticleSource + 'static>(mut self, source: S, poll: PollConfig) -> Self {
+        self.sources.push((Box::new(source), poll));
+        self
+    }
+
+    /// Sets how many article ids are remembered for deduplication
+    pub fn with_seen_capacity(mut self, capacity: usize) -> Self {
+        self.seen_capacity = capacity;
+        self
+    }
+
+    /// Number of registered sources
+    pub fn source_count(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Polls until `shutdown` resolves, every source has finished, or the
+    /// receiver is dropped. In-flight fetches are abandoned on shutdown;
+    /// articles already fetched are still delivered. Returns stats in the order
+    /// the sources were added.
+    pub async fn run<F>(self, tx: mpsc::Sender<Article>, shutdown: F) -> Vec<SourceStats>
+    where
+        F: Future<Output = ()>,
+    {
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
+        let seen = Arc::new(Mutex::new(SeenIds::new(self.seen_capacity)));
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
+        let mut tasks = JoinSet::new();
+        for (index, (source, poll)) in self.sources.into_iter().enumerate() {
+            let worker = Worker {
+                source,
+                poll,
+                semaphore: semaphore.clone(),
+                seen: seen.clone(),
+                tx: tx.clone(),
+                stop: stop_rx.clone(),
+                rng: SeededRng::new(seed ^ (index as u64).wrapping_mul(0x9e3779b97f4a7c15)),
+            };
+            tasks.spawn(async move { (index, worker.run().await) });
+        }
+        drop(tx);
+
+        let mut shutdown = std::pin::pin!(shutdown);
+        let mut stopping = false;
+        let mut stats = Vec::new();
+        loop {
+            tokio::select! {
+                _ = &mut shutdown, if !stopping => {
+                    info!("Scheduler shutting down");
+                    stopping = true;
+                    let _ = stop_tx.send(true);
+                }
+                joined = tasks.join_next() => match joined {
+                    Some(Ok(result)) => stats.push(result),
+                    Some(Err(e)) => warn!("Scheduler task failed: {}", e),
+                    None => break,
+                },
+            }
+        }
+
+        stats.sort_by_key(|(index, _)| *index);
+        stats.into_iter().map(|(_, s)| s).collect()
+    }
+
+    /// Polls until SIGINT (Ctrl-C) or SIGTERM
+    pub async fn run_until_signal(self, tx: mpsc::Sender<Article>) -> Vec<SourceStats> {
+        self.run(tx, shutdown_signal()).await
+    }
+}
+
+/// Resolves on SIGINT, or on SIGTERM where the platform has it
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+                return;
+            }
+            Err(e) => warn!("Cannot listen for SIGTERM: {}", e),
+        }
+    }
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        warn!("Cannot listen for Ctrl-C: {}", e);
+        std::future::pending::<()>().await;
+    }
+}
+
+struct Worker {
+    source: Box<dyn ArticleSource>,
+    poll: PollConfig,
+    semaphore: Arc<Semaphore>,
+    seen: Arc<Mutex<SeenIds>>,
+    tx: mpsc::Sender<Article>,
+    stop: watch::Receiver<bool>,
+    rng: SeededRng,
+}
+
+impl Worker {
+    async fn run(mut self) -> SourceStats {
+        let mut stats = SourceStats {
+            name: self.source.name().to_string(),
+            ..SourceStats::default()
+        };
+
+        while !*self.stop.borrow() {
+            // Stop is checked first so a shutdown never starts another fetch
+            let permit = tokio::select! {
+                biased;
+                _ = self.stop.changed() => break,
+                permit = self.semaphore.clone().acquire_owned() => match permit {
+                    Ok(permit) => permit,
+                    Err(_) => break,
+                },
+            };
+            let result = tokio::select! {
+                biased;
+                _ = self.stop.changed() => break,
+                result = self.source.fetch() => result,
+            };
+            drop(permit);
+            stats.polls += 1;
+
+            match result {
+                Ok(articles) => {
+                    for article in articles {
+                        let is_new = self.seen.lock().map(|mut seen| seen.insert(&article.id)).unwrap_or(true);
+                        if !is_new {
+                            stats.duplicates += 1;
+                            continue;
+                        }
+                        if self.tx.send(article).await.is_err() {
+                            return stats;
+                        }
+                        stats.new_articles += 1;
+                    }
+                }
+                Err(e) => {
+                    stats.errors += 1;
+                    warn!("Polling {} failed: {:#}", stats.name, e);
+                }
+            }
+
+            if self.source.is_finished() {
+                break;
+            }
+            let delay = self.poll.next_delay(&mut self.rng);
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = self.stop.changed() => break,
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scrapers::ReplaySource;
+    use anyhow::{anyhow, Result};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::oneshot;
+
+    /// Returns a sliding window of two articles per poll, so every poll after
+    /// the first repeats one article
+    struct WindowSource {
+        name: String,
+        polls: usize,
+        active: Arc<AtomicUsize>,
+        max_active: Arc<AtomicUsize>,
+        fail_every: Option<usize>,
+    }
+
+    impl WindowSource {
+        fn new(name: &str) -> Self {
+            Self {
+                name: name.to_string(),
+                polls: 0,
+                active: Arc::new(AtomicUsize::new(0)),
+                max_active: Arc::new(AtomicUsize::new(0)),
+                fail_every: None,
+            }
+        }
+    }
+
+    /// Tracks in-flight fetches; also released when a fetch is cancelled
+    struct ActiveGuard(Arc<AtomicUsize>);
+
+    impl ActiveGuard {
+        fn enter(active: &Arc<AtomicUsize>, max_active: &AtomicUsize) -> Self {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            max_active.fetch_max(now, Ordering::SeqCst);
+            Self(active.clone())
+        }
+    }
+
+    impl Drop for ActiveGuard {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl ArticleSource for WindowSource {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn fetch(&mut self) -> Result<Vec<Article>> {
+            self.polls += 1;
+            let _active = ActiveGuard::enter(&self.active, &self.max_active);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            if self.fail_every.is_some_and(|n| self.polls.is_multiple_of(n)) {
+                return Err(anyhow!("upstream unavailable"));
+            }
+            Ok((self.polls..self.polls + 2)
+                .map(|i| Article::new(&format!("{} #{}", self.name, i), "body", &self.name, i as i64))
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deduplicates_across_polls_and_stops_on_shutdown() {
+        let (tx, mut rx) = mpsc::channel(64);
+        let (stop, stopped) = oneshot::channel::<()>();
+        let scheduler = Scheduler::new(4).add_source(
+            WindowSource::new("wire"),
+            PollConfig::every(Duration::from_millis(5)).with_jitter(Duration::from_millis(5)),
+        );
+
+        let handle = tokio::spawn(scheduler.run(tx, async {
+            let _ = stopped.await;
+        }));
+        let mut titles = Vec::new();
+        while titles.len() < 5 {
+            titles.push(rx.recv().await.unwrap().title);
+        }
+        stop.send(()).unwrap();
+        let stats = handle.await.unwrap();
+
+        assert_eq!(&titles[..5], ["wire #1", "wire #2", "wire #3", "wire #4", "wire #5"]);
+        assert_eq!(stats.len(), 1);
+        assert!(stats[0].polls >= 4);
+        assert!(stats[0].duplicates >= 3);
+        // Channel closes once every worker has stopped
+        while rx.recv().await.is_some() {}
+    }
+
+    #[tokio::test]
+    async fn test_global_concurrency_cap() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let mut scheduler = Scheduler::new(2);
+        for name in ["a", "b", "c", "d", "e"] {
+            let mut source = WindowSource::new(name);
+            source.active = active.clone();
+            source.max_active = max_active.clone();
+            scheduler = scheduler.add_source(source, PollConfig::every(Duration::ZERO));
+        }
+
+        let (tx, mut rx) = mpsc::channel(256);
+        let stats = scheduler
+            .run(tx, tokio::time::sleep(Duration::from_millis(150)))
+            .await;
+        while rx.recv().await.is_some() {}
+
+        assert_eq!(stats.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["a", "b", "c", "d", "e"]);
+        assert!(stats.iter().all(|s| s.polls >= 1));
+        assert_eq!(max_active.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_counted_and_polling_continues() {
+        let mut source = WindowSource::new("flaky");
+        source.fail_every = Some(2);
+        let (tx, mut rx) = mpsc::channel(64);
+        let stats = Scheduler::new(1)
+            .add_source(source, PollConfig::every(Duration::from_millis(1)))
+            .run(tx, tokio::time::sleep(Duration::from_millis(150)))
+            .await;
+        while rx.recv().await.is_some() {}
+
+        assert!(stats[0].errors >= 1);
+        assert!(stats[0].polls > stats[0].errors);
+        assert!(stats[0].new_articles >= 2);
+    }
+
+    #[tokio::test]
+    async fn test_finite_sources_end_the_run() {
+        let articles = vec![
+            Article::new("a", "x", "archive", 1),
+            Article::new("a", "x", "archive", 1),
+            Article::new("b", "y", "archive", 2),
+        ];
+        let (tx, mut rx) = mpsc::channel(8);
+        let stats = Scheduler::new(1)
+            .add_source(ReplaySource::new("archive", articles), PollConfig::every(Duration::from_secs(60)))
+            .run(tx, std::future::pending())
+            .await;
+
+        let mut titles = Vec::new();
+        while let Some(article) = rx.recv().await {
+            titles.push(article.title);
+        }
+        assert_eq!(titles, ["a", "b"]);
+        assert_eq!(stats[0].duplicates, 1);
+    }
+
+    #[test]
+    fn test_seen_ids_forget_oldest() {
+        let mut seen = SeenIds::new(2);
+        assert!(seen.insert("a"));
+        assert!(seen.insert("b"));
+        assert!(!seen.insert("a"));
+        assert!(seen.insert("c"));
+        assert!(seen.insert("a"));
+        assert!(!seen.insert("c"));
+    }
+}
@@ -0,0 +1,359 @@
+//! Importers for social media bulk exports
+//!
+//! Turns X/Twitter archive files (`data/tweets.js`) and Telegram Desktop channel
+//! exports (`result.json`) into articles, keeping author, engagement counts,
+//! reply/forward relationships and the original timestamps.
+
+use crate::types::Article;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Maximum number of characters used for an article title built from a post
+const TITLE_CHARS: usize = 80;
+
+/// Platform a social post was exported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocialPlatform {
+    Twitter,
+    Telegram,
+}
+
+/// A post from a social media export together with the article built from it
+#[derive(Debug, Clone)]
+pub struct SocialPost {
+    /// Platform-specific post id
+    pub id: String,
+    pub platform: SocialPlatform,
+    pub author: String,
+    /// Likes on X, total reactions on Telegram
+    pub likes: u64,
+    /// Retweets on X (Telegram exports carry no forward counts)
+    pub reposts: u64,
+    /// Id of the post this one replies to
+    pub reply_to: Option<String>,
+    /// Author of the post this one replies to, when the export records it
+    pub reply_to_author: Option<String>,
+    /// Original author of a retweet or forwarded message
+    pub forwarded_from: Option<String>,
+    pub article: Article,
+}
+
+impl SocialPost {
+    /// Copies author, engagement and relationship fields into the article metadata
+    fn with_article_metadata(mut self, language: Option<&str>) -> Self {
+        let platform = match self.platform {
+            SocialPlatform::Twitter => "twitter",
+            SocialPlatform::Telegram => "telegram",
+        };
+        let mut article = self
+            .article
+            .with_author(&self.author)
+            .with_tag(platform)
+            .with_detected_symbols()
+            .with_metadata("post_id", self.id.clone())
+            .with_metadata("likes", self.likes)
+            .with_metadata("reposts", self.reposts);
+        // X marks undetermined language as "und"
+        if let Some(language) = language.filter(|l| *l != "und") {
+            article = article.with_language(language);
+        }
+        if let Some(reply_to) = &self.reply_to {
+            article = article.with_metadata("reply_to", reply_to.clone());
+        }
+        if let Some(reply_to_author) = &self.reply_to_author {
+            article = article.with_metadata("reply_to_author", reply_to_author.clone());
+        }
+        if let Some(forwarded_from) = &self.forwarded_from {
+            article = article.with_metadata("forwarded_from", forwarded_from.clone());
+        }
+        self.article = article;
+        self
+    }
+}
+
+/// Builds a single-line title from the start of a post
+fn excerpt(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    if line.chars().count() <= TITLE_CHARS {
+        line.to_string()
+    } else {
+        let cut: String = line.chars().take(TITLE_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+#[derive(Deserialize)]
+struct Tweet {
+    id_str: String,
+    #[serde(default)]
+    full_text: String,
+    created_at: String,
+    #[serde(default)]
+    favorite_count: Value,
+    #[serde(default)]
+    retweet_count: Value,
+    #[serde(default)]
+    in_reply_to_status_id_str: Option<String>,
+    #[serde(default)]
+    in_reply_to_screen_name: Option<String>,
+    #[serde(default)]
+    lang: Option<String>,
+}
+
+/// Archive counts are usually strings ("12") but older exports use numbers
+fn count(value: &Value) -> u64 {
+    match value {
+        Value::Number(n) => n.as_u64().unwrap_or(0),
+        Value::String(s) => s.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Parses the contents of an X/Twitter archive `tweets.js` (or `tweet.js`) file.
+///
+/// The archive does not repeat the account name on every tweet, so the archive
+/// owner's handle is passed as `author`.
+pub fn parse_twitter_archive(text: &str, author: &str) -> Result<Vec<SocialPost>> {
+    // Strip the `window.YTD.tweets.part0 = ` assignment in front of the JSON array
+    let json = match text.find('[') {
+        Some(start) => &text[start..],
+        None => anyhow::bail!("no JSON array found in Twitter archive"),
+    };
+    let entries: Vec<Value> = serde_json::from_str(json).context("invalid Twitter archive JSON")?;
+    let author = author.trim_start_matches('@');
+
+    let mut posts = Vec::new();
+    for entry in entries {
+        // Newer archives wrap every tweet in {"tweet": {...}}
+        let raw = match entry {
+            Value::Object(mut map) if map.contains_key("tweet") => map.remove("tweet").unwrap(),
+            other => other,
+        };
+        let tweet: Tweet = serde_json::from_value(raw).context("malformed tweet in archive")?;
+        let timestamp = DateTime::parse_from_str(&tweet.created_at, "%a %b %d %H:%M:%S %z %Y")
+            .with_context(|| format!("invalid tweet date '{}'", tweet.created_at))?
+            .timestamp();
+
+        let forwarded_from = tweet
+            .full_text
+            .strip_prefix("RT @")
+            .and_then(|rest| rest.split(':').next())
+            .map(|name| name.to_string());
+
+        posts.push(SocialPost {
+            id: tweet.id_str.clone(),
+            platform: SocialPlatform::Twitter,
+            author: author.to_string(),
+            likes: count(&tweet.favorite_count),
+            reposts: count(&tweet.retweet_count),
+            reply_to: tweet.in_reply_to_status_id_str.filter(|id| !id.is_empty()),
+            reply_to_author: tweet.in_reply_to_screen_name.filter(|n| !n.is_empty()),
+            forwarded_from,
+            article: Article::new(
+                &excerpt(&tweet.full_text),
+                &tweet.full_text,
+                &format!("twitter/@{}", author),
+                timestamp,
+            )
+            .with_url(&format!("https://x.com/{}/status/{}", author, tweet.id_str)),
+        }
+        .with_article_metadata(tweet.lang.as_deref()));
+    }
+
+    posts.sort_by_key(|p| p.article.timestamp);
+    Ok(posts)
+}
+
+/// Reads an X/Twitter archive `tweets.js` file from disk
+pub fn import_twitter_archive(path: impl AsRef<Path>, author: &str) -> Result<Vec<SocialPost>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    parse_twitter_archive(&text, author)
+}
+
+#[derive(Deserialize)]
+struct TelegramExport {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    messages: Vec<TelegramMessage>,
+}
+
+#[derive(Deserialize)]
+struct TelegramMessage {
+    id: i64,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    date: String,
+    #[serde(default)]
+    date_unixtime: Option<String>,
+    #[serde(default)]
+    from: Option<String>,
+    #[serde(default)]
+    forwarded_from: Option<String>,
+    #[serde(default)]
+    reply_to_message_id: Option<i64>,
+    #[serde(default)]
+    text: Value,
+    #[serde(default)]
+    reactions: Vec<TelegramReaction>,
+}
+
+#[derive(Deserialize)]
+struct TelegramReaction {
+    #[serde(default)]
+    count: u64,
+}
+
+/// Flattens Telegram's text field, which is either a string or a list of
+/// strings and formatted entities (`{"type": "bold", "text": "..."}`)
+fn telegram_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .map(|part| match part {
+                Value::String(s) => s.as_str(),
+                Value::Object(map) => map.get("text").and_then(Value::as_str).unwrap_or_default(),
+                _ => "",
+            })
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+fn telegram_timestamp(message: &TelegramMessage) -> Result<i64> {
+    if let Some(unix) = message.date_unixtime.as_deref().and_then(|s| s.parse().ok()) {
+        return Ok(unix);
+    }
+    // Older exports only carry a local wall-clock date; it is taken as UTC
+    NaiveDateTime::parse_from_str(&message.date, "%Y-%m-%dT%H:%M:%S")
+        .map(|dt| dt.and_utc().timestamp())
+        .with_context(|| format!("invalid Telegram date '{}' on message {}", message.date, message.id))
+}
+
+/// Parses a Telegram Desktop channel export (`result.json`).
+///
+/// Service messages and messages without text (e.g. bare photos) are skipped.
+pub fn parse_telegram_export(json: &str) -> Result<Vec<SocialPost>> {
+    let export: TelegramExport =
+        serde_json::from_str(json).context("invalid Telegram export JSON")?;
+
+    let mut posts = Vec::new();
+    for message in &export.messages {
+        if message.kind != "message" {
+            continue;
+        }
+        let text = telegram_text(&message.text);
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        posts.push(SocialPost {
+            id: message.id.to_string(),
+            platform: SocialPlatform::Telegram,
+            author: message.from.clone().unwrap_or_else(|| export.name.clone()),
+            likes: message.reactions.iter().map(|r| r.count).sum(),
+            reposts: 0,
+            reply_to: message.reply_to_message_id.map(|id| id.to_string()),
+            reply_to_author: None,
+            forwarded_from: message.forwarded_from.clone(),
+            article: Article::new(
+                &excerpt(&text),
+                &text,
+                &format!("telegram/{}", export.name),
+                telegram_timestamp(message)?,
+            ),
+        }
+        .with_article_metadata(None));
+    }
+
+    Ok(posts)
+}
+
+/// Reads a Telegram Desktop `result.json` export from disk
+pub fn import_telegram_export(path: impl AsRef<Path>) -> Result<Vec<SocialPost>> {
+    let path = path.as_ref();
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    parse_telegram_export(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWEETS: &str = include_str!("../../tests/fixtures/social/tweets.js");
+    const TELEGRAM: &str = include_str!("../../tests/fixtures/social/telegram_result.json");
+
+    #[test]
+    fn test_parse_twitter_archive() {
+        let posts = parse_twitter_archive(TWEETS, "@desk_trader").unwrap();
+        assert_eq!(posts.len(), 3);
+
+        let first = &posts[0];
+        assert_eq!(first.platform, SocialPlatform::Twitter);
+        assert_eq!(first.author, "desk_trader");
+        assert_eq!(first.likes, 250);
+        assert_eq!(first.reposts, 42);
+        assert_eq!(first.article.timestamp, 1696435200);
+        assert_eq!(first.article.source, "twitter/@desk_trader");
+        assert_eq!(first.article.author.as_deref(), Some("desk_trader"));
+        assert_eq!(first.article.language.as_deref(), Some("en"));
+        assert_eq!(first.article.metadata["likes"], 250);
+        assert_eq!(
+            first.article.url.as_deref(),
+            Some("https://x.com/desk_trader/status/1709561234567890001")
+        );
+    }
+
+    #[test]
+    fn test_twitter_reply_and_retweet_relationships() {
+        let posts = parse_twitter_archive(TWEETS, "desk_trader").unwrap();
+        assert_eq!(posts[1].reply_to.as_deref(), Some("1709560000000000000"));
+        assert_eq!(posts[1].reply_to_author.as_deref(), Some("whale_alert"));
+        assert_eq!(posts[2].forwarded_from.as_deref(), Some("cryptodesk"));
+        assert!(posts[0].reply_to.is_none());
+    }
+
+    #[test]
+    fn test_parse_telegram_export() {
+        let posts = parse_telegram_export(TELEGRAM).unwrap();
+        // The service message and the bare photo are skipped
+        assert_eq!(posts.len(), 3);
+
+        assert_eq!(posts[0].likes, 45);
+        assert_eq!(posts[0].article.timestamp, 1696410000);
+        assert_eq!(posts[0].article.source, "telegram/Crypto Signals Daily");
+
+        // Formatted text is flattened and the local date is used when unixtime is missing
+        assert!(posts[1].article.content.contains("20,000 ETH moved"));
+        assert_eq!(posts[1].forwarded_from.as_deref(), Some("Whale Watch"));
+        assert_eq!(posts[1].article.metadata["forwarded_from"], "Whale Watch");
+        assert_eq!(posts[1].article.symbols, ["ETH"]);
+        assert_eq!(posts[1].article.timestamp, 1696415400);
+
+        assert_eq!(posts[2].reply_to.as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn test_excerpt_truncates_long_text() {
+        let long = "a".repeat(200);
+        let title = excerpt(&long);
+        assert_eq!(title.chars().count(), TITLE_CHARS + 1);
+        assert_eq!(excerpt("short\nsecond line"), "short");
+    }
+
+    #[test]
+    fn test_import_from_files() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/social");
+        assert_eq!(import_twitter_archive(format!("{}/tweets.js", dir), "x").unwrap().len(), 3);
+        assert_eq!(import_telegram_export(format!("{}/telegram_result.json", dir)).unwrap().len(), 3);
+        assert!(import_telegram_export(format!("{}/missing.json", dir)).is_err());
+    }
+}
@@ -0,0 +1,513 @@
+//! Seeded synthetic news generator
+//!
+//! Produces reproducible article streams for load tests and strategy research.
+//! The sentiment mix follows a configurable regime (bull, bear, mixed) with
+//! optional shock events, headlines come from templates that the keyword
+//! analyzer in `nlp` scores as intended, and an optional price series reacts to
+//! the generated sentiment after a configurable lag.
+
+use crate::correlation::PricePoint;
+use crate::types::Article;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+
+/// Overall sentiment mix of the generated news flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SentimentRegime {
+    /// Mostly positive news
+    Bull,
+    /// Mostly negative news
+    Bear,
+    /// Balanced positive and negative news
+    Mixed,
+}
+
+impl SentimentRegime {
+    /// Probabilities (in percent) of positive and negative articles; the rest are neutral
+    fn mix(&self) -> (u64, u64) {
+        match self {
+            SentimentRegime::Bull => (70, 10),
+            SentimentRegime::Bear => (10, 70),
+            SentimentRegime::Mixed => (35, 35),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SentimentRegime::Bull => "bull",
+            SentimentRegime::Bear => "bear",
+            SentimentRegime::Mixed => "mixed",
+        }
+    }
+}
+
+/// A burst of one-sided news, e.g. an exchange hack or an ETF approval
+#[derive(Debug, Clone)]
+pub struct ShockEvent {
+    pub start: i64,
+    pub duration_secs: i64,
+    /// True for a positive shock, false for a negative one
+    pub bullish: bool,
+    /// Symbol the shock applies to; `None` affects every symbol
+    pub symbol: Option<String>,
+}
+
+impl ShockEvent {
+    fn covers(&self, timestamp: i64, symbol: &str) -> bool {
+        timestamp >= self.start
+            && timestamp < self.start + self.duration_secs
+            && self.symbol.as_deref().is_none_or(|s| s == symbol)
+    }
+}
+
+/// Settings for the synthetic price series
+#[derive(Debug, Clone)]
+pub struct SyntheticPriceConfig {
+    pub initial_price: Decimal,
+    /// Spacing of price points in seconds
+    pub interval_secs: i64,
+    /// Delay before news is reflected in the price
+    pub lag_secs: i64,
+    /// Return per interval for each net positive article in the lagged window
+    pub sentiment_impact: Decimal,
+    /// Maximum absolute random return per interval
+    pub volatility: Decimal,
+}
+
+impl Default for SyntheticPriceConfig {
+    fn default() -> Self {
+        Self {
+            initial_price: dec!(100),
+            interval_secs: 3600,
+            lag_secs: 7200,
+            sentiment_impact: dec!(0.005),
+            volatility: dec!(0.002),
+        }
+    }
+}
+
+/// Settings for the synthetic news generator
+#[derive(Debug, Clone)]
+pub struct SyntheticNewsConfig {
+    pub seed: u64,
+    pub count: usize,
+    pub start: i64,
+    pub end: i64,
+    pub symbols: Vec<String>,
+    pub sources: Vec<String>,
+    pub regime: SentimentRegime,
+    pub shocks: Vec<ShockEvent>,
+    /// Also generate a price series per symbol when set
+    pub prices: Option<SyntheticPriceConfig>,
+}
+
+impl SyntheticNewsConfig {
+    /// Creates a configuration for `count` articles over one week of mixed BTC/ETH news
+    pub fn new(seed: u64, count: usize) -> Self {
+        let start = 1696435200;
+        Self {
+            seed,
+            count,
+            start,
+            end: start + 7 * 24 * 3600,
+            symbols: vec!["BTC".to_string(), "ETH".to_string()],
+            sources: ["CryptoNews", "BlockchainDaily", "FinanceTimes", "DeFiWatch"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            regime: SentimentRegime::Mixed,
+            shocks: Vec::new(),
+            prices: None,
+        }
+    }
+}
+
+/// Generated articles and, when requested, one price series per symbol
+#[derive(Debug, Clone)]
+pub struct SyntheticDataset {
+    pub articles: Vec<Article>,
+    pub prices: BTreeMap<String, Vec<PricePoint>>,
+}
+
+/// SplitMix64: tiny, fast and stable across platforms and releases, which
+/// matters more here than statistical quality
+pub(crate) struct SeededRng(u64);
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            0
+        } else {
+            self.next_u64() % n
+        }
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    /// Uniform value in [-1, 1] with three decimal places
+    fn unit(&mut self) -> Decimal {
+        Decimal::new(self.below(2001) as i64 - 1000, 3)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Polarity {
+    Positive,
+    Negative,
+    Neutral,
+}
+
+impl Polarity {
+    fn sign(&self) -> i64 {
+        match self {
+            Polarity::Positive => 1,
+            Polarity::Negative => -1,
+            Polarity::Neutral => 0,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Polarity::Positive => "positive",
+            Polarity::Negative => "negative",
+            Polarity::Neutral => "neutral",
+        }
+    }
+}
+
+// Templates use `{name}` and `{symbol}` placeholders. Wording is chosen so the
+// keyword analyzer in `nlp` scores each group with the intended polarity.
+const POSITIVE: &[(&str, &str)] = &[
+    ("{name} surges to record high", "{symbol} gains as institutional adoption shows strong growth."),
+    ("{name} rallies on strong inflows", "Analysts are optimistic as {symbol} demand posts a record increase."),
+    ("{symbol} breakthrough boosts investor confidence", "Developers report a successful release and excellent network growth for {name}."),
+    ("Bullish momentum builds for {name}", "{symbol} profits rise as buyers return with strong volume."),
+];
+
+const NEGATIVE: &[(&str, &str)] = &[
+    ("{name} plunges amid regulatory concerns", "{symbol} losses mount as investors fear a deeper correction."),
+    ("{symbol} drops as outflows raise fears", "Bearish traders warn of further decline and rising risk for {name}."),
+    ("Analysts warn of {name} downturn", "{symbol} falls on weak demand and growing worries about liquidity."),
+    ("{name} slides after negative report", "Pessimistic outlook for {symbol} as failure of a major lender adds to concerns."),
+];
+
+const NEUTRAL: &[(&str, &str)] = &[
+    ("{name} trading steady ahead of policy meeting", "{symbol} markets remain calm as traders await new data."),
+    ("{symbol} network processes routine maintenance", "The {name} foundation published its monthly transparency report."),
+    ("{name} volume in line with monthly average", "Exchanges list no changes to {symbol} trading pairs this week."),
+];
+
+const POSITIVE_SHOCK: &[(&str, &str)] = &[
+    ("{name} soars as spot ETF approval sparks record gains", "Breakthrough decision lifts {symbol} to new highs on massive bullish inflows."),
+    ("{symbol} surges after landmark adoption deal", "{name} jumps as a major bank announces successful integration and strong profits."),
+];
+
+const NEGATIVE_SHOCK: &[(&str, &str)] = &[
+    ("{name} crashes after major exchange hack", "{symbol} plunges as fears spread; losses and withdrawals mount amid panic."),
+    ("{symbol} collapses on fraud charges", "{name} drops sharply; investors warn of contagion risk and further crashes."),
+];
+
+fn display_name(symbol: &str) -> &str {
+    match symbol {
+        "BTC" => "Bitcoin",
+        "ETH" => "Ethereum",
+        "SOL" => "Solana",
+        "XRP" => "Ripple",
+        "ADA" => "Cardano",
+        "DOGE" => "Dogecoin",
+        "BNB" => "BNB",
+        other => other,
+    }
+}
+
+fn fill(template: &str, symbol: &str) -> String {
+    template
+        .replace("{name}", display_name(symbol))
+        .replace("{symbol}", symbol)
+}
+
+/// Generates a reproducible synthetic dataset. The same configuration always
+/// produces the same articles (including ids) and prices.
+pub fn generate(config: &SyntheticNewsConfig) -> SyntheticDataset {
+    let mut rng = SeededRng::new(config.seed);
+    let span = (config.end - config.start).max(1) as u64;
+    let default_symbol = ["MARKET".to_string()];
+    let default_source = ["Synthetic".to_string()];
+    let symbols = if config.symbols.is_empty() { &default_symbol[..] } else { &config.symbols[..] };
+    let sources = if config.sources.is_empty() { &default_source[..] } else { &config.sources[..] };
+
+    let mut timestamps: Vec<i64> = (0..config.count)
+        .map(|_| config.start + rng.below(span) as i64)
+        .collect();
+    timestamps.sort_unstable();
+
+    let mut articles = Vec::with_capacity(config.count);
+    let mut polarities: Vec<(i64, String, Polarity)> = Vec::with_capacity(config.count);
+
+    for (index, timestamp) in timestamps.into_iter().enumerate() {
+        let symbol = rng.pick(symbols).clone();
+        let source = rng.pick(sources).clone();
+
+        let shock = config.shocks.iter().find(|s| s.covers(timestamp, &symbol));
+        let (polarity, templates, is_shock) = match shock {
+            // Shocks dominate the flow but a few off-message articles still slip through
+            Some(shock) if rng.below(100) < 90 => {
+                if shock.bullish {
+                    (Polarity::Positive, POSITIVE_SHOCK, true)
+                } else {
+                    (Polarity::Negative, NEGATIVE_SHOCK, true)
+                }
+            }
+            _ => {
+                let (positive, negative) = config.regime.mix();
+                let roll = rng.below(100);
+                if roll < positive {
+                    (Polarity::Positive, POSITIVE, false)
+                } else if roll < positive + negative {
+                    (Polarity::Negative, NEGATIVE, false)
+                } else {
+                    (Polarity::Neutral, NEUTRAL, false)
+                }
+            }
+        };
+
+        let (title, content) = rng.pick(templates);
+        let mut article = Article::new(
+            &fill(title, &symbol),
+            &fill(content, &symbol),
+            &source,
+            timestamp,
+        )
+        .with_symbols(&[&symbol])
+        .with_language("en")
+        .with_tag("synthetic")
+        .with_metadata("synthetic_polarity", polarity.name())
+        .with_metadata("synthetic_regime", config.regime.name())
+        .with_metadata("synthetic_shock", is_shock);
+        // Templates repeat, so salt the content id with the seed and position;
+        // otherwise dedupe in the aggregator and scheduler drops most of the stream
+        article.id = Article::content_id(&article.id, &config.seed.to_string(), &index.to_string());
+
+        polarities.push((timestamp, symbol, polarity));
+        articles.push(article);
+    }
+
+    let prices = match &config.prices {
+        Some(price_config) => symbols
+            .iter()
+            .map(|symbol| {
+                let series = price_series(config, price_config, symbol, &polarities, &mut rng);
+                (symbol.clone(), series)
+            })
+            .collect(),
+        None => BTreeMap::new(),
+    };
+
+    SyntheticDataset { articles, prices }
+}
+
+/// Random-walk price series whose drift follows the net article polarity seen
+/// `lag_secs` earlier. Volume grows with the number of articles in that window.
+fn price_series(
+    config: &SyntheticNewsConfig,
+    price_config: &SyntheticPriceConfig,
+    symbol: &str,
+    polarities: &[(i64, String, Polarity)],
+    rng: &mut SeededRng,
+) -> Vec<PricePoint> {
+    let interval = price_config.interval_secs.max(1);
+    let mut price = price_config.initial_price;
+    let mut series = Vec::new();
+    let mut timestamp = config.start;
+
+    while timestamp <= config.end {
+        let window_end = timestamp - price_config.lag_secs;
+        let window_start = window_end - interval;
+        let (net, count) = polarities
+            .iter()
+            .filter(|(t, s, _)| s == symbol && *t > window_start && *t <= window_end)
+            .fold((0i64, 0i64), |(net, count), (_, _, p)| (net + p.sign(), count + 1));
+
+        if timestamp > config.start {
+            let change = price_config.sentiment_impact * Decimal::from(net)
+                + price_config.volatility * rng.unit();
+            price = (price * (Decimal::ONE + change)).max(dec!(0.0001)).round_dp(4);
+        }
+
+        let base_volume = dec!(1000) + Decimal::from(rng.below(500));
+        series.push(PricePoint {
+            timestamp,
+            price,
+            volume: Some(base_volume * Decimal::from(1 + count)),
+        });
+        timestamp += interval;
+    }
+
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nlp::analyze_sentiment;
+
+    #[test]
+    fn test_same_seed_same_output() {
+        let config = SyntheticNewsConfig {
+            prices: Some(SyntheticPriceConfig::default()),
+            ..SyntheticNewsConfig::new(42, 200)
+        };
+        let a = generate(&config);
+        let b = generate(&config);
+        let ids_a: Vec<&str> = a.articles.iter().map(|x| x.id.as_str()).collect();
+        let ids_b: Vec<&str> = b.articles.iter().map(|x| x.id.as_str()).collect();
+        assert_eq!(ids_a, ids_b);
+        assert_eq!(a.prices["BTC"].last().unwrap().price, b.prices["BTC"].last().unwrap().price);
+
+        let other = generate(&SyntheticNewsConfig::new(43, 200));
+        assert_ne!(a.articles[0].timestamp, other.articles[0].timestamp);
+    }
+
+    #[test]
+    fn test_article_ids_are_unique() {
+        let articles = crate::scrapers::MockDataProvider::generate_articles(1, 1000);
+        let ids: std::collections::HashSet<&str> = articles.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids.len(), 1000);
+
+        let other = crate::scrapers::MockDataProvider::generate_articles(2, 1000);
+        assert!(other.iter().all(|a| !ids.contains(a.id.as_str())));
+    }
+
+    #[test]
+    fn test_respects_count_range_symbols_and_sources() {
+        let config = SyntheticNewsConfig {
+            start: 1_000_000,
+            end: 1_086_400,
+            symbols: vec!["SOL".to_string()],
+            sources: vec!["Wire".to_string()],
+            ..SyntheticNewsConfig::new(7, 500)
+        };
+        let data = generate(&config);
+        assert_eq!(data.articles.len(), 500);
+        assert!(data.prices.is_empty());
+        assert!(data.articles.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        for article in &data.articles {
+            assert!(article.timestamp >= config.start && article.timestamp < config.end);
+            assert_eq!(article.symbols, ["SOL"]);
+            assert_eq!(article.source, "Wire");
+            assert!(article.tags.contains(&"synthetic".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_templates_score_as_intended() {
+        for (templates, positive) in [(POSITIVE, true), (POSITIVE_SHOCK, true), (NEGATIVE, false), (NEGATIVE_SHOCK, false)] {
+            for (title, content) in templates {
+                let article = Article::new(&fill(title, "BTC"), &fill(content, "BTC"), "t", 0);
+                let sentiment = analyze_sentiment(&article).unwrap();
+                assert_eq!(sentiment.positive > sentiment.negative, positive, "{}", title);
+            }
+        }
+        for (title, content) in NEUTRAL {
+            let article = Article::new(&fill(title, "ETH"), &fill(content, "ETH"), "t", 0);
+            assert!(analyze_sentiment(&article).unwrap().neutral > dec!(0.5), "{}", title);
+        }
+    }
+
+    #[test]
+    fn test_regimes_shift_the_mix() {
+        let share_positive = |regime| {
+            let data = generate(&SyntheticNewsConfig {
+                regime,
+                ..SyntheticNewsConfig::new(1, 1000)
+            });
+            data.articles
+                .iter()
+                .filter(|a| a.metadata["synthetic_polarity"] == "positive")
+                .count()
+        };
+        assert!(share_positive(SentimentRegime::Bull) > 600);
+        assert!(share_positive(SentimentRegime::Bear) < 200);
+    }
+
+    #[test]
+    fn test_shock_dominates_its_window() {
+        let start = 1696435200;
+        let config = SyntheticNewsConfig {
+            regime: SentimentRegime::Bull,
+            shocks: vec![ShockEvent {
+                start: start + 86400,
+                duration_secs: 86400,
+                bullish: false,
+                symbol: Some("BTC".to_string()),
+            }],
+            ..SyntheticNewsConfig::new(9, 2000)
+        };
+        let data = generate(&config);
+        let in_shock: Vec<&Article> = data
+            .articles
+            .iter()
+            .filter(|a| a.metadata["synthetic_shock"] == true)
+            .collect();
+        assert!(!in_shock.is_empty());
+        assert!(in_shock.iter().all(|a| a.symbols == ["BTC"]));
+        assert!(in_shock
+            .iter()
+            .all(|a| a.timestamp >= start + 86400 && a.timestamp < start + 2 * 86400));
+    }
+
+    #[test]
+    fn test_prices_react_with_lag() {
+        let start = 1696435200;
+        let config = SyntheticNewsConfig {
+            start,
+            end: start + 48 * 3600,
+            symbols: vec!["BTC".to_string()],
+            regime: SentimentRegime::Mixed,
+            shocks: vec![ShockEvent {
+                start: start + 24 * 3600,
+                duration_secs: 6 * 3600,
+                bullish: false,
+                symbol: None,
+            }],
+            prices: Some(SyntheticPriceConfig {
+                lag_secs: 3 * 3600,
+                volatility: Decimal::ZERO,
+                ..SyntheticPriceConfig::default()
+            }),
+            ..SyntheticNewsConfig::new(5, 400)
+        };
+        let data = generate(&config);
+        let series = &data.prices["BTC"];
+        assert_eq!(series.len(), 49);
+
+        let price_at = |hours: i64| {
+            series
+                .iter()
+                .find(|p| p.timestamp == start + hours * 3600)
+                .unwrap()
+                .price
+        };
+        // The shock starts at hour 24 but only reaches the price after the 3h lag
+        let before_lag = (price_at(27) - price_at(24)).abs();
+        let after_lag = price_at(27) - price_at(30);
+        assert!(after_lag > before_lag * dec!(2));
+        assert!(price_at(36) < price_at(24));
+        assert!(series.iter().all(|p| p.volume.unwrap() > Decimal::ZERO));
+    }
+}
@@ -0,0 +1,132 @@
+//! Local HTTP stand-in server used by scraper tests.
+//!
+//! Serves canned responses from a route table so network-facing code can be
+//! exercised without touching the internet.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A canned HTTP response
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Route {
+    pub fn ok(content_type: &'static str, body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            body: body.to_string(),
+        }
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            body: String::new(),
+        }
+    }
+}
+
+/// A request seen by the stand-in server
+#[derive(Debug, Clone)]
+pub struct SeenRequest {
+    pub path: String,
+    pub user_agent: Option<String>,
+}
+
+/// Handle to a running stand-in server
+pub struct TestServer {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<SeenRequest>>>,
+}
+
+impl TestServer {
+    /// Starts a server on an ephemeral port serving the given routes.
+    /// Routes are keyed by path including any query string.
+    pub async fn start(routes: Vec<(&str, Route)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let routes: Arc<HashMap<String, Route>> = Arc::new(
+            routes
+                .into_iter()
+                .map(|(path, route)| (path.to_string(), route))
+                .collect(),
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    break;
+                };
+                let routes = routes.clone();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match stream.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&buf).to_string();
+                    let path = head
+                        .lines()
+                        .next()
+                        .and_then(|line| line.split_whitespace().nth(1))
+                        .unwrap_or("/")
+                        .to_string();
+                    let user_agent = head.lines().find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("user-agent")
+                            .then(|| value.trim().to_string())
+                    });
+                    seen.lock().unwrap().push(SeenRequest {
+                        path: path.clone(),
+                        user_agent,
+                    });
+
+                    let route = routes.get(&path).cloned().unwrap_or(Route::status(404));
+                    let response = format!(
+                        "HTTP/1.1 {} X\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        route.status,
+                        route.content_type,
+                        route.body.len(),
+                        route.body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+
+        Self {
+            base_url: format!("http://{}", addr),
+            requests,
+        }
+    }
+
+    /// Returns an absolute URL for a path on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Returns every request received so far
+    pub fn requests(&self) -> Vec<SeenRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Counts requests received for a given path
+    pub fn hits(&self, path: &str) -> usize {
+        self.requests().iter().filter(|r| r.path == path).count()
+    }
+}
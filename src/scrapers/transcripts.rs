@@ -0,0 +1,448 @@
+//! Earnings call transcript importer
+//!
+//! Parses plain-text and HTML earnings call transcripts into speaker segments.
+//! Prepared remarks are separated from the Q&A session and every passage is
+//! attributed to a speaker and a role (CEO, CFO, other executive, analyst,
+//! operator), so management tone can be compared with analyst questions.
+
+use super::html::html_to_text;
+use crate::nlp::analyze_sentiment;
+use crate::types::{Article, SentimentScore};
+use anyhow::Result;
+use rust_decimal::Decimal;
+
+/// Role of a speaker on an earnings call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeakerRole {
+    Ceo,
+    Cfo,
+    /// Any other company representative (COO, investor relations, ...)
+    Executive,
+    Analyst,
+    Operator,
+    Unknown,
+}
+
+impl SpeakerRole {
+    /// Returns true for company representatives
+    pub fn is_management(&self) -> bool {
+        matches!(self, SpeakerRole::Ceo | SpeakerRole::Cfo | SpeakerRole::Executive)
+    }
+
+    /// Short label used in article titles
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpeakerRole::Ceo => "CEO",
+            SpeakerRole::Cfo => "CFO",
+            SpeakerRole::Executive => "Executive",
+            SpeakerRole::Analyst => "Analyst",
+            SpeakerRole::Operator => "Operator",
+            SpeakerRole::Unknown => "Speaker",
+        }
+    }
+
+    fn from_title(title: &str, analyst_roster: bool) -> Self {
+        let lower = title.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        if analyst_roster || words.contains(&"analyst") {
+            SpeakerRole::Analyst
+        } else if lower.contains("chief executive") || words.contains(&"ceo") {
+            SpeakerRole::Ceo
+        } else if lower.contains("chief financial") || words.contains(&"cfo") {
+            SpeakerRole::Cfo
+        } else {
+            SpeakerRole::Executive
+        }
+    }
+}
+
+/// Part of the call a segment belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallSection {
+    PreparedRemarks,
+    QuestionAndAnswer,
+}
+
+/// A call participant listed in the roster or introduced by a speaker header
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant {
+    pub name: String,
+    /// Job title for executives, firm for analysts
+    pub title: Option<String>,
+    pub role: SpeakerRole,
+}
+
+/// One uninterrupted passage by a single speaker
+#[derive(Debug, Clone)]
+pub struct TranscriptSegment {
+    pub index: usize,
+    pub section: CallSection,
+    pub speaker: String,
+    pub role: SpeakerRole,
+    pub text: String,
+}
+
+/// Average sentiment of the main speaker groups on a call
+#[derive(Debug, Clone)]
+pub struct CallSentiment {
+    pub management_prepared: Option<SentimentScore>,
+    pub management_answers: Option<SentimentScore>,
+    pub analyst_questions: Option<SentimentScore>,
+}
+
+/// A parsed earnings call transcript
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    pub title: String,
+    pub source: String,
+    pub timestamp: i64,
+    pub participants: Vec<Participant>,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Roster {
+    Company,
+    Analysts,
+}
+
+fn marker(line: &str) -> String {
+    line.trim_end_matches(':').trim().to_lowercase()
+}
+
+fn roster_header(line: &str) -> Option<Roster> {
+    match marker(line).as_str() {
+        "company participants" | "corporate participants" | "executives"
+        | "company representatives" => Some(Roster::Company),
+        "conference call participants" | "call participants" | "analysts" => {
+            Some(Roster::Analysts)
+        }
+        _ => None,
+    }
+}
+
+fn section_header(line: &str) -> Option<CallSection> {
+    match marker(line).as_str() {
+        "prepared remarks" | "presentation" => Some(CallSection::PreparedRemarks),
+        "question-and-answer session" | "question and answer session" | "questions and answers"
+        | "questions & answers" | "q&a" | "q&a session" => Some(CallSection::QuestionAndAnswer),
+        _ => None,
+    }
+}
+
+/// Heuristic for "Jane Doe": 1-5 words, each starting with an uppercase letter
+fn looks_like_name(text: &str) -> bool {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    !words.is_empty()
+        && words.len() <= 5
+        && words
+            .iter()
+            .all(|w| w.chars().next().is_some_and(|c| c.is_uppercase()))
+}
+
+/// Splits "Name - Title", "Name -- Title" or "Name — Title" into its parts
+fn split_name_title(line: &str, separators: &[&str]) -> Option<(String, String)> {
+    separators.iter().find_map(|sep| {
+        let (name, title) = line.split_once(sep)?;
+        let (name, title) = (name.trim(), title.trim());
+        (looks_like_name(name) && !title.is_empty()).then(|| (name.to_string(), title.to_string()))
+    })
+}
+
+fn average(scores: &[SentimentScore]) -> Option<SentimentScore> {
+    if scores.is_empty() {
+        return None;
+    }
+    let n = Decimal::from(scores.len());
+    Some(SentimentScore {
+        positive: scores.iter().map(|s| s.positive).sum::<Decimal>() / n,
+        negative: scores.iter().map(|s| s.negative).sum::<Decimal>() / n,
+        neutral: scores.iter().map(|s| s.neutral).sum::<Decimal>() / n,
+    })
+}
+
+impl Transcript {
+    /// Parses a plain-text transcript.
+    ///
+    /// Speakers are recognised from a participant roster ("Company Participants",
+    /// "Conference Call Participants"), from "Name -- Title" header lines, from a
+    /// line holding only a known name or "Operator", and from "Name: text" lines
+    /// for known names. Text before the first speaker is ignored.
+    pub fn parse_text(text: &str, source: &str, timestamp: i64) -> Result<Self> {
+        let title = text
+            .lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+            .unwrap_or_default()
+            .to_string();
+
+        let mut participants: Vec<Participant> = Vec::new();
+        let mut segments: Vec<TranscriptSegment> = Vec::new();
+        let mut section = CallSection::PreparedRemarks;
+        let mut roster: Option<Roster> = None;
+        let mut current: Option<TranscriptSegment> = None;
+
+        let flush = |current: &mut Option<TranscriptSegment>, segments: &mut Vec<TranscriptSegment>| {
+            if let Some(mut segment) = current.take() {
+                segment.text = segment.text.trim().to_string();
+                if !segment.text.is_empty() {
+                    segment.index = segments.len();
+                    segments.push(segment);
+                }
+            }
+        };
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some(kind) = roster_header(line) {
+                roster = Some(kind);
+                continue;
+            }
+            if let Some(kind) = roster {
+                if let Some((name, title)) = split_name_title(line, &[" -- ", " — ", " - ", ", "]) {
+                    participants.push(Participant {
+                        role: SpeakerRole::from_title(&title, kind == Roster::Analysts),
+                        name,
+                        title: Some(title),
+                    });
+                    continue;
+                }
+                roster = None;
+            }
+            if let Some(kind) = section_header(line) {
+                flush(&mut current, &mut segments);
+                section = kind;
+                continue;
+            }
+
+            // Work out whether this line starts a new speaker turn
+            let mut turn: Option<(String, SpeakerRole, String)> = None;
+            if line.eq_ignore_ascii_case("operator") {
+                turn = Some(("Operator".to_string(), SpeakerRole::Operator, String::new()));
+            } else if let Some(p) = participants.iter().find(|p| p.name == line) {
+                turn = Some((p.name.clone(), p.role, String::new()));
+            } else if let Some((name, title)) = split_name_title(line, &[" -- ", " — "]) {
+                let role = match participants.iter().find(|p| p.name == name) {
+                    Some(p) => p.role,
+                    None => {
+                        let role = SpeakerRole::from_title(&title, false);
+                        participants.push(Participant {
+                            name: name.clone(),
+                            title: Some(title),
+                            role,
+                        });
+                        role
+                    }
+                };
+                turn = Some((name, role, String::new()));
+            } else if let Some((name, rest)) = line.split_once(':') {
+                let name = name.trim();
+                if name.eq_ignore_ascii_case("operator") {
+                    turn = Some(("Operator".to_string(), SpeakerRole::Operator, rest.to_string()));
+                } else if let Some(p) = participants.iter().find(|p| p.name == name) {
+                    turn = Some((p.name.clone(), p.role, rest.to_string()));
+                }
+            }
+
+            match turn {
+                Some((speaker, role, first_text)) => {
+                    flush(&mut current, &mut segments);
+                    current = Some(TranscriptSegment {
+                        index: 0,
+                        section,
+                        speaker,
+                        role,
+                        text: format!("{}\n", first_text.trim()),
+                    });
+                }
+                None => {
+                    if let Some(segment) = current.as_mut() {
+                        segment.text.push_str(line);
+                        segment.text.push('\n');
+                    }
+                }
+            }
+        }
+        flush(&mut current, &mut segments);
+
+        if segments.is_empty() {
+            anyhow::bail!("no speaker segments found in transcript '{}'", title);
+        }
+
+        Ok(Self {
+            title,
+            source: source.to_string(),
+            timestamp,
+            participants,
+            segments,
+        })
+    }
+
+    /// Parses an HTML transcript page
+    pub fn parse_html(html: &str, source: &str, timestamp: i64) -> Result<Self> {
+        Self::parse_text(&html_to_text(html), source, timestamp)
+    }
+
+    /// Returns the segments spoken by company representatives
+    pub fn management_segments(&self) -> impl Iterator<Item = &TranscriptSegment> {
+        self.segments.iter().filter(|s| s.role.is_management())
+    }
+
+    /// Returns the segments spoken by analysts
+    pub fn analyst_segments(&self) -> impl Iterator<Item = &TranscriptSegment> {
+        self.segments.iter().filter(|s| s.role == SpeakerRole::Analyst)
+    }
+
+    /// Builds one article per segment so each passage can be scored separately
+    pub fn to_articles(&self) -> Vec<Article> {
+        self.segments
+            .iter()
+            .map(|segment| {
+                let section = match segment.section {
+                    CallSection::PreparedRemarks => "prepared_remarks",
+                    CallSection::QuestionAndAnswer => "q_and_a",
+                };
+                Article::new(
+                    &format!("{} - {} ({})", self.title, segment.speaker, segment.role.label()),
+                    &segment.text,
+                    &self.source,
+                    self.timestamp,
+                )
+                .with_author(&segment.speaker)
+                .with_tag("earnings-call")
+                .with_metadata("speaker_role", segment.role.label())
+                .with_metadata("call_section", section)
+                .with_metadata("segment_index", segment.index)
+                .with_metadata("call_title", self.title.clone())
+            })
+            .collect()
+    }
+
+    /// Scores management prepared remarks, management answers and analyst
+    /// questions separately and returns the average sentiment of each group
+    pub fn sentiment_comparison(&self) -> Result<CallSentiment> {
+        let mut prepared = Vec::new();
+        let mut answers = Vec::new();
+        let mut questions = Vec::new();
+
+        for (segment, article) in self.segments.iter().zip(self.to_articles()) {
+            // Score only the spoken text, not the speaker header in the title
+            let article = Article::new("", &article.content, &article.source, article.timestamp);
+            let bucket = match (segment.role, segment.section) {
+                (role, CallSection::PreparedRemarks) if role.is_management() => &mut prepared,
+                (role, CallSection::QuestionAndAnswer) if role.is_management() => &mut answers,
+                (SpeakerRole::Analyst, CallSection::QuestionAndAnswer) => &mut questions,
+                _ => continue,
+            };
+            bucket.push(analyze_sentiment(&article)?);
+        }
+
+        Ok(CallSentiment {
+            management_prepared: average(&prepared),
+            management_answers: average(&answers),
+            analyst_questions: average(&questions),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = include_str!("../../tests/fixtures/transcripts/acme_q3_2023.txt");
+    const HTML: &str = include_str!("../../tests/fixtures/transcripts/globex_q2_2023.html");
+
+    #[test]
+    fn test_roster_roles() {
+        let transcript = Transcript::parse_text(TEXT, "transcripts", 1698350400).unwrap();
+        let role_of = |name: &str| {
+            transcript
+                .participants
+                .iter()
+                .find(|p| p.name == name)
+                .map(|p| p.role)
+        };
+        assert_eq!(role_of("Jane Doe"), Some(SpeakerRole::Ceo));
+        assert_eq!(role_of("John Smith"), Some(SpeakerRole::Cfo));
+        assert_eq!(role_of("Mary Major"), Some(SpeakerRole::Executive));
+        assert_eq!(role_of("Alex Analyst"), Some(SpeakerRole::Analyst));
+        assert_eq!(transcript.title, "Acme Robotics (ACME) Q3 2023 Earnings Call Transcript");
+    }
+
+    #[test]
+    fn test_text_segments_and_sections() {
+        let transcript = Transcript::parse_text(TEXT, "transcripts", 1698350400).unwrap();
+        let speakers: Vec<(&str, CallSection)> = transcript
+            .segments
+            .iter()
+            .map(|s| (s.speaker.as_str(), s.section))
+            .collect();
+        assert_eq!(
+            speakers,
+            [
+                ("Operator", CallSection::PreparedRemarks),
+                ("Mary Major", CallSection::PreparedRemarks),
+                ("Jane Doe", CallSection::PreparedRemarks),
+                ("John Smith", CallSection::PreparedRemarks),
+                ("Operator", CallSection::QuestionAndAnswer),
+                ("Alex Analyst", CallSection::QuestionAndAnswer),
+                ("Jane Doe", CallSection::QuestionAndAnswer),
+                ("Bea Banker", CallSection::QuestionAndAnswer),
+                ("John Smith", CallSection::QuestionAndAnswer),
+                ("Operator", CallSection::QuestionAndAnswer),
+            ]
+        );
+        // Multi-line turns are kept together and inline "Name: text" turns keep their text
+        assert!(transcript.segments[2].text.contains("breakthrough"));
+        assert!(transcript.segments[7].text.starts_with("Can you talk"));
+        assert_eq!(transcript.segments[9].index, 9);
+    }
+
+    #[test]
+    fn test_html_speaker_headers() {
+        let transcript = Transcript::parse_html(HTML, "fool.com", 1690000000).unwrap();
+        assert_eq!(transcript.title, "Globex Corp (GBX) Q2 2023 Earnings Call Transcript");
+        assert_eq!(transcript.segments.len(), 6);
+
+        let question = &transcript.segments[4];
+        assert_eq!(question.speaker, "Tom Ticker");
+        assert_eq!(question.role, SpeakerRole::Analyst);
+        assert_eq!(question.section, CallSection::QuestionAndAnswer);
+
+        assert_eq!(transcript.segments[5].role, SpeakerRole::Ceo);
+        assert_eq!(transcript.management_segments().count(), 3);
+        assert_eq!(transcript.analyst_segments().count(), 1);
+    }
+
+    #[test]
+    fn test_segment_articles() {
+        let transcript = Transcript::parse_text(TEXT, "transcripts", 1698350400).unwrap();
+        let articles = transcript.to_articles();
+        assert_eq!(articles.len(), transcript.segments.len());
+        assert!(articles[2].title.ends_with("Jane Doe (CEO)"));
+        assert_eq!(articles[2].timestamp, 1698350400);
+        assert_eq!(articles[2].source, "transcripts");
+        assert_eq!(articles[2].author.as_deref(), Some("Jane Doe"));
+        assert_eq!(articles[2].metadata["speaker_role"], "CEO");
+        assert_eq!(articles[6].metadata["call_section"], "q_and_a");
+    }
+
+    #[test]
+    fn test_management_vs_analyst_sentiment() {
+        let transcript = Transcript::parse_text(TEXT, "transcripts", 1698350400).unwrap();
+        let comparison = transcript.sentiment_comparison().unwrap();
+
+        let management = comparison.management_prepared.unwrap();
+        let analysts = comparison.analyst_questions.unwrap();
+        assert!(management.positive > management.negative);
+        assert!(analysts.negative > analysts.positive);
+        assert!(comparison.management_answers.is_some());
+    }
+
+    #[test]
+    fn test_rejects_text_without_speakers() {
+        assert!(Transcript::parse_text("Just a paragraph of prose.", "x", 0).is_err());
+    }
+}
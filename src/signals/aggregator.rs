@@ -0,0 +1,446 @@
+//! Rolling per-symbol sentiment aggregation
+//!
+//! A single article's score is noisy, so the aggregator keeps every scored
+//! article for a symbol inside a time window and combines them into one
+//! sentiment. Each observation is weighted by:
+//!
+//! - **time decay**: `0.5^(age / half_life_secs)`, so older news fades out
+//! - **source**: a configurable per-source weight (1.0 by default)
+//!
+//! The weighted mean is then blended with `neutral_prior` pseudo-articles of
+//! pure neutral sentiment, so a lone headline cannot push the aggregate past
+//! the BUY/SELL thresholds on its own; confidence grows with the article count.
+
+use crate::nlp::extract_entities;
+use crate::types::{Article, SentimentScore};
+use anyhow::{bail, Result};
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Aggregation settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AggregatorConfig {
+    /// Observations older than this are dropped and never aggregated
+    pub window_secs: i64,
+    /// Age at which an observation counts half as much as a fresh one
+    pub half_life_secs: i64,
+    /// Weight of neutral pseudo-articles mixed into every aggregate
+    pub neutral_prior: Decimal,
+    /// Weight for sources missing from `source_weights`
+    pub default_source_weight: Decimal,
+    /// Per-source weights, keyed by lower-case source name
+    pub source_weights: BTreeMap<String, Decimal>,
+}
+
+impl Default for AggregatorConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 6 * 3600,
+            half_life_secs: 2 * 3600,
+            neutral_prior: dec!(1),
+            default_source_weight: dec!(1),
+            source_weights: BTreeMap::new(),
+        }
+    }
+}
+
+impl AggregatorConfig {
+    /// Sets the retention window
+    pub fn with_window_secs(mut self, window_secs: i64) -> Self {
+        self.window_secs = window_secs;
+        self
+    }
+
+    /// Sets the decay half-life
+    pub fn with_half_life_secs(mut self, half_life_secs: i64) -> Self {
+        self.half_life_secs = half_life_secs;
+        self
+    }
+
+    /// Sets the neutral pseudo-article weight (0 disables count weighting)
+    pub fn with_neutral_prior(mut self, neutral_prior: Decimal) -> Self {
+        self.neutral_prior = neutral_prior;
+        self
+    }
+
+    /// Sets the weight of one source
+    pub fn with_source_weight(mut self, source: &str, weight: Decimal) -> Self {
+        self.source_weights.insert(source.to_lowercase(), weight);
+        self
+    }
+
+    /// Weight applied to articles from `source`
+    pub fn source_weight(&self, source: &str) -> Decimal {
+        self.source_weights
+            .get(&source.to_lowercase())
+            .copied()
+            .unwrap_or(self.default_source_weight)
+    }
+
+    /// Checks the windows are positive and no weight is negative
+    pub fn validate(&self) -> Result<()> {
+        if self.window_secs <= 0 {
+            bail!("window_secs must be positive, got {}", self.window_secs);
+        }
+        if self.half_life_secs <= 0 {
+            bail!("half_life_secs must be positive, got {}", self.half_life_secs);
+        }
+        if self.neutral_prior < Decimal::ZERO || self.default_source_weight < Decimal::ZERO {
+            bail!("neutral_prior and default_source_weight must not be negative");
+        }
+        if let Some((source, weight)) = self.source_weights.iter().find(|(_, w)| **w < Decimal::ZERO) {
+            bail!("weight for source {} must not be negative, got {}", source, weight);
+        }
+        Ok(())
+    }
+}
+
+/// Aggregated sentiment of one symbol over a window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregateSentiment {
+    pub symbol: String,
+    /// Decayed, weighted and prior-blended sentiment; components sum to 1
+    pub sentiment: SentimentScore,
+    /// Articles inside the window
+    pub article_count: usize,
+    /// Sum of decay and source weights of those articles
+    pub effective_weight: Decimal,
+    /// Time the aggregate was computed for
+    pub as_of: i64,
+    pub window_secs: i64,
+    /// Ids of the articles inside the window, oldest first
+    pub article_ids: Vec<String>,
+}
+
+/// Article count and undecayed sentiment of one fixed time bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SentimentBucket {
+    /// Bucket covers `(start, start + bucket_secs]`
+    pub start: i64,
+    pub article_count: usize,
+    /// Source-weighted mean of `positive - negative`, `None` without articles
+    pub net_sentiment: Option<Decimal>,
+}
+
+#[derive(Debug, Clone)]
+struct Observation {
+    timestamp: i64,
+    source: String,
+    article_id: String,
+    sentiment: SentimentScore,
+}
+
+/// Keeps a rolling window of scored articles per symbol
+#[derive(Debug, Clone, Default)]
+pub struct SentimentAggregator {
+    config: AggregatorConfig,
+    observations: HashMap<String, Vec<Observation>>,
+}
+
+impl SentimentAggregator {
+    pub fn new(config: AggregatorConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            observations: HashMap::new(),
+        })
+    }
+
+    pub fn config(&self) -> &AggregatorConfig {
+        &self.config
+    }
+
+    /// Records a scored article for `symbol`
+    pub fn observe(
+        &mut self,
+        symbol: &str,
+        sentiment: &SentimentScore,
+        source: &str,
+        timestamp: i64,
+        article_id: &str,
+    ) {
+        let window = self.config.window_secs;
+        let entries = self.observations.entry(symbol.to_uppercase()).or_default();
+        if entries.iter().any(|o| o.article_id == article_id && !article_id.is_empty()) {
+            return;
+        }
+        entries.push(Observation {
+            timestamp,
+            source: source.to_string(),
+            article_id: article_id.to_string(),
+            sentiment: sentiment.clone(),
+        });
+        // Arrival order is not guaranteed; keep entries sorted and trimmed
+        entries.sort_by_key(|o| o.timestamp);
+        let newest = entries.last().map(|o| o.timestamp).unwrap_or(timestamp);
+        entries.retain(|o| o.timestamp > newest - window);
+    }
+
+    /// Records a scored article for every symbol it mentions. Uses
+    /// `article.symbols` when set, otherwise the symbols detected in the
+    /// title and content. Returns the symbols it was recorded under.
+    pub fn observe_article(&mut self, article: &Article, sentiment: &SentimentScore) -> Vec<String> {
+        let symbols = if article.symbols.is_empty() {
+            extract_entities(&format!("{} {}", article.title, article.content))
+        } else {
+            article.symbols.clone()
+        };
+        for symbol in &symbols {
+            self.observe(symbol, sentiment, &article.source, article.timestamp, &article.id);
+        }
+        symbols
+    }
+
+    /// Symbols with at least one retained observation, sorted
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self
+            .observations
+            .iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        symbols.sort();
+        symbols
+    }
+
+    /// Aggregate for `symbol` over the configured window ending at `now`
+    pub fn aggregate(&self, symbol: &str, now: i64) -> Option<AggregateSentiment> {
+        self.aggregate_window(symbol, now, self.config.window_secs)
+    }
+
+    /// Aggregate over a custom window ending at `now`. Windows longer than the
+    /// configured one only see what is still retained. Articles dated after
+    /// `now` are ignored, so replays can query past points in time.
+    pub fn aggregate_window(&self, symbol: &str, now: i64, window_secs: i64) -> Option<AggregateSentiment> {
+        let entries = self.observations.get(&symbol.to_uppercase())?;
+        let in_window: Vec<&Observation> = entries
+            .iter()
+            .filter(|o| o.timestamp <= now && o.timestamp > now - window_secs)
+            .collect();
+        if in_window.is_empty() {
+            return None;
+        }
+
+        let mut total = Decimal::ZERO;
+        let mut positive = Decimal::ZERO;
+        let mut negative = Decimal::ZERO;
+        let mut neutral = Decimal::ZERO;
+        for observation in &in_window {
+            let weight = self.decay(now - observation.timestamp) * self.config.source_weight(&observation.source);
+            total += weight;
+            positive += weight * observation.sentiment.positive;
+            negative += weight * observation.sentiment.negative;
+            neutral += weight * observation.sentiment.neutral;
+        }
+
+        let denominator = total + self.config.neutral_prior;
+        let sentiment = if denominator.is_zero() {
+            SentimentScore {
+                positive: Decimal::ZERO,
+                negative: Decimal::ZERO,
+                neutral: Decimal::ONE,
+            }
+        } else {
+            let positive = (positive / denominator).round_dp(6);
+            let negative = (negative / denominator).round_dp(6);
+            SentimentScore {
+                positive,
+                negative,
+                neutral: Decimal::ONE - positive - negative,
+            }
+        };
+
+        Some(AggregateSentiment {
+            symbol: symbol.to_uppercase(),
+            sentiment,
+            article_count: in_window.len(),
+            effective_weight: total.round_dp(6),
+            as_of: now,
+            window_secs,
+            article_ids: in_window
+                .iter()
+                .filter(|o| !o.article_id.is_empty())
+                .map(|o| o.article_id.clone())
+                .collect(),
+        })
+    }
+
+    /// Splits the `count * bucket_secs` seconds ending at `now` into fixed
+    /// buckets, oldest first. Unlike [`aggregate`](Self::aggregate) no decay
+    /// or prior is applied, so buckets can be compared with each other.
+    pub fn buckets(&self, symbol: &str, now: i64, bucket_secs: i64, count: usize) -> Vec<SentimentBucket> {
+        let entries = self.observations.get(&symbol.to_uppercase());
+        (0..count as i64)
+            .map(|i| {
+                let start = now - (count as i64 - i) * bucket_secs;
+                let mut articles = 0;
+                let mut weight = Decimal::ZERO;
+                let mut net = Decimal::ZERO;
+                for o in entries.into_iter().flatten() {
+                    if o.timestamp > start && o.timestamp <= start + bucket_secs {
+                        let w = self.config.source_weight(&o.source);
+                        articles += 1;
+                        weight += w;
+                        net += w * (o.sentiment.positive - o.sentiment.negative);
+                    }
+                }
+                SentimentBucket {
+                    start,
+                    article_count: articles,
+                    net_sentiment: (!weight.is_zero()).then(|| (net / weight).round_dp(6)),
+                }
+            })
+            .collect()
+    }
+
+    /// Aggregates for every symbol that has articles in the window at `now`
+    pub fn snapshot(&self, now: i64) -> Vec<AggregateSentiment> {
+        self.symbols()
+            .iter()
+            .filter_map(|symbol| self.aggregate(symbol, now))
+            .collect()
+    }
+
+    /// `0.5^(age / half_life)` in `Decimal`, so weights do not depend on the
+    /// platform's float maths
+    fn decay(&self, age_secs: i64) -> Decimal {
+        let age = age_secs.max(0);
+        let half_life = self.config.half_life_secs;
+        let halvings = age / half_life;
+        // 0.5^32 already rounds to zero at 8 decimal places
+        if halvings >= 32 {
+            return Decimal::ZERO;
+        }
+        let fraction = Decimal::from(age % half_life) / Decimal::from(half_life);
+        (dec!(0.5).powi(halvings) * dec!(0.5).powd(fraction)).round_dp(8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bullish() -> SentimentScore {
+        SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
+        }
+    }
+
+    fn bearish() -> SentimentScore {
+        SentimentScore {
+            positive: dec!(0.05),
+            negative: dec!(0.85),
+            neutral: dec!(0.10),
+        }
+    }
+
+    #[test]
+    fn test_single_article_is_damped_by_prior() {
+        let mut aggregator = SentimentAggregator::new(AggregatorConfig::default()).unwrap();
+        aggregator.observe("btc", &bullish(), "CryptoNews", 1000, "a1");
+
+        let aggregate = aggregator.aggregate("BTC", 1000).unwrap();
+        assert_eq!(aggregate.article_count, 1);
+        assert_eq!(aggregate.sentiment.positive, dec!(0.425));
+        assert_eq!(
+            aggregate.sentiment.positive + aggregate.sentiment.negative + aggregate.sentiment.neutral,
+            Decimal::ONE
+        );
+
+        for (i, id) in ["a2", "a3", "a4"].iter().enumerate() {
+            aggregator.observe("BTC", &bullish(), "CryptoNews", 1000 + i as i64, id);
+        }
+        let aggregate = aggregator.aggregate("BTC", 1003).unwrap();
+        assert_eq!(aggregate.article_count, 4);
+        assert!(aggregate.sentiment.positive > dec!(0.65));
+        assert_eq!(aggregate.article_ids, ["a1", "a2", "a3", "a4"]);
+    }
+
+    #[test]
+    fn test_decay_and_window() {
+        let config = AggregatorConfig::default()
+            .with_neutral_prior(Decimal::ZERO)
+            .with_half_life_secs(3600)
+            .with_window_secs(4 * 3600);
+        let mut aggregator = SentimentAggregator::new(config).unwrap();
+        aggregator.observe("ETH", &bearish(), "A", 0, "old");
+        aggregator.observe("ETH", &bullish(), "A", 3600, "new");
+
+        // The older bearish article counts half as much
+        let aggregate = aggregator.aggregate("ETH", 3600).unwrap();
+        assert_eq!(aggregate.effective_weight, dec!(1.5));
+        assert!(aggregate.sentiment.positive > aggregate.sentiment.negative);
+
+        // Shorter window, future articles and expiry
+        assert_eq!(aggregator.aggregate_window("ETH", 3600, 60).unwrap().article_count, 1);
+        assert_eq!(aggregator.aggregate("ETH", 1800).unwrap().article_ids, ["old"]);
+        assert!(aggregator.aggregate("ETH", 9 * 3600).is_none());
+        aggregator.observe("ETH", &bullish(), "A", 4 * 3600, "newest");
+        assert_eq!(aggregator.aggregate("ETH", 4 * 3600).unwrap().article_ids, ["new", "newest"]);
+        // "old" was pruned, so even a longer window cannot see it
+        assert_eq!(aggregator.aggregate_window("ETH", 4 * 3600, 10 * 3600).unwrap().article_count, 2);
+    }
+
+    #[test]
+    fn test_decay_is_exact_decimal() {
+        let config = AggregatorConfig::default().with_half_life_secs(3600);
+        let aggregator = SentimentAggregator::new(config).unwrap();
+        assert_eq!(aggregator.decay(-5), Decimal::ONE);
+        assert_eq!(aggregator.decay(0), Decimal::ONE);
+        assert_eq!(aggregator.decay(7200), dec!(0.25));
+        // 0.5^0.5 and 0.5^2.5
+        assert_eq!(aggregator.decay(1800), dec!(0.70710678));
+        assert_eq!(aggregator.decay(9000), dec!(0.1767767));
+        assert_eq!(aggregator.decay(i64::MAX), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_source_weights_and_duplicates() {
+        let config = AggregatorConfig::default()
+            .with_neutral_prior(Decimal::ZERO)
+            .with_source_weight("Reddit", dec!(0.25));
+        let mut aggregator = SentimentAggregator::new(config).unwrap();
+        aggregator.observe("BTC", &bullish(), "reddit", 100, "r1");
+        aggregator.observe("BTC", &bullish(), "reddit", 100, "r1");
+        aggregator.observe("BTC", &bearish(), "Reuters", 100, "n1");
+
+        let aggregate = aggregator.aggregate("BTC", 100).unwrap();
+        assert_eq!(aggregate.article_count, 2);
+        assert_eq!(aggregate.effective_weight, dec!(1.25));
+        assert!(aggregate.sentiment.negative > aggregate.sentiment.positive);
+
+        assert!(SentimentAggregator::new(AggregatorConfig::default().with_window_secs(0)).is_err());
+    }
+
+    #[test]
+    fn test_buckets() {
+        let mut aggregator = SentimentAggregator::new(AggregatorConfig::default()).unwrap();
+        aggregator.observe("BTC", &bullish(), "A", 100, "a");
+        aggregator.observe("BTC", &bearish(), "A", 250, "b");
+        aggregator.observe("BTC", &bullish(), "A", 300, "c");
+
+        let buckets = aggregator.buckets("BTC", 300, 100, 3);
+        assert_eq!(buckets.iter().map(|b| b.start).collect::<Vec<_>>(), [0, 100, 200]);
+        assert_eq!(buckets.iter().map(|b| b.article_count).collect::<Vec<_>>(), [1, 0, 2]);
+        assert_eq!(buckets[0].net_sentiment, Some(dec!(0.8)));
+        assert_eq!(buckets[1].net_sentiment, None);
+        assert_eq!(buckets[2].net_sentiment, Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_observe_article_uses_symbols() {
+        let mut aggregator = SentimentAggregator::default();
+        let article = Article::new("Bitcoin and Ethereum rally", "BTC and ETH gain", "CryptoNews", 50);
+        let symbols = aggregator.observe_article(&article, &bullish());
+        assert!(symbols.contains(&"BTC".to_string()));
+        let mut expected = symbols.clone();
+        expected.sort();
+        assert_eq!(aggregator.symbols(), expected);
+        assert_eq!(aggregator.snapshot(50).len(), symbols.len());
+    }
+}
@@ -0,0 +1,565 @@
+//! Append-only audit log of emitted signals
+//!
+//! Every signal is written to a JSON Lines file together with the inputs
+//! that produced it and the version of the [`SignalConfig`] in force. Each
+//! config version is written once, the first time a signal uses it:
+//!
+//! ```text
+//! {"entry":"config","version":"9f2c…","config":{…}}
+//! {"entry":"signal","sequence":1,"recorded_at":1700000000,"config_version":"9f2c…","inputs":{"kind":"sentiment",…},"signal":{…}}
+//! ```
+//!
+//! [`AuditTrail`] loads a log back, answers queries by symbol and time range,
+//! and replays records: the signal is regenerated from the logged inputs and
+//! config, and must come out with the same id. Signals from generators that
+//! need more state than a record holds (momentum, spillover, strategies) are
+//! logged as [`SignalInputs::External`] and cannot be replayed.
+//!
+//! A log has a single writer: [`AuditLog::open`] takes an exclusive lock on
+//! the file for as long as the `AuditLog` lives. An entry cut short by a
+//! crash is dropped when the log is next opened.
+
+use super::aggregator::AggregateSentiment;
+use super::config::SignalConfig;
+use super::horizon::HorizonSignal;
+use super::{generate_aggregate_signal, generate_aggregate_signal_in_regime, generate_signal_at, generate_signal_in_regime};
+use crate::types::{Regime, SentimentScore, Signal};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What a signal was generated from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SignalInputs {
+    /// [`generate_signal_at`] on one score
+    Sentiment {
+        sentiment: SentimentScore,
+        #[serde(default)]
+        article_ids: Vec<String>,
+    },
+    /// [`generate_signal_in_regime`] on one score
+    Regime {
+        sentiment: SentimentScore,
+        regime: Regime,
+        #[serde(default)]
+        article_ids: Vec<String>,
+    },
+    /// [`generate_aggregate_signal`], optionally for a horizon
+    Aggregate {
+        aggregate: AggregateSentiment,
+        #[serde(default)]
+        horizon_secs: Option<i64>,
+    },
+    /// [`generate_aggregate_signal_in_regime`], optionally for a horizon
+    RegimeAggregate {
+        aggregate: AggregateSentiment,
+        regime: Regime,
+        #[serde(default)]
+        horizon_secs: Option<i64>,
+    },
+    /// Any other generator; logged but not replayable
+    External { generator: String },
+}
+
+impl From<&HorizonSignal> for SignalInputs {
+    /// Inputs of a [`MultiHorizonGenerator`](super::MultiHorizonGenerator)
+    /// signal; log them with that horizon's thresholds
+    fn from(horizon_signal: &HorizonSignal) -> Self {
+        let aggregate = horizon_signal.aggregate.clone();
+        let horizon_secs = Some(horizon_signal.signal.horizon_secs);
+        match horizon_signal.signal.regime {
+            Some(regime) => SignalInputs::RegimeAggregate {
+                aggregate,
+                regime,
+                horizon_secs,
+            },
+            None => SignalInputs::Aggregate { aggregate, horizon_secs },
+        }
+    }
+}
+
+/// One logged signal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Position in the log, starting at 1
+    pub sequence: u64,
+    /// Unix time the record was written
+    pub recorded_at: i64,
+    pub config_version: String,
+    pub inputs: SignalInputs,
+    pub signal: Signal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "entry", rename_all = "snake_case")]
+enum AuditEntry {
+    Config { version: String, config: SignalConfig },
+    Signal(Box<AuditRecord>),
+}
+
+/// Writer that appends records to an audit file
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    file: File,
+    next_sequence: u64,
+    versions: HashSet<String>,
+}
+
+impl AuditLog {
+    /// Opens `path` for appending, creating it if needed, and locks it
+    /// against other writers. Existing records are read first so sequences
+    /// continue and known configs are not written twice; a partial entry
+    /// left at the end by a crash is truncated away.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open audit log {}", path.display()))?;
+        if let Err(e) = file.try_lock() {
+            bail!("audit log {} is already open by another writer: {}", path.display(), e);
+        }
+
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)
+            .with_context(|| format!("failed to read audit log {}", path.display()))?;
+        let complete = contents.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+        if complete < contents.len() {
+            log::warn!(
+                "dropping {} bytes of a partial entry at the end of audit log {}",
+                contents.len() - complete,
+                path.display()
+            );
+            file.set_len(complete as u64)
+                .and_then(|_| file.sync_data())
+                .with_context(|| format!("failed to truncate audit log {}", path.display()))?;
+        }
+        let trail = AuditTrail::read(&contents[..complete])
+            .with_context(|| format!("failed to read audit log {}", path.display()))?;
+        Ok(Self {
+            path,
+            file,
+            next_sequence: trail.records.last().map_or(1, |r| r.sequence + 1),
+            versions: trail.configs.into_keys().collect(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends `signal` with its inputs and config, stamped with the current
+    /// time. Returns the record's sequence number.
+    pub fn record(&mut self, signal: &Signal, inputs: SignalInputs, config: &SignalConfig) -> Result<u64> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+        self.record_at(signal, inputs, config, now)
+    }
+
+    /// Same as [`record`](Self::record) with an explicit record time
+    pub fn record_at(
+        &mut self,
+        signal: &Signal,
+        inputs: SignalInputs,
+        config: &SignalConfig,
+        recorded_at: i64,
+    ) -> Result<u64> {
+        let version = config.version();
+        let mut lines = String::new();
+        if !self.versions.contains(&version) {
+            let entry = AuditEntry::Config {
+                version: version.clone(),
+                config: config.clone(),
+            };
+            lines.push_str(&serde_json::to_string(&entry)?);
+            lines.push('\n');
+        }
+        let sequence = self.next_sequence;
+        let entry = AuditEntry::Signal(Box::new(AuditRecord {
+            sequence,
+            recorded_at,
+            config_version: version.clone(),
+            inputs,
+            signal: signal.clone(),
+        }));
+        lines.push_str(&serde_json::to_string(&entry)?);
+        lines.push('\n');
+
+        // A crash mid-write can still leave a partial last line; `open`
+        // truncates it and `AuditTrail::read` skips it
+        self.file
+            .write_all(lines.as_bytes())
+            .and_then(|_| self.file.sync_data())
+            .with_context(|| format!("failed to append to audit log {}", self.path.display()))?;
+        self.versions.insert(version);
+        self.next_sequence += 1;
+        Ok(sequence)
+    }
+}
+
+/// Result of regenerating a logged signal
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayOutcome {
+    pub sequence: u64,
+    pub logged_id: String,
+    pub replayed: Signal,
+}
+
+impl ReplayOutcome {
+    /// Whether the regenerated signal has the logged id
+    pub fn matches(&self) -> bool {
+        self.replayed.id == self.logged_id
+    }
+}
+
+/// The contents of an audit log, loaded for queries and replay
+#[derive(Debug, Clone, Default)]
+pub struct AuditTrail {
+    configs: HashMap<String, SignalConfig>,
+    records: Vec<AuditRecord>,
+}
+
+impl AuditTrail {
+    /// Loads an audit log file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("failed to open audit log {}", path.display()))?;
+        Self::read(file).with_context(|| format!("failed to read audit log {}", path.display()))
+    }
+
+    /// Reads audit entries from JSON Lines. An unparsable last line without
+    /// a trailing newline is taken to be a write cut short and skipped.
+    pub fn read<R: Read>(reader: R) -> Result<Self> {
+        let mut trail = AuditTrail::default();
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        let mut n = 0;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            n += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: AuditEntry = match serde_json::from_str(&line) {
+                Ok(entry) => entry,
+                Err(e) if !line.ends_with('\n') => {
+                    log::warn!("skipping partial audit entry on line {}: {}", n, e);
+                    break;
+                }
+                Err(e) => return Err(e).with_context(|| format!("invalid audit entry on line {}", n)),
+            };
+            match entry {
+                AuditEntry::Config { version, config } => {
+                    trail.configs.insert(version, config);
+                }
+                AuditEntry::Signal(record) => {
+                    if !trail.configs.contains_key(&record.config_version) {
+                        bail!("record {} on line {} uses unknown config {}", record.sequence, n, record.config_version);
+                    }
+                    trail.records.push(*record);
+                }
+            }
+        }
+        Ok(trail)
+    }
+
+    /// All records in log order
+    pub fn records(&self) -> &[AuditRecord] {
+        &self.records
+    }
+
+    pub fn get(&self, sequence: u64) -> Option<&AuditRecord> {
+        self.records.iter().find(|r| r.sequence == sequence)
+    }
+
+    pub fn config(&self, version: &str) -> Option<&SignalConfig> {
+        self.configs.get(version)
+    }
+
+    /// Records for `symbol` (any symbol if `None`) whose signal was generated
+    /// in `[from, to)`
+    pub fn query(&self, symbol: Option<&str>, from: i64, to: i64) -> Vec<&AuditRecord> {
+        self.records
+            .iter()
+            .filter(|r| symbol.is_none_or(|s| r.signal.symbol.eq_ignore_ascii_case(s)))
+            .filter(|r| r.signal.generated_at >= from && r.signal.generated_at < to)
+            .collect()
+    }
+
+    /// Regenerates the signal of `record` from its logged inputs and config
+    pub fn replay(&self, record: &AuditRecord) -> Result<ReplayOutcome> {
+        let config = self
+            .config(&record.config_version)
+            .with_context(|| format!("unknown config {}", record.config_version))?;
+        let logged = &record.signal;
+        let replayed = match &record.inputs {
+            SignalInputs::Sentiment { sentiment, article_ids } => {
+                generate_signal_at(sentiment, &logged.symbol, config, logged.generated_at)?
+                    .with_source_articles(article_ids)
+            }
+            SignalInputs::Regime {
+                sentiment,
+                regime,
+                article_ids,
+            } => generate_signal_in_regime(sentiment, &logged.symbol, config, *regime, logged.generated_at)?
+                .with_source_articles(article_ids),
+            SignalInputs::Aggregate { aggregate, horizon_secs } => {
+                let signal = generate_aggregate_signal(aggregate, config)?;
+                match horizon_secs {
+                    Some(horizon) => signal.with_horizon_secs(*horizon),
+                    None => signal,
+                }
+            }
+            SignalInputs::RegimeAggregate {
+                aggregate,
+                regime,
+                horizon_secs,
+            } => {
+                let signal = generate_aggregate_signal_in_regime(aggregate, config, *regime)?;
+                match horizon_secs {
+                    Some(horizon) => signal.with_horizon_secs(*horizon),
+                    None => signal,
+                }
+            }
+            SignalInputs::External { generator } => {
+                bail!("record {} comes from {} and cannot be replayed", record.sequence, generator)
+            }
+        };
+        Ok(ReplayOutcome {
+            sequence: record.sequence,
+            logged_id: logged.id.clone(),
+            replayed,
+        })
+    }
+
+    /// Replays every replayable record, skipping external ones
+    pub fn verify(&self) -> Result<Vec<ReplayOutcome>> {
+        self.records
+            .iter()
+            .filter(|r| !matches!(r.inputs, SignalInputs::External { .. }))
+            .map(|r| self.replay(r))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::{HorizonSpec, MultiHorizonGenerator, SentimentAggregator, SignalType, ThresholdOverride};
+    use rust_decimal_macros::dec;
+
+    fn bullish() -> SentimentScore {
+        SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
+        }
+    }
+
+    fn temp_log(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("audit-{}-{}.jsonl", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_record_query_and_replay() {
+        let path = temp_log("replay");
+        let config = SignalConfig::default();
+        let mut log = AuditLog::open(&path).unwrap();
+
+        let single = generate_signal_at(&bullish(), "BTC", &config, 1000).unwrap().with_source_articles(&["a1"]);
+        let inputs = SignalInputs::Sentiment {
+            sentiment: bullish(),
+            article_ids: vec!["a1".into()],
+        };
+        assert_eq!(log.record_at(&single, inputs, &config, 1001).unwrap(), 1);
+
+        let mut aggregator = SentimentAggregator::default();
+        for id in ["e1", "e2", "e3"] {
+            aggregator.observe("ETH", &bullish(), "CryptoNews", 2000, id);
+        }
+        let aggregate = aggregator.aggregate("ETH", 2000).unwrap();
+        let swing = generate_aggregate_signal(&aggregate, &config).unwrap().with_horizon_secs(3 * 86_400);
+        let inputs = SignalInputs::Aggregate {
+            aggregate,
+            horizon_secs: Some(3 * 86_400),
+        };
+        log.record_at(&swing, inputs, &config, 2001).unwrap();
+
+        // A second config version and a non-replayable signal
+        let strict = config.clone().with_override(
+            "BTC",
+            ThresholdOverride {
+                buy_threshold: Some(dec!(0.9)),
+                ..ThresholdOverride::default()
+            },
+        );
+        let held = generate_signal_at(&bullish(), "BTC", &strict, 3000).unwrap();
+        assert_eq!(held.action, SignalType::Hold);
+        let inputs = SignalInputs::Sentiment {
+            sentiment: bullish(),
+            article_ids: Vec::new(),
+        };
+        log.record_at(&held, inputs, &strict, 3001).unwrap();
+        let external = SignalInputs::External {
+            generator: "momentum".into(),
+        };
+        log.record_at(&held, external, &strict, 3002).unwrap();
+
+        let trail = AuditTrail::load(&path).unwrap();
+        assert_eq!(trail.records().len(), 4);
+        assert_eq!(trail.query(Some("btc"), 0, 5000).len(), 3);
+        assert_eq!(trail.query(None, 1000, 3000).len(), 2);
+        assert!(trail.query(Some("SOL"), 0, 5000).is_empty());
+        assert_eq!(trail.get(2).unwrap().signal, swing);
+        assert_eq!(trail.config(&strict.version()), Some(&strict));
+
+        let outcomes = trail.verify().unwrap();
+        assert_eq!(outcomes.len(), 3);
+        assert!(outcomes.iter().all(ReplayOutcome::matches));
+        assert!(trail.replay(trail.get(4).unwrap()).is_err());
+
+        // Each config is written once, and reopening continues the sequence
+        let text = std::fs::read_to_string(&path).unwrap();
+        drop(log);
+        assert_eq!(text.matches("\"entry\":\"config\"").count(), 2);
+        let mut log = AuditLog::open(&path).unwrap();
+        assert_eq!(log.record_at(&single, SignalInputs::External { generator: "test".into() }, &config, 4000).unwrap(), 5);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().matches("\"entry\":\"config\"").count(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_regime_aggregate_replay() {
+        let path = temp_log("regime");
+        let config = SignalConfig::default().with_regime_override(
+            Regime::HighVolatility,
+            ThresholdOverride {
+                buy_threshold: Some(dec!(0.9)),
+                ..ThresholdOverride::default()
+            },
+        );
+        let mut aggregator = SentimentAggregator::default();
+        for id in ["b1", "b2", "b3"] {
+            aggregator.observe("BTC", &bullish(), "CryptoNews", 5000, id);
+        }
+        let aggregate = aggregator.aggregate("BTC", 5000).unwrap();
+        let signal = generate_aggregate_signal_in_regime(&aggregate, &config, Regime::HighVolatility)
+            .unwrap()
+            .with_horizon_secs(4 * 3600);
+        assert_eq!(signal.action, SignalType::Hold);
+        assert_eq!(signal.regime, Some(Regime::HighVolatility));
+
+        let inputs = SignalInputs::RegimeAggregate {
+            aggregate: aggregate.clone(),
+            regime: Regime::HighVolatility,
+            horizon_secs: Some(4 * 3600),
+        };
+        AuditLog::open(&path).unwrap().record_at(&signal, inputs, &config, 5001).unwrap();
+
+        let trail = AuditTrail::load(&path).unwrap();
+        assert!(trail.verify().unwrap()[0].matches());
+        // Replaying without the regime gives a different signal
+        let mut unregimed = trail.get(1).unwrap().clone();
+        unregimed.inputs = SignalInputs::Aggregate {
+            aggregate,
+            horizon_secs: Some(4 * 3600),
+        };
+        assert!(!trail.replay(&unregimed).unwrap().matches());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_horizon_signal_replay() {
+        let path = temp_log("horizon");
+        let mut generator = MultiHorizonGenerator::new(vec![HorizonSpec::intraday(), HorizonSpec::swing()]).unwrap();
+        for id in ["h1", "h2", "h3", "h4"] {
+            generator.observe("ETH", &bullish(), "CryptoNews", 7000, id);
+        }
+        let mut log = AuditLog::open(&path).unwrap();
+        let mut signals = generator.generate("ETH", 7000).unwrap();
+        signals.extend(generator.generate_in_regime("ETH", Regime::Ranging, 7000).unwrap());
+        assert!(matches!(SignalInputs::from(&signals[0]), SignalInputs::Aggregate { horizon_secs: Some(_), .. }));
+        assert!(matches!(
+            SignalInputs::from(&signals[2]),
+            SignalInputs::RegimeAggregate { regime: Regime::Ranging, .. }
+        ));
+        for horizon_signal in &signals {
+            let spec = generator.horizons().find(|spec| spec.name == horizon_signal.horizon).unwrap();
+            log.record_at(&horizon_signal.signal, horizon_signal.into(), &spec.thresholds, 7001).unwrap();
+        }
+        drop(log);
+
+        let trail = AuditTrail::load(&path).unwrap();
+        assert_eq!(trail.records().len(), 4);
+        assert!(trail.verify().unwrap().iter().all(ReplayOutcome::matches));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_partial_entry_and_single_writer() {
+        let path = temp_log("partial");
+        let config = SignalConfig::default();
+        let signal = generate_signal_at(&bullish(), "BTC", &config, 1000).unwrap();
+        let inputs = || SignalInputs::Sentiment {
+            sentiment: bullish(),
+            article_ids: Vec::new(),
+        };
+        let mut log = AuditLog::open(&path).unwrap();
+        log.record_at(&signal, inputs(), &config, 1000).unwrap();
+
+        // Only one writer at a time
+        let err = AuditLog::open(&path).unwrap_err();
+        assert!(err.to_string().contains("another writer"), "{}", err);
+        drop(log);
+
+        // A crash in the middle of the next write
+        let intact = std::fs::read_to_string(&path).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"entry\":\"signal\",\"sequence\":2,\"recor").unwrap();
+        drop(file);
+        assert_eq!(AuditTrail::load(&path).unwrap().records().len(), 1);
+        let garbled = format!("{}not json\n", intact);
+        assert!(AuditTrail::read(garbled.as_bytes()).is_err());
+
+        let mut log = AuditLog::open(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), intact);
+        assert_eq!(log.record_at(&signal, inputs(), &config, 2000).unwrap(), 2);
+        drop(log);
+        let trail = AuditTrail::load(&path).unwrap();
+        assert_eq!(trail.records().len(), 2);
+        assert!(trail.verify().unwrap().iter().all(ReplayOutcome::matches));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_record_fails_replay() {
+        let path = temp_log("tamper");
+        let config = SignalConfig::default();
+        let signal = generate_signal_at(&bullish(), "BTC", &config, 1000).unwrap();
+        let inputs = SignalInputs::Sentiment {
+            sentiment: bullish(),
+            article_ids: Vec::new(),
+        };
+        AuditLog::open(&path).unwrap().record_at(&signal, inputs, &config, 1000).unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap().replace("\"0.85\"", "\"0.95\"");
+        let trail = AuditTrail::read(text.as_bytes()).unwrap();
+        assert!(!trail.verify().unwrap()[0].matches());
+
+        let orphan = text.lines().nth(1).unwrap().to_string();
+        assert!(AuditTrail::read(orphan.as_bytes()).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
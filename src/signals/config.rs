@@ -0,0 +1,298 @@
+//! Signal thresholds and per-symbol and per-regime overrides
+//!
+//! `SignalConfig` holds the default thresholds plus partial overrides keyed by
+//! symbol and by market regime, and can be loaded from TOML or JSON so
+//! thresholds can be tuned without a release. Regime overrides apply on top
+//! of the defaults and symbol overrides on top of both.
+//!
+//! ```toml
+//! buy_threshold = 0.65
+//! buy_margin = 0.3
+//! sell_threshold = 0.65
+//! sell_margin = 0.3
+//! min_confidence = 0.7
+//! hold_confidence = { rule = "neutral_floor", floor = 0.5 }
+//!
+//! [overrides.DOGE]
+//! buy_threshold = 0.8
+//!
+//! [regimes.high_volatility]
+//! buy_threshold = 0.8
+//! sell_threshold = 0.8
+//! ```
+
+use crate::types::{fnv1a_hex, Regime};
+use anyhow::{bail, Context, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// How the confidence of a HOLD signal is derived
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum HoldConfidence {
+    /// The neutral score, but never below `floor`
+    NeutralFloor { floor: Decimal },
+    /// A constant confidence
+    Fixed { value: Decimal },
+}
+
+impl HoldConfidence {
+    /// Confidence of a HOLD signal for the given neutral score
+    pub fn confidence(&self, neutral: Decimal) -> Decimal {
+        match self {
+            HoldConfidence::NeutralFloor { floor } => neutral.max(*floor),
+            HoldConfidence::Fixed { value } => *value,
+        }
+    }
+}
+
+/// Thresholds that turn a sentiment score into BUY, SELL or HOLD
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignalThresholds {
+    /// BUY requires `positive > buy_threshold`
+    pub buy_threshold: Decimal,
+    /// ... and `positive > negative + buy_margin`
+    pub buy_margin: Decimal,
+    /// SELL requires `negative > sell_threshold`
+    pub sell_threshold: Decimal,
+    /// ... and `negative > positive + sell_margin`
+    pub sell_margin: Decimal,
+    /// Signals below this confidence are not actionable
+    pub min_confidence: Decimal,
+    pub hold_confidence: HoldConfidence,
+}
+
+impl Default for SignalThresholds {
+    fn default() -> Self {
+        Self {
+            buy_threshold: dec!(0.65),
+            buy_margin: dec!(0.3),
+            sell_threshold: dec!(0.65),
+            sell_margin: dec!(0.3),
+            min_confidence: dec!(0.7),
+            hold_confidence: HoldConfidence::NeutralFloor { floor: dec!(0.5) },
+        }
+    }
+}
+
+/// Per-symbol changes to the default thresholds; unset fields are inherited
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThresholdOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_threshold: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy_margin: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_threshold: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sell_margin: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hold_confidence: Option<HoldConfidence>,
+}
+
+impl ThresholdOverride {
+    fn apply(&self, base: &SignalThresholds) -> SignalThresholds {
+        SignalThresholds {
+            buy_threshold: self.buy_threshold.unwrap_or(base.buy_threshold),
+            buy_margin: self.buy_margin.unwrap_or(base.buy_margin),
+            sell_threshold: self.sell_threshold.unwrap_or(base.sell_threshold),
+            sell_margin: self.sell_margin.unwrap_or(base.sell_margin),
+            min_confidence: self.min_confidence.unwrap_or(base.min_confidence),
+            hold_confidence: self.hold_confidence.unwrap_or(base.hold_confidence),
+        }
+    }
+}
+
+/// Signal generation settings: default thresholds plus per-symbol and
+/// per-regime overrides
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignalConfig {
+    #[serde(flatten)]
+    pub defaults: SignalThresholds,
+    /// Keyed by upper-case symbol
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, ThresholdOverride>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub regimes: BTreeMap<Regime, ThresholdOverride>,
+}
+
+impl SignalConfig {
+    /// Adds or replaces the override for `symbol`
+    pub fn with_override(mut self, symbol: &str, value: ThresholdOverride) -> Self {
+        self.overrides.insert(symbol.to_uppercase(), value);
+        self
+    }
+
+    /// Adds or replaces the override for `regime`
+    pub fn with_regime_override(mut self, regime: Regime, value: ThresholdOverride) -> Self {
+        self.regimes.insert(regime, value);
+        self
+    }
+
+    /// Effective thresholds for `symbol`
+    pub fn for_symbol(&self, symbol: &str) -> SignalThresholds {
+        self.for_symbol_in(symbol, None)
+    }
+
+    /// Effective thresholds for `symbol` in `regime`: the defaults, then the
+    /// regime override, then the symbol override
+    pub fn for_symbol_in(&self, symbol: &str, regime: Option<Regime>) -> SignalThresholds {
+        let base = match regime.and_then(|r| self.regimes.get(&r)) {
+            Some(value) => value.apply(&self.defaults),
+            None => self.defaults.clone(),
+        };
+        match self.overrides.get(&symbol.to_uppercase()) {
+            Some(value) => value.apply(&base),
+            None => base,
+        }
+    }
+
+    /// Short hash of the serialized config; equal configs share a version
+    pub fn version(&self) -> String {
+        let json = serde_json::to_string(self).expect("signal config serializes to JSON");
+        fnv1a_hex(&[&json])
+    }
+
+    /// Parses a TOML config
+    pub fn from_toml_str(text: &str) -> Result<Self> {
+        let config: SignalConfig = toml::from_str(text).context("invalid signal config TOML")?;
+        config.normalized()
+    }
+
+    /// Parses a JSON config
+    pub fn from_json_str(text: &str) -> Result<Self> {
+        let config: SignalConfig = serde_json::from_str(text).context("invalid signal config JSON")?;
+        config.normalized()
+    }
+
+    /// Loads a `.toml` or `.json` config file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read signal config {}", path.display()))?;
+        let config = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&text),
+            Some("json") => Self::from_json_str(&text),
+            _ => bail!("unsupported signal config format: {}", path.display()),
+        };
+        config.with_context(|| format!("failed to load signal config {}", path.display()))
+    }
+
+    /// Upper-cases override keys and checks every threshold lies in [0, 1]
+    fn normalized(mut self) -> Result<Self> {
+        self.overrides = self
+            .overrides
+            .into_iter()
+            .map(|(symbol, value)| (symbol.to_uppercase(), value))
+            .collect();
+        validate(&self.defaults).context("invalid default thresholds")?;
+        for symbol in self.overrides.keys() {
+            validate(&self.for_symbol(symbol)).with_context(|| format!("invalid thresholds for {}", symbol))?;
+        }
+        for regime in self.regimes.keys() {
+            validate(&self.for_symbol_in("", Some(*regime)))
+                .with_context(|| format!("invalid thresholds for regime {}", regime.as_str()))?;
+        }
+        Ok(self)
+    }
+}
+
+fn validate(t: &SignalThresholds) -> Result<()> {
+    let unit = Decimal::ZERO..=Decimal::ONE;
+    for (name, value) in [
+        ("buy_threshold", t.buy_threshold),
+        ("buy_margin", t.buy_margin),
+        ("sell_threshold", t.sell_threshold),
+        ("sell_margin", t.sell_margin),
+        ("min_confidence", t.min_confidence),
+    ] {
+        if !unit.contains(&value) {
+            bail!("{} must be between 0 and 1, got {}", name, value);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_inherit_defaults() {
+        let config = SignalConfig::default().with_override(
+            "doge",
+            ThresholdOverride {
+                buy_threshold: Some(dec!(0.8)),
+                ..ThresholdOverride::default()
+            },
+        );
+        let doge = config.for_symbol("DOGE");
+        assert_eq!(doge.buy_threshold, dec!(0.8));
+        assert_eq!(doge.sell_threshold, dec!(0.65));
+        assert_eq!(config.for_symbol("BTC"), SignalThresholds::default());
+    }
+
+    #[test]
+    fn test_load_toml_and_json() {
+        let toml = r#"
+            buy_threshold = 0.6
+            min_confidence = 0.55
+            hold_confidence = { rule = "fixed", value = 0.5 }
+
+            [overrides.doge]
+            buy_threshold = 0.8
+            buy_margin = 0.4
+        "#;
+        let config = SignalConfig::from_toml_str(toml).unwrap();
+        assert_eq!(config.defaults.buy_threshold, dec!(0.6));
+        assert_eq!(config.defaults.sell_threshold, dec!(0.65));
+        assert_eq!(config.defaults.hold_confidence.confidence(dec!(0.9)), dec!(0.5));
+        assert_eq!(config.for_symbol("DOGE").buy_margin, dec!(0.4));
+        assert_eq!(config.for_symbol("DOGE").min_confidence, dec!(0.55));
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(SignalConfig::from_json_str(&json).unwrap(), config);
+
+        let example = SignalConfig::from_toml_str(include_str!("../../config/signals.example.toml")).unwrap();
+        assert_eq!(example.defaults, SignalThresholds::default());
+        assert_eq!(example.for_symbol("doge").buy_threshold, dec!(0.8));
+
+        assert!(SignalConfig::from_toml_str("buy_threshold = 1.5").is_err());
+        assert!(SignalConfig::from_toml_str("[overrides.ETH]\nsell_margin = -0.1").is_err());
+    }
+
+    #[test]
+    fn test_regime_overrides() {
+        let toml = r#"
+            [overrides.DOGE]
+            buy_threshold = 0.85
+
+            [regimes.high_volatility]
+            buy_threshold = 0.8
+            min_confidence = 0.8
+        "#;
+        let config = SignalConfig::from_toml_str(toml).unwrap();
+        let btc = config.for_symbol_in("BTC", Some(Regime::HighVolatility));
+        assert_eq!(btc.buy_threshold, dec!(0.8));
+        assert_eq!(btc.min_confidence, dec!(0.8));
+        assert_eq!(config.for_symbol_in("BTC", Some(Regime::Ranging)), SignalThresholds::default());
+        // The symbol override wins over the regime one
+        let doge = config.for_symbol_in("DOGE", Some(Regime::HighVolatility));
+        assert_eq!(doge.buy_threshold, dec!(0.85));
+        assert_eq!(doge.min_confidence, dec!(0.8));
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"high_volatility\""));
+        assert_eq!(SignalConfig::from_json_str(&json).unwrap(), config);
+        assert!(SignalConfig::from_toml_str("[regimes.ranging]\nbuy_margin = 2").is_err());
+        assert!(SignalConfig::from_toml_str("[regimes.sideways]\nbuy_margin = 0.2").is_err());
+    }
+}
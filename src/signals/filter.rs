@@ -0,0 +1,315 @@
+//! Stateful per-symbol filter that stops signals from flapping
+//!
+//! Threshold rules flip between BUY and HOLD every time sentiment crosses the
+//! threshold. [`SignalFilter`] sits between raw generation and output and only
+//! emits a signal when the symbol's position state changes:
+//!
+//! - **hysteresis**: a BUY (SELL) is entered when the raw signal is a BUY
+//!   (SELL) with positive (negative) sentiment at or above `entry_threshold`,
+//!   and held while that sentiment stays at or above `exit_threshold`
+//! - **persistence**: a new state must be asked for continuously for
+//!   `min_persistence_secs` before it is emitted
+//! - **cooldown**: no change is emitted within `cooldown_secs` of the last one
+//!
+//! Time is taken from each signal's `generated_at`, so replays behave like
+//! live runs. Changes that were asked for but not emitted are recorded as
+//! [`SuppressedFlip`]s.
+
+use crate::types::{Signal, SignalType};
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Filter thresholds and timings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterConfig {
+    /// Directional sentiment needed to enter BUY or SELL
+    pub entry_threshold: Decimal,
+    /// Directional sentiment needed to stay in BUY or SELL
+    pub exit_threshold: Decimal,
+    pub min_persistence_secs: i64,
+    pub cooldown_secs: i64,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            entry_threshold: dec!(0.65),
+            exit_threshold: dec!(0.55),
+            min_persistence_secs: 600,
+            cooldown_secs: 1800,
+        }
+    }
+}
+
+impl FilterConfig {
+    /// Sets the entry and exit thresholds
+    pub fn with_thresholds(mut self, entry: Decimal, exit: Decimal) -> Self {
+        self.entry_threshold = entry;
+        self.exit_threshold = exit;
+        self
+    }
+
+    pub fn with_min_persistence_secs(mut self, secs: i64) -> Self {
+        self.min_persistence_secs = secs;
+        self
+    }
+
+    pub fn with_cooldown_secs(mut self, secs: i64) -> Self {
+        self.cooldown_secs = secs;
+        self
+    }
+
+    /// Checks `0 <= exit <= entry <= 1` and non-negative timings
+    pub fn validate(&self) -> Result<()> {
+        if self.exit_threshold < Decimal::ZERO
+            || self.exit_threshold > self.entry_threshold
+            || self.entry_threshold > Decimal::ONE
+        {
+            bail!(
+                "thresholds must satisfy 0 <= exit <= entry <= 1, got exit {} and entry {}",
+                self.exit_threshold,
+                self.entry_threshold
+            );
+        }
+        if self.min_persistence_secs < 0 || self.cooldown_secs < 0 {
+            bail!("min_persistence_secs and cooldown_secs must not be negative");
+        }
+        Ok(())
+    }
+}
+
+/// Why a requested change was not emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuppressReason {
+    /// The new state had not been asked for long enough yet
+    Persistence,
+    /// The last emitted change was too recent
+    Cooldown,
+}
+
+/// A state change that was asked for but not emitted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuppressedFlip {
+    pub symbol: String,
+    pub from: SignalType,
+    pub to: SignalType,
+    pub at: i64,
+    pub reason: SuppressReason,
+    /// Id of the raw signal that asked for the change
+    pub signal_id: String,
+}
+
+#[derive(Debug, Clone)]
+struct SymbolState {
+    current: SignalType,
+    last_emitted_at: Option<i64>,
+    /// Requested state and when it was first requested
+    pending: Option<(SignalType, i64)>,
+}
+
+impl Default for SymbolState {
+    fn default() -> Self {
+        Self {
+            current: SignalType::Hold,
+            last_emitted_at: None,
+            pending: None,
+        }
+    }
+}
+
+/// Per-symbol hysteresis, debounce and cooldown filter
+#[derive(Debug, Clone, Default)]
+pub struct SignalFilter {
+    config: FilterConfig,
+    states: HashMap<String, SymbolState>,
+    suppressed: Vec<SuppressedFlip>,
+}
+
+impl SignalFilter {
+    pub fn new(config: FilterConfig) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            states: HashMap::new(),
+            suppressed: Vec::new(),
+        })
+    }
+
+    pub fn config(&self) -> &FilterConfig {
+        &self.config
+    }
+
+    /// Last emitted state for `symbol` (HOLD before anything was emitted)
+    pub fn state(&self, symbol: &str) -> SignalType {
+        self.states
+            .get(&symbol.to_uppercase())
+            .map(|s| s.current)
+            .unwrap_or(SignalType::Hold)
+    }
+
+    /// Changes suppressed so far, oldest first
+    pub fn suppressed(&self) -> &[SuppressedFlip] {
+        &self.suppressed
+    }
+
+    /// Returns and clears the suppressed changes
+    pub fn take_suppressed(&mut self) -> Vec<SuppressedFlip> {
+        std::mem::take(&mut self.suppressed)
+    }
+
+    /// Feeds a raw signal. Returns a signal only when the symbol's state
+    /// changes; its action is the new state.
+    pub fn process(&mut self, signal: Signal) -> Option<Signal> {
+        let now = signal.generated_at;
+        let state = self.states.entry(signal.symbol.to_uppercase()).or_default();
+        let desired = desired_state(&self.config, state.current, &signal);
+
+        if desired == state.current {
+            state.pending = None;
+            return None;
+        }
+
+        let since = match state.pending {
+            Some((pending, since)) if pending == desired => since,
+            _ => now,
+        };
+        state.pending = Some((desired, since));
+
+        let reason = if now - since < self.config.min_persistence_secs {
+            Some(SuppressReason::Persistence)
+        } else if state
+            .last_emitted_at
+            .is_some_and(|at| now - at < self.config.cooldown_secs)
+        {
+            Some(SuppressReason::Cooldown)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            log::debug!(
+                "suppressed {} {} -> {} ({:?})",
+                signal.symbol,
+                state.current.as_str(),
+                desired.as_str(),
+                reason
+            );
+            self.suppressed.push(SuppressedFlip {
+                symbol: signal.symbol.clone(),
+                from: state.current,
+                to: desired,
+                at: now,
+                reason,
+                signal_id: signal.id,
+            });
+            return None;
+        }
+
+        state.current = desired;
+        state.last_emitted_at = Some(now);
+        state.pending = None;
+
+        if signal.action == desired {
+            Some(signal)
+        } else {
+            let mut emitted = Signal { action: desired, levels: None, ..signal };
+            emitted.id = emitted.compute_id();
+            Some(emitted)
+        }
+    }
+}
+
+/// State the raw signal asks for, given the current state
+fn desired_state(config: &FilterConfig, current: SignalType, signal: &Signal) -> SignalType {
+    let strength = |action: SignalType| match action {
+        SignalType::Buy => signal.sentiment.positive,
+        SignalType::Sell => signal.sentiment.negative,
+        SignalType::Hold => Decimal::ZERO,
+    };
+    let enters = signal.action != SignalType::Hold && strength(signal.action) >= config.entry_threshold;
+
+    if enters && signal.action != current {
+        signal.action
+    } else if current != SignalType::Hold && strength(current) >= config.exit_threshold {
+        current
+    } else if enters {
+        signal.action
+    } else {
+        SignalType::Hold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SentimentScore;
+
+    fn raw(positive: Decimal, negative: Decimal, at: i64) -> Signal {
+        let sentiment = SentimentScore {
+            positive,
+            negative,
+            neutral: Decimal::ONE - positive - negative,
+        };
+        let (action, confidence) = crate::signals::classify(&sentiment, &Default::default());
+        Signal::new("BTC", action, sentiment, confidence, at)
+    }
+
+    #[test]
+    fn test_hysteresis_holds_position() {
+        let config = FilterConfig::default().with_min_persistence_secs(0).with_cooldown_secs(0);
+        let mut filter = SignalFilter::new(config).unwrap();
+
+        let entered = filter.process(raw(dec!(0.7), dec!(0.05), 0)).unwrap();
+        assert_eq!(entered.action, SignalType::Buy);
+        // Raw rule says HOLD at 0.6, but it is still above the exit threshold
+        assert!(filter.process(raw(dec!(0.6), dec!(0.1), 60)).is_none());
+        assert_eq!(filter.state("btc"), SignalType::Buy);
+
+        let exited = filter.process(raw(dec!(0.5), dec!(0.1), 120)).unwrap();
+        assert_eq!(exited.action, SignalType::Hold);
+        assert!(filter.suppressed().is_empty());
+    }
+
+    #[test]
+    fn test_persistence_suppresses_oscillation() {
+        let config = FilterConfig::default().with_min_persistence_secs(600).with_cooldown_secs(0);
+        let mut filter = SignalFilter::new(config).unwrap();
+
+        for (i, positive) in [dec!(0.7), dec!(0.5), dec!(0.7), dec!(0.5)].into_iter().enumerate() {
+            assert!(filter.process(raw(positive, dec!(0.05), i as i64 * 120)).is_none());
+        }
+        assert_eq!(filter.state("BTC"), SignalType::Hold);
+        assert_eq!(filter.suppressed().len(), 2);
+        assert!(filter.suppressed().iter().all(|f| f.reason == SuppressReason::Persistence && f.to == SignalType::Buy));
+
+        // Asked for continuously long enough
+        assert!(filter.process(raw(dec!(0.7), dec!(0.05), 1000)).is_none());
+        assert!(filter.process(raw(dec!(0.68), dec!(0.05), 1300)).is_none());
+        let emitted = filter.process(raw(dec!(0.7), dec!(0.05), 1600)).unwrap();
+        assert_eq!(emitted.action, SignalType::Buy);
+        assert_eq!(filter.take_suppressed().len(), 4);
+        assert!(filter.suppressed().is_empty());
+    }
+
+    #[test]
+    fn test_cooldown_blocks_reversal() {
+        let config = FilterConfig::default().with_min_persistence_secs(0).with_cooldown_secs(1800);
+        let mut filter = SignalFilter::new(config).unwrap();
+
+        assert!(filter.process(raw(dec!(0.8), dec!(0.05), 0)).is_some());
+        assert!(filter.process(raw(dec!(0.05), dec!(0.8), 600)).is_none());
+        assert_eq!(filter.suppressed()[0].reason, SuppressReason::Cooldown);
+        assert_eq!(filter.suppressed()[0].to, SignalType::Sell);
+
+        let reversed = filter.process(raw(dec!(0.05), dec!(0.8), 1800)).unwrap();
+        assert_eq!(reversed.action, SignalType::Sell);
+        assert_eq!(reversed.id, reversed.compute_id());
+
+        assert!(SignalFilter::new(FilterConfig::default().with_thresholds(dec!(0.5), dec!(0.6))).is_err());
+    }
+}
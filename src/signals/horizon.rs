@@ -0,0 +1,291 @@
+//! Parallel signals for several holding horizons
+//!
+//! A burst of headlines means something different over an hour than over a
+//! week. A [`MultiHorizonGenerator`] keeps one [`SentimentAggregator`] per
+//! [`HorizonSpec`], each with its own window, decay and thresholds, and
+//! emits one signal per horizon. Every signal carries its horizon in
+//! `horizon_secs`, and [`HorizonSignal`] adds the horizon name, so
+//! downstream consumers can pick the horizon they trade.
+//!
+//! The default horizons are:
+//!
+//! | name       | horizon | window | half-life |
+//! |------------|---------|--------|-----------|
+//! | `intraday` | 4h      | 2h     | 30m       |
+//! | `swing`    | 3d      | 24h    | 6h        |
+//! | `position` | 14d     | 7d     | 2d        |
+
+use super::aggregator::{AggregateSentiment, AggregatorConfig, SentimentAggregator};
+use super::config::SignalConfig;
+use super::{generate_aggregate_signal, generate_aggregate_signal_in_regime};
+use crate::types::{Article, Regime, SentimentScore, Signal};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+const HOUR: i64 = 3600;
+const DAY: i64 = 24 * HOUR;
+
+/// One horizon: how long its signals are meant to be held, how sentiment is
+/// aggregated for it and which thresholds apply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HorizonSpec {
+    pub name: String,
+    pub horizon_secs: i64,
+    #[serde(default)]
+    pub aggregator: AggregatorConfig,
+    #[serde(default)]
+    pub thresholds: SignalConfig,
+}
+
+impl HorizonSpec {
+    pub fn new(name: &str, horizon_secs: i64, aggregator: AggregatorConfig) -> Self {
+        Self {
+            name: name.to_string(),
+            horizon_secs,
+            aggregator,
+            thresholds: SignalConfig::default(),
+        }
+    }
+
+    pub fn with_thresholds(mut self, thresholds: SignalConfig) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// 4h horizon over a 2h window with a 30m half-life
+    pub fn intraday() -> Self {
+        let aggregator = AggregatorConfig::default()
+            .with_window_secs(2 * HOUR)
+            .with_half_life_secs(HOUR / 2);
+        Self::new("intraday", 4 * HOUR, aggregator)
+    }
+
+    /// 3d horizon over a 24h window with a 6h half-life
+    pub fn swing() -> Self {
+        let aggregator = AggregatorConfig::default()
+            .with_window_secs(DAY)
+            .with_half_life_secs(6 * HOUR);
+        Self::new("swing", 3 * DAY, aggregator)
+    }
+
+    /// 14d horizon over a 7d window with a 2d half-life
+    pub fn position() -> Self {
+        let aggregator = AggregatorConfig::default()
+            .with_window_secs(7 * DAY)
+            .with_half_life_secs(2 * DAY);
+        Self::new("position", 14 * DAY, aggregator)
+    }
+}
+
+/// A signal, the name of the horizon it was generated for and the aggregate
+/// it was generated from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HorizonSignal {
+    pub horizon: String,
+    pub signal: Signal,
+    pub aggregate: AggregateSentiment,
+}
+
+/// Generates one signal per configured horizon from the same article stream
+#[derive(Debug, Clone)]
+pub struct MultiHorizonGenerator {
+    horizons: Vec<(HorizonSpec, SentimentAggregator)>,
+}
+
+impl Default for MultiHorizonGenerator {
+    fn default() -> Self {
+        Self::new(vec![HorizonSpec::intraday(), HorizonSpec::swing(), HorizonSpec::position()])
+            .expect("default horizons are valid")
+    }
+}
+
+impl MultiHorizonGenerator {
+    /// Creates a generator for `specs`, which must have unique names and
+    /// positive horizons
+    pub fn new(specs: Vec<HorizonSpec>) -> Result<Self> {
+        if specs.is_empty() {
+            bail!("at least one horizon is required");
+        }
+        let mut horizons = Vec::with_capacity(specs.len());
+        for spec in specs {
+            if spec.horizon_secs <= 0 {
+                bail!("horizon {} must have a positive horizon_secs", spec.name);
+            }
+            if horizons.iter().any(|(h, _): &(HorizonSpec, _)| h.name == spec.name) {
+                bail!("duplicate horizon name {}", spec.name);
+            }
+            let aggregator = SentimentAggregator::new(spec.aggregator.clone())
+                .with_context(|| format!("invalid aggregator for horizon {}", spec.name))?;
+            horizons.push((spec, aggregator));
+        }
+        Ok(Self { horizons })
+    }
+
+    /// Configured horizons, in the order given
+    pub fn horizons(&self) -> impl Iterator<Item = &HorizonSpec> {
+        self.horizons.iter().map(|(spec, _)| spec)
+    }
+
+    /// Records a scored observation in every horizon
+    pub fn observe(&mut self, symbol: &str, sentiment: &SentimentScore, source: &str, timestamp: i64, article_id: &str) {
+        for (_, aggregator) in &mut self.horizons {
+            aggregator.observe(symbol, sentiment, source, timestamp, article_id);
+        }
+    }
+
+    /// Records an article in every horizon and returns its symbols
+    pub fn observe_article(&mut self, article: &Article, sentiment: &SentimentScore) -> Vec<String> {
+        let mut symbols = Vec::new();
+        for (_, aggregator) in &mut self.horizons {
+            symbols = aggregator.observe_article(article, sentiment);
+        }
+        symbols
+    }
+
+    /// One signal per horizon with articles for `symbol` in its window
+    pub fn generate(&self, symbol: &str, now: i64) -> Result<Vec<HorizonSignal>> {
+        self.generate_with(symbol, None, now)
+    }
+
+    /// Same as [`generate`](Self::generate) with each horizon's thresholds
+    /// for `regime`. The regime is recorded on every signal.
+    pub fn generate_in_regime(&self, symbol: &str, regime: Regime, now: i64) -> Result<Vec<HorizonSignal>> {
+        self.generate_with(symbol, Some(regime), now)
+    }
+
+    fn generate_with(&self, symbol: &str, regime: Option<Regime>, now: i64) -> Result<Vec<HorizonSignal>> {
+        let mut signals = Vec::new();
+        for (spec, aggregator) in &self.horizons {
+            if let Some((signal, aggregate)) = Self::signal(spec, aggregator, symbol, regime, now)? {
+                signals.push(HorizonSignal {
+                    horizon: spec.name.clone(),
+                    signal,
+                    aggregate,
+                });
+            }
+        }
+        Ok(signals)
+    }
+
+    /// The signal for `symbol` on the horizon called `horizon`
+    pub fn generate_for(&self, horizon: &str, symbol: &str, now: i64) -> Result<Option<Signal>> {
+        match self.horizons.iter().find(|(spec, _)| spec.name == horizon) {
+            Some((spec, aggregator)) => Ok(Self::signal(spec, aggregator, symbol, None, now)?.map(|(signal, _)| signal)),
+            None => bail!("unknown horizon {}", horizon),
+        }
+    }
+
+    fn signal(
+        spec: &HorizonSpec,
+        aggregator: &SentimentAggregator,
+        symbol: &str,
+        regime: Option<Regime>,
+        now: i64,
+    ) -> Result<Option<(Signal, AggregateSentiment)>> {
+        let Some(aggregate) = aggregator.aggregate(symbol, now) else {
+            return Ok(None);
+        };
+        let signal = match regime {
+            Some(regime) => generate_aggregate_signal_in_regime(&aggregate, &spec.thresholds, regime)?,
+            None => generate_aggregate_signal(&aggregate, &spec.thresholds)?,
+        };
+        Ok(Some((signal.with_horizon_secs(spec.horizon_secs), aggregate)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::ThresholdOverride;
+    use crate::types::SignalType;
+    use rust_decimal_macros::dec;
+
+    fn bearish() -> SentimentScore {
+        SentimentScore {
+            positive: dec!(0.05),
+            negative: dec!(0.9),
+            neutral: dec!(0.05),
+        }
+    }
+
+    fn bullish() -> SentimentScore {
+        SentimentScore {
+            positive: dec!(0.9),
+            negative: dec!(0.05),
+            neutral: dec!(0.05),
+        }
+    }
+
+    #[test]
+    fn test_horizons_diverge() {
+        let mut generator = MultiHorizonGenerator::default();
+        let now = 30 * DAY;
+        // A week of good news, then a burst of hack headlines in the last hour
+        for day in 1..=6 {
+            for i in 0..10 {
+                let id = format!("good-{}-{}", day, i);
+                generator.observe("BTC", &bullish(), "CryptoNews", now - day * DAY + i * HOUR, &id);
+            }
+        }
+        for i in 0..5 {
+            generator.observe("BTC", &bearish(), "CryptoNews", now - 600 + i * 60, &format!("hack-{}", i));
+        }
+
+        let signals = generator.generate("BTC", now).unwrap();
+        let names: Vec<&str> = signals.iter().map(|s| s.horizon.as_str()).collect();
+        assert_eq!(names, ["intraday", "swing", "position"]);
+
+        let intraday = &signals[0].signal;
+        assert_eq!(intraday.action, SignalType::Sell);
+        assert_eq!(intraday.horizon_secs, 4 * HOUR);
+        let position = &signals[2].signal;
+        assert_eq!(position.action, SignalType::Buy);
+        assert_eq!(position.horizon_secs, 14 * DAY);
+        assert_ne!(intraday.id, position.id);
+
+        assert_eq!(generator.generate_for("intraday", "BTC", now).unwrap().as_ref(), Some(intraday));
+        assert!(generator.generate_for("weekly", "BTC", now).is_err());
+        assert!(generator.generate("ETH", now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_specs() {
+        let strict = SignalConfig::default().with_override(
+            "BTC",
+            ThresholdOverride {
+                sell_threshold: Some(dec!(0.95)),
+                ..ThresholdOverride::default()
+            },
+        );
+        let mut generator = MultiHorizonGenerator::new(vec![HorizonSpec::intraday().with_thresholds(strict)]).unwrap();
+        for i in 0..5 {
+            generator.observe("BTC", &bearish(), "CryptoNews", 1000 + i, &format!("a{}", i));
+        }
+        assert_eq!(generator.generate_for("intraday", "BTC", 1010).unwrap().unwrap().action, SignalType::Hold);
+
+        // A volatile-regime override applies on top of the horizon's own thresholds
+        let cautious = SignalConfig::default().with_regime_override(
+            Regime::HighVolatility,
+            ThresholdOverride {
+                sell_threshold: Some(dec!(0.95)),
+                ..ThresholdOverride::default()
+            },
+        );
+        let mut generator = MultiHorizonGenerator::new(vec![HorizonSpec::intraday().with_thresholds(cautious)]).unwrap();
+        for i in 0..5 {
+            generator.observe("BTC", &bearish(), "CryptoNews", 1000 + i, &format!("a{}", i));
+        }
+        let calm = generator.generate_in_regime("BTC", Regime::Ranging, 1010).unwrap();
+        assert_eq!(calm[0].signal.action, SignalType::Sell);
+        assert_eq!(calm[0].signal.regime, Some(Regime::Ranging));
+        let volatile = generator.generate_in_regime("BTC", Regime::HighVolatility, 1010).unwrap();
+        assert_eq!(volatile[0].signal.action, SignalType::Hold);
+        assert_eq!(volatile[0].signal.horizon_secs, 4 * HOUR);
+        assert_eq!(volatile[0].aggregate.article_count, 5);
+
+        assert!(MultiHorizonGenerator::new(vec![HorizonSpec::swing(), HorizonSpec::swing()]).is_err());
+        assert!(MultiHorizonGenerator::new(Vec::new()).is_err());
+        let json = serde_json::to_string(&HorizonSpec::position()).unwrap();
+        assert_eq!(serde_json::from_str::<HorizonSpec>(&json).unwrap(), HorizonSpec::position());
+    }
+}
@@ -2,43 +2,88 @@
 //!
 //! This module generates trading signals (BUY, SELL, HOLD) based on sentiment analysis.
 //! Signals are generated with confidence scores to help traders make informed decisions.
+//! Thresholds are configurable per symbol through [`SignalConfig`], and
+//! signals can be generated from a single score, from a rolling
+//! [`SentimentAggregator`] window, or from changes in sentiment and article
+//! volume (see [`momentum`]). [`SignalFilter`] debounces raw signals before
+//! they are acted on, and [`strategy`] combines generators into strategies.
+//! Thresholds can also differ per market regime (see [`regime`]), and
+//! [`horizon`] generates parallel signals for several holding horizons.
+//! [`spillover`] derives signals for related symbols from a symbol's news.
+//! Emitted signals can be kept in an append-only [`audit`] log and replayed.
+
+pub mod aggregator;
+pub mod audit;
+pub mod config;
+pub mod filter;
+pub mod horizon;
+pub mod momentum;
+pub mod regime;
+pub mod spillover;
+pub mod strategy;
+
+pub use aggregator::{AggregateSentiment, AggregatorConfig, SentimentAggregator, SentimentBucket};
+pub use audit::{AuditLog, AuditRecord, AuditTrail, ReplayOutcome, SignalInputs};
+pub use config::{HoldConfidence, SignalConfig, SignalThresholds, ThresholdOverride};
+pub use filter::{FilterConfig, SignalFilter, SuppressReason, SuppressedFlip};
+pub use horizon::{HorizonSignal, HorizonSpec, MultiHorizonGenerator};
+pub use momentum::{momentum_signal, volume_spike_signal, zscore_signal, MomentumConfig};
+pub use regime::{classify_regime, RegimeConfig, RegimeReading};
+pub use spillover::{AssetGraph, Relationship, SpilloverConfig, SpilloverModel};
+pub use strategy::{MarketEvent, Strategy};
+
+pub use crate::types::{Regime, SignalType};
 
 use crate::types::{SentimentScore, Signal};
 use anyhow::Result;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, MathematicalOps};
 use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-/// Type of trading signal
-#[derive(Debug, Clone, PartialEq)]
-pub enum SignalType {
-    /// Buy signal - indicates positive sentiment
-    Buy,
-    /// Sell signal - indicates negative sentiment
-    Sell,
-    /// Hold signal - indicates neutral or uncertain sentiment
-    Hold,
+/// Classifies a sentiment score with the given thresholds.
+///
+/// Signal generation rules:
+/// - BUY: positive > buy_threshold and positive > negative + buy_margin
+/// - SELL: negative > sell_threshold and negative > positive + sell_margin
+/// - HOLD: otherwise, with confidence from the hold-confidence rule
+///
+/// Returns the signal type and its confidence.
+pub fn classify(sentiment: &SentimentScore, thresholds: &SignalThresholds) -> (SignalType, Decimal) {
+    if sentiment.positive > thresholds.buy_threshold
+        && sentiment.positive > sentiment.negative + thresholds.buy_margin {
+        // Strong positive sentiment -> BUY
+        (SignalType::Buy, sentiment.positive)
+    } else if sentiment.negative > thresholds.sell_threshold
+        && sentiment.negative > sentiment.positive + thresholds.sell_margin {
+        // Strong negative sentiment -> SELL
+        (SignalType::Sell, sentiment.negative)
+    } else {
+        // Neutral or uncertain -> HOLD
+        (SignalType::Hold, thresholds.hold_confidence.confidence(sentiment.neutral))
+    }
 }
 
-/// Generates a trading signal based on sentiment scores.
+/// Generates a trading signal based on sentiment scores, stamped with the
+/// current time.
 ///
-/// Signal generation rules:
-/// - BUY: positive > 0.65 and positive > negative + 0.3
-/// - SELL: negative > 0.65 and negative > positive + 0.3
-/// - HOLD: otherwise
+/// Thresholds come from `config`, including any override for `symbol`;
+/// see [`classify`] for the rules.
 ///
 /// # Arguments
 ///
 /// * `sentiment` - The sentiment score to analyze
 /// * `symbol` - The trading symbol (e.g., "BTC", "ETH")
+/// * `config` - Thresholds and per-symbol overrides
 ///
 /// # Returns
 ///
-/// A `Signal` struct with the signal type and confidence score
+/// A `Signal` with its action and confidence score
 ///
 /// # Examples
 ///
 /// ```
-/// use sentiment_analysis_trading::{SentimentScore, generate_signal};
+/// use sentiment_analysis_trading::{SentimentScore, SignalConfig, SignalType, generate_signal};
 /// use rust_decimal_macros::dec;
 ///
 /// let sentiment = SentimentScore {
@@ -47,63 +92,122 @@ pub enum SignalType {
 ///     neutral: dec!(0.10),
 /// };
 ///
-/// let signal = generate_signal(&sentiment, "BTC").unwrap();
+/// let signal = generate_signal(&sentiment, "BTC", &SignalConfig::default()).unwrap();
+/// assert_eq!(signal.action, SignalType::Buy);
 /// assert!(signal.confidence > dec!(0.7));
 /// ```
-pub fn generate_signal(sentiment: &SentimentScore, symbol: &str) -> Result<Signal> {
-    let confidence: Decimal;
-    
-    // Determine signal type based on sentiment scores
-    let signal_sentiment = if sentiment.positive > dec!(0.65) 
-        && sentiment.positive > sentiment.negative + dec!(0.3) {
-        // Strong positive sentiment -> BUY
-        confidence = sentiment.positive;
-        sentiment.clone()
-    } else if sentiment.negative > dec!(0.65) 
-        && sentiment.negative > sentiment.positive + dec!(0.3) {
-        // Strong negative sentiment -> SELL
-        confidence = sentiment.negative;
-        sentiment.clone()
-    } else {
-        // Neutral or uncertain -> HOLD
-        confidence = sentiment.neutral.max(dec!(0.5));
-        sentiment.clone()
-    };
-    
-    Ok(Signal {
-        symbol: symbol.to_string(),
-        sentiment: signal_sentiment,
-        confidence,
-    })
+pub fn generate_signal(sentiment: &SentimentScore, symbol: &str, config: &SignalConfig) -> Result<Signal> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    generate_signal_at(sentiment, symbol, config, now)
 }
 
-/// Generates a signal with additional context about the signal type.
-pub fn generate_signal_with_type(sentiment: &SentimentScore, symbol: &str) -> Result<(Signal, SignalType)> {
-    let signal = generate_signal(sentiment, symbol)?;
-    
-    let signal_type = if signal.sentiment.positive > dec!(0.65) 
-        && signal.sentiment.positive > signal.sentiment.negative + dec!(0.3) {
-        SignalType::Buy
-    } else if signal.sentiment.negative > dec!(0.65) 
-        && signal.sentiment.negative > signal.sentiment.positive + dec!(0.3) {
-        SignalType::Sell
-    } else {
-        SignalType::Hold
+/// Same as [`generate_signal`] with an explicit generation time, so backtests
+/// and replays produce identical signals (and ids) for identical inputs.
+pub fn generate_signal_at(
+    sentiment: &SentimentScore,
+    symbol: &str,
+    config: &SignalConfig,
+    generated_at: i64,
+) -> Result<Signal> {
+    let (action, confidence) = classify(sentiment, &config.for_symbol(symbol));
+    Ok(Signal::new(symbol, action, sentiment.clone(), confidence, generated_at))
+}
+
+/// Same as [`generate_signal_at`] with the thresholds of `regime` (see
+/// [`SignalConfig::for_symbol_in`]). The regime is recorded on the signal.
+pub fn generate_signal_in_regime(
+    sentiment: &SentimentScore,
+    symbol: &str,
+    config: &SignalConfig,
+    regime: Regime,
+    generated_at: i64,
+) -> Result<Signal> {
+    let (action, confidence) = classify(sentiment, &config.for_symbol_in(symbol, Some(regime)));
+    Ok(Signal::new(symbol, action, sentiment.clone(), confidence, generated_at).with_regime(regime))
+}
+
+/// Generates a signal from a windowed aggregate instead of a single article.
+///
+/// The signal is stamped with the aggregate's `as_of` time and carries the
+/// window's article ids and article count next to the confidence.
+pub fn generate_aggregate_signal(aggregate: &AggregateSentiment, config: &SignalConfig) -> Result<Signal> {
+    Ok(generate_signal_at(&aggregate.sentiment, &aggregate.symbol, config, aggregate.as_of)?
+        .with_source_articles(&aggregate.article_ids)
+        .with_article_count(aggregate.article_count))
+}
+
+/// Same as [`generate_aggregate_signal`] with the thresholds of `regime`.
+/// The regime is recorded on the signal.
+pub fn generate_aggregate_signal_in_regime(
+    aggregate: &AggregateSentiment,
+    config: &SignalConfig,
+    regime: Regime,
+) -> Result<Signal> {
+    Ok(
+        generate_signal_in_regime(&aggregate.sentiment, &aggregate.symbol, config, regime, aggregate.as_of)?
+            .with_source_articles(&aggregate.article_ids)
+            .with_article_count(aggregate.article_count),
+    )
+}
+
+/// Direction and conviction of a sentiment score
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SignalStrength {
+    /// From -100 (all negative) to 100 (all positive); 0 for balanced or
+    /// purely neutral sentiment
+    pub direction: Decimal,
+    /// From 0 (even three-way split) to 100 (all mass in one class), based on
+    /// the entropy of the scores. Read together with `direction`: a confident
+    /// neutral article has high conviction but no direction.
+    pub conviction: Decimal,
+}
+
+/// Calculates the directional score and conviction of a sentiment score,
+/// both rounded to two decimal places
+pub fn calculate_signal_strength(sentiment: &SentimentScore) -> SignalStrength {
+    SignalStrength {
+        direction: directional_score(sentiment),
+        conviction: conviction(sentiment),
+    }
+}
+
+/// Positive, negative and neutral scores as shares of their total, with
+/// negative scores counted as zero. `None` if nothing is left.
+fn distribution(sentiment: &SentimentScore) -> Option<[Decimal; 3]> {
+    let scores = [sentiment.positive, sentiment.negative, sentiment.neutral].map(|s| s.max(Decimal::ZERO));
+    let total: Decimal = scores.iter().sum();
+    (total > Decimal::ZERO).then(|| scores.map(|s| s / total))
+}
+
+/// `(positive - negative) / total * 100`, in -100..=100
+pub fn directional_score(sentiment: &SentimentScore) -> Decimal {
+    let Some([positive, negative, _]) = distribution(sentiment) else {
+        return Decimal::ZERO;
     };
-    
-    Ok((signal, signal_type))
+    ((positive - negative) * dec!(100)).clamp(dec!(-100), dec!(100)).round_dp(2)
 }
 
-/// Calculates signal strength on a scale of 0-100
-pub fn calculate_signal_strength(sentiment: &SentimentScore) -> u8 {
-    let max_score = sentiment.positive.max(sentiment.negative).max(sentiment.neutral);
-    let strength = (max_score * dec!(100)).to_string().parse::<f64>().unwrap_or(0.0);
-    strength.min(100.0).max(0.0) as u8
+/// `(1 - H / ln 3) * 100`, where `H` is the Shannon entropy of the
+/// normalized positive, negative and neutral scores, in 0..=100
+pub fn conviction(sentiment: &SentimentScore) -> Decimal {
+    let Some(shares) = distribution(sentiment) else {
+        return Decimal::ZERO;
+    };
+    // Shares too small for Decimal round to zero and contribute nothing
+    let entropy: Decimal = shares
+        .iter()
+        .filter_map(|p| p.checked_ln().map(|ln| -*p * ln))
+        .sum();
+    let max_entropy = Decimal::from(3).ln();
+    ((Decimal::ONE - entropy / max_entropy) * dec!(100))
+        .clamp(Decimal::ZERO, dec!(100))
+        .round_dp(2)
 }
 
-/// Validates if a signal has sufficient confidence to be actionable
-pub fn is_signal_actionable(signal: &Signal, min_confidence: Decimal) -> bool {
-    signal.confidence >= min_confidence
+/// Validates if a signal reaches the minimum confidence configured for its
+/// symbol and, if recorded, its regime
+pub fn is_signal_actionable(signal: &Signal, config: &SignalConfig) -> bool {
+    signal.confidence >= config.for_symbol_in(&signal.symbol, signal.regime).min_confidence
 }
 
 #[cfg(test)]
@@ -118,8 +222,8 @@ mod tests {
             neutral: dec!(0.10),
         };
         
-        let (signal, signal_type) = generate_signal_with_type(&sentiment, "BTC").unwrap();
-        assert_eq!(signal_type, SignalType::Buy);
+        let signal = generate_signal(&sentiment, "BTC", &SignalConfig::default()).unwrap();
+        assert_eq!(signal.action, SignalType::Buy);
         assert_eq!(signal.symbol, "BTC");
         assert!(signal.confidence > dec!(0.7));
     }
@@ -132,8 +236,8 @@ mod tests {
             neutral: dec!(0.10),
         };
         
-        let (signal, signal_type) = generate_signal_with_type(&sentiment, "ETH").unwrap();
-        assert_eq!(signal_type, SignalType::Sell);
+        let signal = generate_signal(&sentiment, "ETH", &SignalConfig::default()).unwrap();
+        assert_eq!(signal.action, SignalType::Sell);
         assert_eq!(signal.symbol, "ETH");
         assert!(signal.confidence > dec!(0.7));
     }
@@ -146,8 +250,8 @@ mod tests {
             neutral: dec!(0.4),
         };
         
-        let (_signal, signal_type) = generate_signal_with_type(&sentiment, "BTC").unwrap();
-        assert_eq!(signal_type, SignalType::Hold);
+        let signal = generate_signal(&sentiment, "BTC", &SignalConfig::default()).unwrap();
+        assert_eq!(signal.action, SignalType::Hold);
     }
     
     #[test]
@@ -159,23 +263,84 @@ mod tests {
         };
         
         let strength = calculate_signal_strength(&sentiment);
-        assert!(strength >= 85);
+        assert_eq!(strength.direction, dec!(80));
+        assert!(strength.conviction > dec!(52) && strength.conviction < dec!(53));
+
+        // A mostly neutral article is decisive but has no direction
+        let neutral = SentimentScore {
+            positive: dec!(0.05),
+            negative: dec!(0.05),
+            neutral: dec!(0.90),
+        };
+        let strength = calculate_signal_strength(&neutral);
+        assert_eq!(strength.direction, Decimal::ZERO);
+        assert!(strength.conviction > dec!(60));
+
+        let bearish = SentimentScore {
+            positive: Decimal::ZERO,
+            negative: Decimal::ONE,
+            neutral: Decimal::ZERO,
+        };
+        assert_eq!(
+            calculate_signal_strength(&bearish),
+            SignalStrength { direction: dec!(-100), conviction: dec!(100) }
+        );
+
+        let third = Decimal::ONE / Decimal::from(3);
+        let even = SentimentScore {
+            positive: third,
+            negative: third,
+            neutral: third,
+        };
+        assert_eq!(conviction(&even), Decimal::ZERO);
+        let empty = SentimentScore {
+            positive: Decimal::ZERO,
+            negative: Decimal::ZERO,
+            neutral: Decimal::ZERO,
+        };
+        assert_eq!(
+            calculate_signal_strength(&empty),
+            SignalStrength { direction: Decimal::ZERO, conviction: Decimal::ZERO }
+        );
+
+        // Unnormalized scores with a share that rounds to zero
+        let tiny = SentimentScore {
+            positive: dec!(0.0000000000000000000000000001),
+            negative: dec!(5),
+            neutral: dec!(5),
+        };
+        let strength = calculate_signal_strength(&tiny);
+        assert_eq!(strength.direction, dec!(-50));
+        assert_eq!(strength.conviction, conviction(&SentimentScore { positive: Decimal::ZERO, ..tiny.clone() }));
+
+        // Negative components count as zero for both measures
+        let negative = SentimentScore {
+            positive: dec!(-0.5),
+            negative: dec!(0.5),
+            neutral: dec!(0.5),
+        };
+        assert_eq!(directional_score(&negative), dec!(-50));
+        assert_eq!(conviction(&negative), conviction(&tiny));
     }
     
     #[test]
     fn test_signal_actionable() {
-        let signal = Signal {
-            symbol: "BTC".to_string(),
-            sentiment: SentimentScore {
-                positive: dec!(0.85),
-                negative: dec!(0.05),
-                neutral: dec!(0.10),
-            },
-            confidence: dec!(0.85),
+        let sentiment = SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
         };
+        let signal = Signal::new("BTC", SignalType::Buy, sentiment, dec!(0.85), 1696435200);
         
-        assert!(is_signal_actionable(&signal, dec!(0.7)));
-        assert!(!is_signal_actionable(&signal, dec!(0.9)));
+        assert!(is_signal_actionable(&signal, &SignalConfig::default()));
+        let strict = SignalConfig::default().with_override(
+            "BTC",
+            ThresholdOverride {
+                min_confidence: Some(dec!(0.9)),
+                ..ThresholdOverride::default()
+            },
+        );
+        assert!(!is_signal_actionable(&signal, &strict));
     }
     
     #[test]
@@ -186,8 +351,132 @@ mod tests {
             neutral: dec!(0.0),
         };
         
-        let (_, signal_type) = generate_signal_with_type(&sentiment, "BTC").unwrap();
-        assert_eq!(signal_type, SignalType::Buy);
+        let signal = generate_signal(&sentiment, "BTC", &SignalConfig::default()).unwrap();
+        assert_eq!(signal.action, SignalType::Buy);
     }
-}
+    
+    #[test]
+    fn test_per_symbol_thresholds() {
+        let sentiment = SentimentScore {
+            positive: dec!(0.7),
+            negative: dec!(0.1),
+            neutral: dec!(0.2),
+        };
+        let config = SignalConfig::default().with_override(
+            "DOGE",
+            ThresholdOverride {
+                buy_threshold: Some(dec!(0.8)),
+                hold_confidence: Some(HoldConfidence::Fixed { value: dec!(0.4) }),
+                ..ThresholdOverride::default()
+            },
+        );
+
+        let btc = generate_signal(&sentiment, "BTC", &config).unwrap();
+        let doge = generate_signal(&sentiment, "DOGE", &config).unwrap();
+        assert_eq!(btc.action, SignalType::Buy);
+        assert_eq!(doge.action, SignalType::Hold);
+        assert_eq!(doge.confidence, dec!(0.4));
+    }
+    
+    #[test]
+    fn test_generate_signal_at_is_deterministic() {
+        let sentiment = SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
+        };
+        let config = SignalConfig::default();
+        let a = generate_signal_at(&sentiment, "BTC", &config, 1696435200).unwrap();
+        let b = generate_signal_at(&sentiment, "BTC", &config, 1696435200).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.generated_at, 1696435200);
+        assert_ne!(a.id, generate_signal_at(&sentiment, "BTC", &config, 1696435201).unwrap().id);
+    }
+    
+    #[test]
+    fn test_aggregate_signal() {
+        let mut aggregator = SentimentAggregator::default();
+        let sentiment = SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
+        };
+        let config = SignalConfig::default();
+
+        aggregator.observe("BTC", &sentiment, "CryptoNews", 1000, "a1");
+        let single = generate_aggregate_signal(&aggregator.aggregate("BTC", 1000).unwrap(), &config).unwrap();
+        assert_eq!(single.action, SignalType::Hold);
+        assert_eq!(single.article_count, 1);
+
+        for id in ["a2", "a3", "a4"] {
+            aggregator.observe("BTC", &sentiment, "CryptoNews", 1000, id);
+        }
+        let signal = generate_aggregate_signal(&aggregator.aggregate("BTC", 1000).unwrap(), &config).unwrap();
+        assert_eq!(signal.action, SignalType::Buy);
+        assert_eq!(signal.article_count, 4);
+        assert_eq!(signal.generated_at, 1000);
+        assert_eq!(signal.source_articles, ["a1", "a2", "a3", "a4"]);
+    }
+
+    #[test]
+    fn test_signal_in_regime() {
+        let sentiment = SentimentScore {
+            positive: dec!(0.75),
+            negative: dec!(0.10),
+            neutral: dec!(0.15),
+        };
+        let config = SignalConfig::default().with_regime_override(
+            Regime::HighVolatility,
+            ThresholdOverride {
+                buy_threshold: Some(dec!(0.8)),
+                ..ThresholdOverride::default()
+            },
+        );
+
+        let calm = generate_signal_in_regime(&sentiment, "BTC", &config, Regime::TrendingUp, 1000).unwrap();
+        assert_eq!(calm.action, SignalType::Buy);
+        assert_eq!(calm.regime, Some(Regime::TrendingUp));
+        assert!(is_signal_actionable(&calm, &config));
+
+        let stormy = generate_signal_in_regime(&sentiment, "BTC", &config, Regime::HighVolatility, 1000).unwrap();
+        assert_eq!(stormy.action, SignalType::Hold);
+        assert_eq!(stormy.regime, Some(Regime::HighVolatility));
 
+        // The regime is part of the id
+        let plain = generate_signal_at(&sentiment, "BTC", &config, 1000).unwrap();
+        assert_ne!(plain.id, calm.id);
+        assert_eq!(plain.with_regime(Regime::TrendingUp).id, calm.id);
+    }
+
+    #[test]
+    fn test_aggregate_signal_in_regime() {
+        let mut aggregator = SentimentAggregator::default();
+        let sentiment = SentimentScore {
+            positive: dec!(0.85),
+            negative: dec!(0.05),
+            neutral: dec!(0.10),
+        };
+        for id in ["a1", "a2", "a3", "a4"] {
+            aggregator.observe("BTC", &sentiment, "CryptoNews", 1000, id);
+        }
+        let aggregate = aggregator.aggregate("BTC", 1000).unwrap();
+        let config = SignalConfig::default().with_regime_override(
+            Regime::HighVolatility,
+            ThresholdOverride {
+                buy_threshold: Some(dec!(0.95)),
+                ..ThresholdOverride::default()
+            },
+        );
+
+        let calm = generate_aggregate_signal_in_regime(&aggregate, &config, Regime::Ranging).unwrap();
+        assert_eq!(calm.action, SignalType::Buy);
+        assert_eq!(calm.regime, Some(Regime::Ranging));
+        assert_eq!(calm.article_count, 4);
+        assert_eq!(calm.source_articles, ["a1", "a2", "a3", "a4"]);
+        assert_eq!(calm.generated_at, 1000);
+
+        let stormy = generate_aggregate_signal_in_regime(&aggregate, &config, Regime::HighVolatility).unwrap();
+        assert_eq!(stormy.action, SignalType::Hold);
+        assert_eq!(stormy.regime, Some(Regime::HighVolatility));
+    }
+}
@@ -0,0 +1,437 @@
+//! Signals from changes in sentiment rather than its level
+//!
+//! Three generators read a [`SentimentAggregator`] and emit the same
+//! [`Signal`] type as [`generate_signal`](super::generate_signal):
+//!
+//! - [`momentum_signal`]: net sentiment of a short window minus a long window
+//! - [`zscore_signal`]: the latest bucket's net sentiment against the mean and
+//!   standard deviation of the preceding buckets
+//! - [`volume_spike_signal`]: the latest bucket's article count against the
+//!   average of the preceding buckets, in the direction of its sentiment
+//!
+//! Each fires BUY or SELL once its value crosses the configured bound. The
+//! confidence is `1 - bound / (2 * |value|)`, so it is 0.5 right at the bound
+//! and approaches 1 as the value grows; inside the bounds a HOLD is returned
+//! with confidence `1 - |value| / (2 * bound)`. The aggregator's window must
+//! be at least [`MomentumConfig::required_window_secs`].
+
+use super::aggregator::{SentimentAggregator, SentimentBucket};
+use crate::types::{SentimentScore, Signal, SignalType};
+use anyhow::{bail, Result};
+use rust_decimal::prelude::*;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Windows and bounds for the momentum, z-score and volume generators
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MomentumConfig {
+    pub short_window_secs: i64,
+    pub long_window_secs: i64,
+    /// Fire when `|short - long|` net sentiment reaches this
+    pub momentum_threshold: Decimal,
+    /// Size of the buckets used by the z-score and volume generators
+    pub bucket_secs: i64,
+    /// Buckets before the latest one that form the baseline
+    pub baseline_buckets: usize,
+    /// Baseline buckets with articles needed before a z-score is computed
+    pub min_baseline_buckets: usize,
+    /// Fire when `|z|` reaches this
+    pub zscore_threshold: Decimal,
+    /// Lower bound on the baseline standard deviation, so a flat baseline
+    /// does not turn every small change into an anomaly
+    pub min_std_dev: Decimal,
+    /// Fire when latest / average article count reaches this
+    pub volume_spike_ratio: Decimal,
+    /// Baseline articles needed before a volume ratio is computed
+    pub min_baseline_articles: usize,
+}
+
+impl Default for MomentumConfig {
+    fn default() -> Self {
+        Self {
+            short_window_secs: 3600,
+            long_window_secs: 24 * 3600,
+            momentum_threshold: dec!(0.15),
+            bucket_secs: 3600,
+            baseline_buckets: 24,
+            min_baseline_buckets: 6,
+            zscore_threshold: dec!(2),
+            min_std_dev: dec!(0.05),
+            volume_spike_ratio: dec!(3),
+            min_baseline_articles: 5,
+        }
+    }
+}
+
+impl MomentumConfig {
+    /// Sets the short and long momentum windows
+    pub fn with_windows(mut self, short_window_secs: i64, long_window_secs: i64) -> Self {
+        self.short_window_secs = short_window_secs;
+        self.long_window_secs = long_window_secs;
+        self
+    }
+
+    /// Sets the bucket size and how many buckets form the baseline
+    pub fn with_buckets(mut self, bucket_secs: i64, baseline_buckets: usize) -> Self {
+        self.bucket_secs = bucket_secs;
+        self.baseline_buckets = baseline_buckets;
+        self
+    }
+
+    pub fn with_momentum_threshold(mut self, threshold: Decimal) -> Self {
+        self.momentum_threshold = threshold;
+        self
+    }
+
+    pub fn with_zscore_threshold(mut self, threshold: Decimal) -> Self {
+        self.zscore_threshold = threshold;
+        self
+    }
+
+    pub fn with_volume_spike_ratio(mut self, ratio: Decimal) -> Self {
+        self.volume_spike_ratio = ratio;
+        self
+    }
+
+    /// Aggregator window needed to see every window and bucket used here
+    pub fn required_window_secs(&self) -> i64 {
+        self.long_window_secs
+            .max(self.bucket_secs * (self.baseline_buckets as i64 + 1))
+    }
+
+    /// Checks windows are positive and ordered and bounds are positive
+    pub fn validate(&self) -> Result<()> {
+        if self.short_window_secs <= 0 || self.short_window_secs >= self.long_window_secs {
+            bail!(
+                "short_window_secs must be positive and below long_window_secs, got {} and {}",
+                self.short_window_secs,
+                self.long_window_secs
+            );
+        }
+        if self.bucket_secs <= 0 || self.baseline_buckets == 0 {
+            bail!("bucket_secs and baseline_buckets must be positive");
+        }
+        for (name, value) in [
+            ("momentum_threshold", self.momentum_threshold),
+            ("zscore_threshold", self.zscore_threshold),
+            ("volume_spike_ratio", self.volume_spike_ratio),
+            ("min_std_dev", self.min_std_dev),
+        ] {
+            if value <= Decimal::ZERO {
+                bail!("{} must be positive, got {}", name, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `config` passes [`MomentumConfig::validate`]; logs why not
+fn is_valid(config: &MomentumConfig) -> bool {
+    match config.validate() {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("invalid momentum config: {}", e);
+            false
+        }
+    }
+}
+
+/// Maps a signed value and its bound to an action and confidence. A
+/// non-positive bound cannot be crossed meaningfully and gives a HOLD with
+/// zero confidence.
+pub(crate) fn crossing(value: Decimal, bound: Decimal) -> (SignalType, Decimal) {
+    if bound <= Decimal::ZERO {
+        return (SignalType::Hold, Decimal::ZERO);
+    }
+    let magnitude = value.abs();
+    if magnitude >= bound {
+        let confidence = (Decimal::ONE - bound / (dec!(2) * magnitude)).round_dp(4);
+        let action = if value > Decimal::ZERO { SignalType::Buy } else { SignalType::Sell };
+        (action, confidence)
+    } else {
+        (SignalType::Hold, (Decimal::ONE - magnitude / (dec!(2) * bound)).round_dp(4))
+    }
+}
+
+/// Builds the signal from the aggregate over `window_secs` ending at `now`
+fn emit(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    window_secs: i64,
+    action: SignalType,
+    confidence: Decimal,
+) -> Option<Signal> {
+    let aggregate = aggregator.aggregate_window(symbol, now, window_secs)?;
+    Some(
+        Signal::new(&aggregate.symbol, action, aggregate.sentiment, confidence, now)
+            .with_source_articles(&aggregate.article_ids)
+            .with_article_count(aggregate.article_count),
+    )
+}
+
+/// Net sentiment of the short window minus the long window. `None` if
+/// either window has no articles or `config` is invalid.
+pub fn sentiment_momentum(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    config: &MomentumConfig,
+) -> Option<Decimal> {
+    if !is_valid(config) {
+        return None;
+    }
+    let short = aggregator.aggregate_window(symbol, now, config.short_window_secs)?;
+    let long = aggregator.aggregate_window(symbol, now, config.long_window_secs)?;
+    let net = |s: &SentimentScore| s.positive - s.negative;
+    Some(net(&short.sentiment) - net(&long.sentiment))
+}
+
+/// Fires when short-window sentiment pulls away from the long window
+pub fn momentum_signal(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    config: &MomentumConfig,
+) -> Option<Signal> {
+    let momentum = sentiment_momentum(aggregator, symbol, now, config)?;
+    let (action, confidence) = crossing(momentum, config.momentum_threshold);
+    emit(aggregator, symbol, now, config.short_window_secs, action, confidence)
+}
+
+/// Latest bucket plus its baseline, oldest first
+fn split_buckets(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    config: &MomentumConfig,
+) -> (SentimentBucket, Vec<SentimentBucket>) {
+    let mut buckets = aggregator.buckets(symbol, now, config.bucket_secs, config.baseline_buckets + 1);
+    let latest = buckets.pop().expect("at least one bucket");
+    (latest, buckets)
+}
+
+/// Z-score of the latest bucket's net sentiment against the baseline
+/// buckets that have articles. `None` without enough data or with an
+/// invalid `config`.
+pub fn sentiment_zscore(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    config: &MomentumConfig,
+) -> Option<Decimal> {
+    if !is_valid(config) {
+        return None;
+    }
+    let (latest, baseline) = split_buckets(aggregator, symbol, now, config);
+    let current = latest.net_sentiment?;
+    let values: Vec<Decimal> = baseline.iter().filter_map(|b| b.net_sentiment).collect();
+    if values.is_empty() || values.len() < config.min_baseline_buckets {
+        return None;
+    }
+
+    let n = Decimal::from(values.len());
+    let mean = values.iter().sum::<Decimal>() / n;
+    let variance = values.iter().map(|v| (*v - mean) * (*v - mean)).sum::<Decimal>() / n;
+    let std_dev = variance.sqrt()?.max(config.min_std_dev);
+    if std_dev.is_zero() {
+        return None;
+    }
+    Some(((current - mean) / std_dev).round_dp(4))
+}
+
+/// Fires when the latest sentiment is an outlier against its recent history
+pub fn zscore_signal(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    config: &MomentumConfig,
+) -> Option<Signal> {
+    let z = sentiment_zscore(aggregator, symbol, now, config)?;
+    let (action, confidence) = crossing(z, config.zscore_threshold);
+    emit(aggregator, symbol, now, config.bucket_secs, action, confidence)
+}
+
+/// Latest bucket's article count divided by the baseline average. `None`
+/// without enough baseline articles or with an invalid `config`.
+pub fn volume_ratio(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    config: &MomentumConfig,
+) -> Option<Decimal> {
+    if !is_valid(config) {
+        return None;
+    }
+    let (latest, baseline) = split_buckets(aggregator, symbol, now, config);
+    let total: usize = baseline.iter().map(|b| b.article_count).sum();
+    if total == 0 || total < config.min_baseline_articles {
+        return None;
+    }
+    let average = Decimal::from(total) / Decimal::from(baseline.len());
+    Some((Decimal::from(latest.article_count) / average).round_dp(4))
+}
+
+/// Fires when article volume spikes, in the direction of the latest
+/// bucket's sentiment. A spike with no net sentiment is a HOLD.
+pub fn volume_spike_signal(
+    aggregator: &SentimentAggregator,
+    symbol: &str,
+    now: i64,
+    config: &MomentumConfig,
+) -> Option<Signal> {
+    let ratio = volume_ratio(aggregator, symbol, now, config)?;
+    let (latest, _) = split_buckets(aggregator, symbol, now, config);
+    let direction = latest.net_sentiment.unwrap_or_default();
+    let (action, confidence) = if direction.is_zero() {
+        (SignalType::Hold, crossing(Decimal::ZERO, config.volume_spike_ratio).1)
+    } else {
+        crossing(ratio * direction.signum(), config.volume_spike_ratio)
+    };
+    emit(aggregator, symbol, now, config.bucket_secs, action, confidence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::{is_signal_actionable, AggregatorConfig, SignalConfig};
+
+    const HOUR: i64 = 3600;
+
+    fn score(positive: Decimal, negative: Decimal) -> SentimentScore {
+        SentimentScore {
+            positive,
+            negative,
+            neutral: Decimal::ONE - positive - negative,
+        }
+    }
+
+    fn aggregator() -> SentimentAggregator {
+        SentimentAggregator::new(
+            AggregatorConfig::default()
+                .with_window_secs(48 * HOUR)
+                .with_neutral_prior(Decimal::ZERO),
+        )
+        .unwrap()
+    }
+
+    /// One article per hour for the 24 hours before `now - HOUR`
+    fn hourly_baseline(aggregator: &mut SentimentAggregator, now: i64, net: impl Fn(i64) -> SentimentScore) {
+        for h in 2..=25 {
+            let ts = now - h * HOUR + 60;
+            aggregator.observe("BTC", &net(h), "Wire", ts, &format!("base-{}", h));
+        }
+    }
+
+    #[test]
+    fn test_crossing() {
+        assert_eq!(crossing(dec!(0.3), dec!(0.15)), (SignalType::Buy, dec!(0.75)));
+        assert_eq!(crossing(dec!(-0.15), dec!(0.15)), (SignalType::Sell, dec!(0.5)));
+        assert_eq!(crossing(Decimal::ZERO, dec!(0.15)), (SignalType::Hold, Decimal::ONE));
+        assert_eq!(crossing(Decimal::ZERO, Decimal::ZERO), (SignalType::Hold, Decimal::ZERO));
+        assert_eq!(crossing(dec!(0.3), dec!(-0.1)), (SignalType::Hold, Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_momentum_signal() {
+        let now = 100 * HOUR;
+        let mut aggregator = aggregator();
+        hourly_baseline(&mut aggregator, now, |_| score(dec!(0.2), dec!(0.6)));
+        let config = MomentumConfig::default();
+        assert!(momentum_signal(&aggregator, "BTC", now, &config).is_none());
+
+        for i in 0..3 {
+            aggregator.observe("BTC", &score(dec!(0.9), dec!(0.05)), "Wire", now - 600 + i, &format!("new-{}", i));
+        }
+        let signal = momentum_signal(&aggregator, "BTC", now, &config).unwrap();
+        assert_eq!(signal.action, SignalType::Buy);
+        assert_eq!(signal.article_count, 3);
+        assert!(sentiment_momentum(&aggregator, "BTC", now, &config).unwrap() > dec!(0.3));
+        assert!(is_signal_actionable(&signal, &SignalConfig::default()));
+    }
+
+    #[test]
+    fn test_zscore_signal() {
+        let now = 100 * HOUR;
+        let mut aggregator = aggregator();
+        hourly_baseline(&mut aggregator, now, |h| {
+            if h % 2 == 0 { score(dec!(0.4), dec!(0.3)) } else { score(dec!(0.3), dec!(0.4)) }
+        });
+        let config = MomentumConfig::default();
+
+        aggregator.observe("BTC", &score(dec!(0.35), dec!(0.3)), "Wire", now - 60, "calm");
+        assert_eq!(zscore_signal(&aggregator, "BTC", now, &config).unwrap().action, SignalType::Hold);
+
+        aggregator.observe("BTC", &score(dec!(0.05), dec!(0.9)), "Wire", now - 30, "crash");
+        let z = sentiment_zscore(&aggregator, "BTC", now, &config).unwrap();
+        assert!(z < dec!(-2), "z = {}", z);
+        let signal = zscore_signal(&aggregator, "BTC", now, &config).unwrap();
+        assert_eq!(signal.action, SignalType::Sell);
+        assert_eq!(signal.source_articles, ["calm", "crash"]);
+
+        let short_history = MomentumConfig::default().with_buckets(HOUR, 3);
+        assert!(sentiment_zscore(&aggregator, "BTC", now, &short_history).is_none());
+    }
+
+    #[test]
+    fn test_flat_baseline_without_std_dev_floor() {
+        let now = 100 * HOUR;
+        let mut aggregator = aggregator();
+        hourly_baseline(&mut aggregator, now, |_| score(dec!(0.4), dec!(0.3)));
+        aggregator.observe("BTC", &score(dec!(0.4), dec!(0.3)), "Wire", now - 60, "same");
+
+        let config = MomentumConfig {
+            min_std_dev: Decimal::ZERO,
+            ..MomentumConfig::default()
+        };
+        assert!(config.validate().unwrap_err().to_string().contains("min_std_dev"));
+        assert!(sentiment_zscore(&aggregator, "BTC", now, &config).is_none());
+        assert_eq!(sentiment_zscore(&aggregator, "BTC", now, &MomentumConfig::default()), Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_zero_threshold_is_rejected() {
+        let now = 100 * HOUR;
+        let mut aggregator = aggregator();
+        hourly_baseline(&mut aggregator, now, |_| score(dec!(0.4), dec!(0.4)));
+        aggregator.observe("BTC", &score(dec!(0.4), dec!(0.4)), "Wire", now - 60, "flat");
+
+        for config in [
+            MomentumConfig { momentum_threshold: Decimal::ZERO, ..MomentumConfig::default() },
+            MomentumConfig { zscore_threshold: Decimal::ZERO, ..MomentumConfig::default() },
+            MomentumConfig { volume_spike_ratio: Decimal::ZERO, ..MomentumConfig::default() },
+        ] {
+            assert!(config.validate().is_err());
+            assert!(momentum_signal(&aggregator, "BTC", now, &config).is_none());
+            assert!(zscore_signal(&aggregator, "BTC", now, &config).is_none());
+            assert!(volume_spike_signal(&aggregator, "BTC", now, &config).is_none());
+        }
+        let config = MomentumConfig::default();
+        assert_eq!(momentum_signal(&aggregator, "BTC", now, &config).unwrap().action, SignalType::Hold);
+    }
+
+    #[test]
+    fn test_volume_spike_signal() {
+        let now = 100 * HOUR;
+        let mut aggregator = aggregator();
+        hourly_baseline(&mut aggregator, now, |_| score(dec!(0.4), dec!(0.4)));
+        let config = MomentumConfig::default();
+
+        aggregator.observe("BTC", &score(dec!(0.1), dec!(0.7)), "Wire", now - 60, "n0");
+        assert_eq!(volume_ratio(&aggregator, "BTC", now, &config), Some(Decimal::ONE));
+        assert_eq!(volume_spike_signal(&aggregator, "BTC", now, &config).unwrap().action, SignalType::Hold);
+
+        for i in 1..6 {
+            aggregator.observe("BTC", &score(dec!(0.1), dec!(0.7)), "Wire", now - 60 + i, &format!("n{}", i));
+        }
+        let signal = volume_spike_signal(&aggregator, "BTC", now, &config).unwrap();
+        assert_eq!(signal.action, SignalType::Sell);
+        assert_eq!(signal.confidence, dec!(0.75));
+        assert_eq!(signal.article_count, 6);
+
+        assert!(volume_ratio(&aggregator, "ETH", now, &config).is_none());
+        assert!(MomentumConfig::default().with_windows(HOUR, HOUR).validate().is_err());
+        assert_eq!(config.required_window_secs(), 25 * HOUR);
+    }
+}
@@ -0,0 +1,277 @@
+//! Market regime classification
+//!
+//! [`classify_regime`] labels the latest point of a price series from:
+//!
+//! - the **volatility percentile**: where the current realized volatility
+//!   ranks among the rolling volatilities of the recent past. At or above
+//!   `high_volatility_percentile` the regime is `HighVolatility`, whatever
+//!   the trend.
+//! - the **trend score**: the signed efficiency ratio of the price over
+//!   `trend_period` points (net move over the sum of absolute moves, from -1
+//!   to 1), plus `sentiment_weight` times the aggregate net sentiment when
+//!   one is given. At or beyond `trend_threshold` the regime is trending,
+//!   otherwise ranging.
+//!
+//! The regime then selects the per-regime thresholds of a [`SignalConfig`]
+//! (see [`generate_signal_in_regime`](super::generate_signal_in_regime)).
+//!
+//! [`SignalConfig`]: super::SignalConfig
+
+use crate::correlation::PricePoint;
+use crate::risk::realized_volatility;
+use crate::types::{Regime, SentimentScore};
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Windows and cut-offs of the regime classifier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RegimeConfig {
+    /// Returns per realized volatility sample
+    pub volatility_lookback: usize,
+    /// Past volatility samples the current one is ranked against
+    pub volatility_history: usize,
+    /// Minimum samples before a percentile is computed
+    pub min_volatility_history: usize,
+    pub high_volatility_percentile: Decimal,
+    /// Points the efficiency ratio is measured over
+    pub trend_period: usize,
+    pub trend_threshold: Decimal,
+    /// Weight of `positive - negative` aggregate sentiment in the trend score
+    pub sentiment_weight: Decimal,
+}
+
+impl Default for RegimeConfig {
+    fn default() -> Self {
+        Self {
+            volatility_lookback: 20,
+            volatility_history: 100,
+            min_volatility_history: 20,
+            high_volatility_percentile: dec!(0.9),
+            trend_period: 20,
+            trend_threshold: dec!(0.3),
+            sentiment_weight: dec!(0.2),
+        }
+    }
+}
+
+impl RegimeConfig {
+    /// Sets the volatility sample size, history and high-volatility percentile
+    pub fn with_volatility(mut self, lookback: usize, history: usize, percentile: Decimal) -> Self {
+        self.volatility_lookback = lookback;
+        self.volatility_history = history;
+        self.high_volatility_percentile = percentile;
+        self
+    }
+
+    /// Sets the trend period and threshold
+    pub fn with_trend(mut self, period: usize, threshold: Decimal) -> Self {
+        self.trend_period = period;
+        self.trend_threshold = threshold;
+        self
+    }
+
+    pub fn with_sentiment_weight(mut self, weight: Decimal) -> Self {
+        self.sentiment_weight = weight;
+        self
+    }
+
+    /// Price points needed for a classification
+    pub fn required_points(&self) -> usize {
+        (self.volatility_lookback + self.min_volatility_history).max(self.trend_period + 1)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.volatility_lookback < 2 || self.trend_period == 0 || self.min_volatility_history == 0 {
+            bail!("volatility lookback must be at least 2 and other windows positive");
+        }
+        if self.min_volatility_history > self.volatility_history {
+            bail!("min_volatility_history cannot exceed volatility_history");
+        }
+        let unit = Decimal::ZERO..=Decimal::ONE;
+        if !unit.contains(&self.high_volatility_percentile) || !unit.contains(&self.trend_threshold) {
+            bail!("high_volatility_percentile and trend_threshold must be between 0 and 1");
+        }
+        if self.sentiment_weight < Decimal::ZERO {
+            bail!("sentiment_weight must not be negative, got {}", self.sentiment_weight);
+        }
+        Ok(())
+    }
+}
+
+/// A regime label with the measurements behind it
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegimeReading {
+    pub regime: Regime,
+    /// Current realized volatility of returns
+    pub volatility: Decimal,
+    /// Share of past volatility samples below the current one
+    pub volatility_percentile: Decimal,
+    /// Signed efficiency ratio of the price
+    pub trend_strength: Decimal,
+    /// Trend strength plus the weighted sentiment, clamped to [-1, 1]
+    pub trend_score: Decimal,
+}
+
+/// Signed efficiency ratio over the last `period` moves: net change over
+/// the sum of absolute changes
+pub fn trend_strength(prices: &[PricePoint], period: usize) -> Option<Decimal> {
+    if period == 0 || prices.len() <= period {
+        return None;
+    }
+    let window = &prices[prices.len() - period - 1..];
+    let path = window
+        .windows(2)
+        .map(|w| (w[1].price - w[0].price).abs())
+        .sum::<Decimal>();
+    if path.is_zero() {
+        return Some(Decimal::ZERO);
+    }
+    Some((window[period].price - window[0].price) / path)
+}
+
+/// Rank of the latest realized volatility among up to `history` earlier
+/// samples, each over `lookback` returns. Volatilities are compared at six
+/// decimal places so a steady market ranks low rather than on rounding noise.
+fn volatility_percentile(prices: &[PricePoint], config: &RegimeConfig) -> Option<(Decimal, Decimal)> {
+    let sample = |prices: &[PricePoint]| realized_volatility(prices, config.volatility_lookback);
+    let current = sample(prices)?;
+    let samples: Vec<Decimal> = (1..=config.volatility_history)
+        .map_while(|back| sample(&prices[..prices.len().checked_sub(back)?]))
+        .collect();
+    if samples.is_empty() || samples.len() < config.min_volatility_history {
+        return None;
+    }
+    let below = samples.iter().filter(|v| v.round_dp(6) < current.round_dp(6)).count();
+    Some((current, (Decimal::from(below) / Decimal::from(samples.len())).round_dp(4)))
+}
+
+/// Classifies the regime at the last point of `prices`, optionally tilted by
+/// the aggregate `sentiment`. `None` with fewer than
+/// [`RegimeConfig::required_points`] prices or an invalid `config`.
+pub fn classify_regime(
+    prices: &[PricePoint],
+    sentiment: Option<&SentimentScore>,
+    config: &RegimeConfig,
+) -> Option<RegimeReading> {
+    if let Err(e) = config.validate() {
+        log::warn!("cannot classify regime: {}", e);
+        return None;
+    }
+    let (volatility, volatility_percentile) = volatility_percentile(prices, config)?;
+    let trend_strength = trend_strength(prices, config.trend_period)?;
+    let tilt = sentiment.map_or(Decimal::ZERO, |s| config.sentiment_weight * (s.positive - s.negative));
+    let trend_score = (trend_strength + tilt).clamp(-Decimal::ONE, Decimal::ONE).round_dp(4);
+
+    let regime = if volatility_percentile >= config.high_volatility_percentile {
+        Regime::HighVolatility
+    } else if trend_score >= config.trend_threshold {
+        Regime::TrendingUp
+    } else if trend_score <= -config.trend_threshold {
+        Regime::TrendingDown
+    } else {
+        Regime::Ranging
+    };
+
+    Some(RegimeReading {
+        regime,
+        volatility,
+        volatility_percentile,
+        trend_strength: trend_strength.round_dp(4),
+        trend_score,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(prices: &[Decimal]) -> Vec<PricePoint> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| PricePoint {
+                timestamp: i as i64 * 3600,
+                price: *price,
+                volume: None,
+            })
+            .collect()
+    }
+
+    /// Zig-zag of `swing` around a drift of `step` per point
+    fn zigzag(len: usize, step: Decimal, swing: Decimal) -> Vec<Decimal> {
+        (0..len)
+            .map(|i| {
+                let wiggle = if i % 2 == 0 { swing } else { -swing };
+                dec!(100) + step * Decimal::from(i) + wiggle
+            })
+            .collect()
+    }
+
+    /// Alternating `down` and `up` factors, so relative moves stay constant
+    fn geometric(len: usize, down: Decimal, up: Decimal) -> Vec<Decimal> {
+        let mut price = dec!(100);
+        (0..len)
+            .map(|i| {
+                price *= if i % 2 == 0 { down } else { up };
+                price
+            })
+            .collect()
+    }
+
+    fn config() -> RegimeConfig {
+        RegimeConfig::default().with_volatility(10, 40, dec!(0.9)).with_trend(10, dec!(0.3))
+    }
+
+    #[test]
+    fn test_trend_and_range() {
+        let up = series(&zigzag(80, dec!(0.5), dec!(0.2)));
+        let reading = classify_regime(&up, None, &config()).unwrap();
+        assert_eq!(reading.regime, Regime::TrendingUp);
+        assert!(reading.trend_strength > dec!(0.5));
+
+        let down = series(&geometric(80, dec!(0.98), dec!(1.005)));
+        assert_eq!(classify_regime(&down, None, &config()).unwrap().regime, Regime::TrendingDown);
+
+        let flat = series(&zigzag(80, Decimal::ZERO, dec!(0.5)));
+        let reading = classify_regime(&flat, None, &config()).unwrap();
+        assert_eq!(reading.regime, Regime::Ranging);
+        assert_eq!(reading.trend_strength, Decimal::ZERO);
+
+        // Strong aggregate sentiment tips a flat market into a trend
+        let bullish = SentimentScore {
+            positive: dec!(0.9),
+            negative: dec!(0.0),
+            neutral: dec!(0.1),
+        };
+        let config = config().with_sentiment_weight(dec!(0.5));
+        assert_eq!(classify_regime(&flat, Some(&bullish), &config).unwrap().regime, Regime::TrendingUp);
+
+        assert_eq!(classify_regime(&flat[..20], None, &config), None);
+
+        // No volatility history would divide by zero; it is rejected instead
+        let no_history = RegimeConfig {
+            volatility_history: 0,
+            min_volatility_history: 0,
+            ..config
+        };
+        assert!(no_history.validate().is_err());
+        assert_eq!(classify_regime(&flat, None, &no_history), None);
+        assert_eq!(volatility_percentile(&flat, &no_history), None);
+    }
+
+    #[test]
+    fn test_high_volatility() {
+        // Calm uptrend, then the swings blow up
+        let mut prices = zigzag(70, dec!(0.5), dec!(0.2));
+        prices.extend(zigzag(10, dec!(0.5), dec!(8)).iter().map(|p| *p + dec!(35)));
+        let reading = classify_regime(&series(&prices), None, &config()).unwrap();
+        assert_eq!(reading.regime, Regime::HighVolatility);
+        assert_eq!(reading.volatility_percentile, Decimal::ONE);
+
+        assert!(config().validate().is_ok());
+        assert!(config().with_trend(10, dec!(1.5)).validate().is_err());
+    }
+}
@@ -0,0 +1,395 @@
+//! Cross-asset sentiment spillover
+//!
+//! Bad BTC news drags the altcoins and an ETH upgrade lifts the L2 tokens.
+//! An [`AssetGraph`] lists directed links from a source symbol to the
+//! symbols its news moves, each with a relationship, a weight and a lag:
+//!
+//! ```toml
+//! [[links]]
+//! source = "BTC"
+//! target = "SOL"
+//! relationship = "beta"
+//! weight = 0.8
+//! lag_secs = 1800
+//! ```
+//!
+//! A [`SpilloverModel`] reads each source's net sentiment (`positive -
+//! negative`) over `shock_window_secs` ending `lag_secs` ago, sums the
+//! weighted shocks into the target, and emits a BUY or SELL once the sum
+//! reaches `min_shock`, with the confidence of the momentum generators.
+//! Derived signals list their sources in `Signal::spillover_from`, so they
+//! are never mistaken for signals from the target's own news.
+
+use super::aggregator::SentimentAggregator;
+use super::momentum::crossing;
+use crate::types::{Regime, SentimentScore, Signal, SignalType};
+use anyhow::{bail, Context, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Why one symbol's news moves another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Relationship {
+    /// Both belong to the same sector
+    Sector,
+    /// The target follows the source as a market leader
+    Beta,
+    /// The target is built on the source's ecosystem
+    Ecosystem,
+}
+
+/// A directed link from `source` to `target`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetLink {
+    pub source: String,
+    pub target: String,
+    pub relationship: Relationship,
+    /// Share of the source shock passed on; negative for inverse moves
+    pub weight: Decimal,
+    /// Delay before the source shock reaches the target
+    #[serde(default)]
+    pub lag_secs: i64,
+}
+
+/// Directed links between symbols
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AssetGraph {
+    #[serde(default)]
+    pub links: Vec<AssetLink>,
+}
+
+impl AssetGraph {
+    /// Adds a link, replacing any with the same source, target and relationship
+    pub fn with_link(
+        mut self,
+        source: &str,
+        target: &str,
+        relationship: Relationship,
+        weight: Decimal,
+        lag_secs: i64,
+    ) -> Self {
+        let (source, target) = (source.to_uppercase(), target.to_uppercase());
+        self.links
+            .retain(|l| !(l.source == source && l.target == target && l.relationship == relationship));
+        self.links.push(AssetLink {
+            source,
+            target,
+            relationship,
+            weight,
+            lag_secs,
+        });
+        self
+    }
+
+    /// Links `leader` to each follower with the follower's beta
+    pub fn with_beta(self, leader: &str, followers: &[(&str, Decimal)], lag_secs: i64) -> Self {
+        followers.iter().fold(self, |graph, (follower, beta)| {
+            graph.with_link(leader, follower, Relationship::Beta, *beta, lag_secs)
+        })
+    }
+
+    /// Links every member of a sector to every other member
+    pub fn with_sector(self, members: &[&str], weight: Decimal, lag_secs: i64) -> Self {
+        let mut graph = self;
+        for source in members {
+            for target in members.iter().filter(|t| !t.eq_ignore_ascii_case(source)) {
+                graph = graph.with_link(source, target, Relationship::Sector, weight, lag_secs);
+            }
+        }
+        graph
+    }
+
+    /// Links an ecosystem's base asset to the tokens built on it
+    pub fn with_ecosystem(self, base: &str, members: &[&str], weight: Decimal, lag_secs: i64) -> Self {
+        members.iter().fold(self, |graph, member| {
+            graph.with_link(base, member, Relationship::Ecosystem, weight, lag_secs)
+        })
+    }
+
+    /// Links whose shocks reach `target`
+    pub fn links_to<'a>(&'a self, target: &'a str) -> impl Iterator<Item = &'a AssetLink> + 'a {
+        self.links.iter().filter(move |l| l.target.eq_ignore_ascii_case(target))
+    }
+
+    /// Symbols that receive spillover, sorted
+    pub fn targets(&self) -> Vec<String> {
+        let mut targets: Vec<String> = self.links.iter().map(|l| l.target.clone()).collect();
+        targets.sort();
+        targets.dedup();
+        targets
+    }
+
+    /// Parses a TOML graph
+    pub fn from_toml_str(text: &str) -> Result<Self> {
+        let graph: AssetGraph = toml::from_str(text).context("invalid asset graph TOML")?;
+        graph.normalized()
+    }
+
+    /// Parses a JSON graph
+    pub fn from_json_str(text: &str) -> Result<Self> {
+        let graph: AssetGraph = serde_json::from_str(text).context("invalid asset graph JSON")?;
+        graph.normalized()
+    }
+
+    /// Upper-cases symbols and rejects self-links and negative lags
+    fn normalized(mut self) -> Result<Self> {
+        for link in &mut self.links {
+            link.source = link.source.to_uppercase();
+            link.target = link.target.to_uppercase();
+            if link.source == link.target {
+                bail!("{} cannot spill over into itself", link.source);
+            }
+            if link.lag_secs < 0 {
+                bail!("lag_secs for {} -> {} must not be negative", link.source, link.target);
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Shock window and emission threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpilloverConfig {
+    /// Window the source net sentiment is read over
+    pub shock_window_secs: i64,
+    /// Weighted shock a target needs for a BUY or SELL
+    pub min_shock: Decimal,
+}
+
+impl Default for SpilloverConfig {
+    fn default() -> Self {
+        Self {
+            shock_window_secs: 2 * 3600,
+            min_shock: dec!(0.2),
+        }
+    }
+}
+
+/// One source's share of a target's spillover
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Contribution {
+    pub source: String,
+    pub relationship: Relationship,
+    pub weight: Decimal,
+    pub lag_secs: i64,
+    /// Source net sentiment over the shock window ending `lag_secs` ago
+    pub source_net: Decimal,
+    /// `weight * source_net`
+    pub shock: Decimal,
+    pub article_count: usize,
+    pub article_ids: Vec<String>,
+}
+
+/// Propagates sentiment shocks along an [`AssetGraph`]
+#[derive(Debug, Clone, Default)]
+pub struct SpilloverModel {
+    graph: AssetGraph,
+    config: SpilloverConfig,
+}
+
+impl SpilloverModel {
+    pub fn new(graph: AssetGraph, config: SpilloverConfig) -> Result<Self> {
+        if config.shock_window_secs <= 0 || config.min_shock <= Decimal::ZERO {
+            bail!("shock_window_secs and min_shock must be positive");
+        }
+        Ok(Self {
+            graph: graph.normalized()?,
+            config,
+        })
+    }
+
+    pub fn graph(&self) -> &AssetGraph {
+        &self.graph
+    }
+
+    /// Retention the aggregator needs for the longest lag in the graph
+    pub fn required_window_secs(&self) -> i64 {
+        let max_lag = self.graph.links.iter().map(|l| l.lag_secs).max().unwrap_or(0);
+        max_lag + self.config.shock_window_secs
+    }
+
+    /// Shocks reaching `target` at `now`, one per link whose source has
+    /// articles in its lagged window
+    pub fn contributions(&self, aggregator: &SentimentAggregator, target: &str, now: i64) -> Vec<Contribution> {
+        self.graph
+            .links_to(target)
+            .filter_map(|link| {
+                let aggregate =
+                    aggregator.aggregate_window(&link.source, now - link.lag_secs, self.config.shock_window_secs)?;
+                let source_net = aggregate.sentiment.positive - aggregate.sentiment.negative;
+                Some(Contribution {
+                    source: link.source.clone(),
+                    relationship: link.relationship,
+                    weight: link.weight,
+                    lag_secs: link.lag_secs,
+                    source_net,
+                    shock: (link.weight * source_net).round_dp(6),
+                    article_count: aggregate.article_count,
+                    article_ids: aggregate.article_ids,
+                })
+            })
+            .collect()
+    }
+
+    /// Derived signal for `target`, or `None` when the combined shock stays
+    /// below `min_shock`
+    pub fn signal(&self, aggregator: &SentimentAggregator, target: &str, now: i64) -> Option<Signal> {
+        let contributions = self.contributions(aggregator, target, now);
+        let shock = contributions
+            .iter()
+            .map(|c| c.shock)
+            .sum::<Decimal>()
+            .clamp(-Decimal::ONE, Decimal::ONE);
+        let (action, confidence) = crossing(shock, self.config.min_shock);
+        if action == SignalType::Hold {
+            return None;
+        }
+
+        let sentiment = SentimentScore {
+            positive: shock.max(Decimal::ZERO),
+            negative: (-shock).max(Decimal::ZERO),
+            neutral: Decimal::ONE - shock.abs(),
+        };
+        let sources: Vec<&str> = contributions.iter().map(|c| c.source.as_str()).collect();
+        let articles: Vec<&str> = contributions
+            .iter()
+            .flat_map(|c| c.article_ids.iter().map(String::as_str))
+            .collect();
+        let article_count = contributions.iter().map(|c| c.article_count).sum();
+        Some(
+            Signal::new(&target.to_uppercase(), action, sentiment, confidence, now)
+                .with_source_articles(&articles)
+                .with_article_count(article_count)
+                .with_spillover_from(&sources),
+        )
+    }
+
+    /// Same as [`signal`](Self::signal), recording the regime `target` is
+    /// in. The shock bound does not depend on the regime.
+    pub fn signal_in_regime(
+        &self,
+        aggregator: &SentimentAggregator,
+        target: &str,
+        regime: Regime,
+        now: i64,
+    ) -> Option<Signal> {
+        self.signal(aggregator, target, now).map(|s| s.with_regime(regime))
+    }
+
+    /// Derived signals for every target in the graph
+    pub fn signals(&self, aggregator: &SentimentAggregator, now: i64) -> Vec<Signal> {
+        self.graph
+            .targets()
+            .iter()
+            .filter_map(|target| self.signal(aggregator, target, now))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signals::AggregatorConfig;
+
+    fn score(positive: Decimal, negative: Decimal) -> SentimentScore {
+        SentimentScore {
+            positive,
+            negative,
+            neutral: Decimal::ONE - positive - negative,
+        }
+    }
+
+    fn graph() -> AssetGraph {
+        AssetGraph::default()
+            .with_beta("BTC", &[("SOL", dec!(0.8)), ("DOGE", dec!(1.2))], 1800)
+            .with_ecosystem("ETH", &["ARB", "OP"], dec!(0.6), 0)
+    }
+
+    fn aggregator() -> SentimentAggregator {
+        SentimentAggregator::new(AggregatorConfig::default().with_window_secs(12 * 3600)).unwrap()
+    }
+
+    #[test]
+    fn test_bad_leader_news_spills_over() {
+        let model = SpilloverModel::new(graph(), SpilloverConfig::default()).unwrap();
+        let mut aggregator = aggregator();
+        for i in 0..4 {
+            aggregator.observe("BTC", &score(dec!(0.05), dec!(0.9)), "CryptoNews", 10_000 + i * 60, &format!("hack-{}", i));
+        }
+
+        // Not yet: the BTC shock reaches SOL after 30 minutes
+        assert!(model.signal(&aggregator, "SOL", 10_200).is_none());
+
+        let sol = model.signal(&aggregator, "SOL", 12_000).unwrap();
+        assert_eq!(sol.action, SignalType::Sell);
+        assert_eq!(sol.symbol, "SOL");
+        assert_eq!(sol.spillover_from, ["BTC"]);
+        assert_eq!(sol.article_count, 4);
+        assert_eq!(sol.source_articles.len(), 4);
+        let volatile = model.signal_in_regime(&aggregator, "SOL", Regime::HighVolatility, 12_000).unwrap();
+        assert_eq!(volatile.regime, Some(Regime::HighVolatility));
+        assert_ne!(volatile.id, sol.id);
+
+        let contributions = model.contributions(&aggregator, "DOGE", 12_000);
+        assert_eq!(contributions[0].relationship, Relationship::Beta);
+        assert_eq!(contributions[0].shock, (contributions[0].source_net * dec!(1.2)).round_dp(6));
+        // DOGE's higher beta gives a stronger signal
+        let doge = model.signal(&aggregator, "DOGE", 12_000).unwrap();
+        assert!(doge.confidence > sol.confidence);
+
+        // BTC's own signal is not derived, and unrelated tokens get nothing
+        assert!(model.signal(&aggregator, "BTC", 12_000).is_none());
+        assert!(model.signal(&aggregator, "ARB", 12_000).is_none());
+        assert_eq!(model.signals(&aggregator, 12_000).len(), 2);
+    }
+
+    #[test]
+    fn test_ecosystem_and_weak_shocks() {
+        let model = SpilloverModel::new(graph(), SpilloverConfig::default()).unwrap();
+        let mut aggregator = aggregator();
+        aggregator.observe("ETH", &score(dec!(0.9), dec!(0.05)), "CryptoNews", 1000, "upgrade-1");
+        // One article with the neutral prior: net 0.425, times 0.6 = 0.255
+        let arb = model.signal(&aggregator, "ARB", 1000).unwrap();
+        assert_eq!(arb.action, SignalType::Buy);
+        assert_eq!(arb.spillover_from, ["ETH"]);
+        assert_ne!(arb.id, arb.clone().with_spillover_from(&["BTC"]).id);
+
+        let mild = SpilloverModel::new(graph(), SpilloverConfig { min_shock: dec!(0.3), ..SpilloverConfig::default() });
+        assert!(mild.unwrap().signal(&aggregator, "ARB", 1000).is_none());
+    }
+
+    #[test]
+    fn test_graph_config() {
+        let toml = r#"
+            [[links]]
+            source = "btc"
+            target = "sol"
+            relationship = "beta"
+            weight = 0.8
+            lag_secs = 1800
+
+            [[links]]
+            source = "SOL"
+            target = "JUP"
+            relationship = "ecosystem"
+            weight = 0.5
+        "#;
+        let graph = AssetGraph::from_toml_str(toml).unwrap();
+        assert_eq!(graph.links[0].source, "BTC");
+        assert_eq!(graph.links[1].lag_secs, 0);
+        assert_eq!(graph.targets(), ["JUP", "SOL"]);
+        let json = serde_json::to_string(&graph).unwrap();
+        assert_eq!(AssetGraph::from_json_str(&json).unwrap(), graph);
+
+        assert!(AssetGraph::from_toml_str("[[links]]\nsource = \"BTC\"\ntarget = \"btc\"\nrelationship = \"sector\"\nweight = 1").is_err());
+        let sector = AssetGraph::default().with_sector(&["UNI", "AAVE", "CRV"], dec!(0.3), 0);
+        assert_eq!(sector.links.len(), 6);
+        assert_eq!(sector.links_to("uni").count(), 2);
+
+        let model = SpilloverModel::new(graph, SpilloverConfig::default()).unwrap();
+        assert_eq!(model.required_window_secs(), 1800 + 7200);
+    }
+}
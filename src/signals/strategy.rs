@@ -0,0 +1,758 @@
+//! Composable strategies
+//!
+//! A [`Strategy`] consumes [`MarketEvent`]s (scored articles and prices) and
+//! emits [`Signal`]s. Building blocks wrap the existing generators, and
+//! combinators assemble them into larger strategies:
+//!
+//! ```
+//! use sentiment_analysis_trading::signals::strategy::*;
+//! use sentiment_analysis_trading::signals::MomentumConfig;
+//! use rust_decimal_macros::dec;
+//!
+//! let strategy = Veto::new(
+//!     MajorityVote::new("sentiment-majority")
+//!         .with(SentimentStrategy::default())
+//!         .with(MomentumStrategy::new(MomentumKind::Momentum, MomentumConfig::default()).unwrap())
+//!         .with(MomentumStrategy::new(MomentumKind::ZScore, MomentumConfig::default()).unwrap()),
+//! )
+//! .with_veto(PriceTrendFilter::new(6 * 3600, dec!(0.03)).unwrap());
+//! assert_eq!(strategy.name(), "sentiment-majority");
+//! ```
+//!
+//! Strategies update their state with [`Strategy::update`] and are asked for
+//! a signal with [`Strategy::evaluate`]; combinators forward every event to
+//! all children and combine what they return. `None` means "no opinion yet"
+//! (not enough data), which is different from a HOLD.
+
+use super::aggregator::{AggregatorConfig, SentimentAggregator};
+use super::momentum::{crossing, momentum_signal, volume_spike_signal, zscore_signal, MomentumConfig};
+use super::{generate_aggregate_signal, SignalConfig};
+use crate::correlation::PricePoint;
+use crate::indicators::ConfirmationFilter;
+use crate::types::{SentimentScore, Signal, SignalType};
+use anyhow::{bail, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+
+/// Input to a strategy
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    /// A scored article mentioning `symbol`
+    Sentiment {
+        symbol: String,
+        sentiment: SentimentScore,
+        source: String,
+        timestamp: i64,
+        article_id: String,
+    },
+    /// A price observation for `symbol`
+    Price { symbol: String, point: PricePoint },
+}
+
+impl MarketEvent {
+    pub fn symbol(&self) -> &str {
+        match self {
+            MarketEvent::Sentiment { symbol, .. } | MarketEvent::Price { symbol, .. } => symbol,
+        }
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        match self {
+            MarketEvent::Sentiment { timestamp, .. } => *timestamp,
+            MarketEvent::Price { point, .. } => point.timestamp,
+        }
+    }
+}
+
+/// Something that turns market events into signals
+pub trait Strategy: Send {
+    fn name(&self) -> &str;
+
+    /// Updates internal state with an event
+    fn update(&mut self, event: &MarketEvent);
+
+    /// Signal for `symbol` at `now`, or `None` without enough data
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal>;
+
+    /// Updates with the event and evaluates its symbol at its timestamp
+    fn on_event(&mut self, event: &MarketEvent) -> Option<Signal> {
+        self.update(event);
+        self.evaluate(event.symbol(), event.timestamp())
+    }
+}
+
+impl<S: Strategy + ?Sized> Strategy for Box<S> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        (**self).update(event)
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        (**self).evaluate(symbol, now)
+    }
+}
+
+fn observe(aggregator: &mut SentimentAggregator, event: &MarketEvent) {
+    if let MarketEvent::Sentiment { symbol, sentiment, source, timestamp, article_id } = event {
+        aggregator.observe(symbol, sentiment, source, *timestamp, article_id);
+    }
+}
+
+/// Threshold rules of [`generate_signal`](super::generate_signal) applied to
+/// the rolling aggregate of each symbol
+#[derive(Debug, Clone, Default)]
+pub struct SentimentStrategy {
+    aggregator: SentimentAggregator,
+    config: SignalConfig,
+}
+
+impl SentimentStrategy {
+    pub fn new(aggregator: AggregatorConfig, config: SignalConfig) -> Result<Self> {
+        Ok(Self {
+            aggregator: SentimentAggregator::new(aggregator)?,
+            config,
+        })
+    }
+}
+
+impl Strategy for SentimentStrategy {
+    fn name(&self) -> &str {
+        "sentiment"
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        observe(&mut self.aggregator, event);
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        let aggregate = self.aggregator.aggregate(symbol, now)?;
+        generate_aggregate_signal(&aggregate, &self.config).ok()
+    }
+}
+
+/// Which generator of [`momentum`](super::momentum) a [`MomentumStrategy`] runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MomentumKind {
+    Momentum,
+    ZScore,
+    VolumeSpike,
+}
+
+/// Momentum, z-score or volume-spike signals as a strategy
+#[derive(Debug, Clone)]
+pub struct MomentumStrategy {
+    kind: MomentumKind,
+    aggregator: SentimentAggregator,
+    config: MomentumConfig,
+}
+
+impl MomentumStrategy {
+    /// Creates the strategy with an aggregator window that covers `config`
+    pub fn new(kind: MomentumKind, config: MomentumConfig) -> Result<Self> {
+        config.validate()?;
+        let aggregator = AggregatorConfig::default().with_window_secs(config.required_window_secs());
+        Ok(Self {
+            kind,
+            aggregator: SentimentAggregator::new(aggregator)?,
+            config,
+        })
+    }
+}
+
+impl Strategy for MomentumStrategy {
+    fn name(&self) -> &str {
+        match self.kind {
+            MomentumKind::Momentum => "momentum",
+            MomentumKind::ZScore => "zscore",
+            MomentumKind::VolumeSpike => "volume-spike",
+        }
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        observe(&mut self.aggregator, event);
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        match self.kind {
+            MomentumKind::Momentum => momentum_signal(&self.aggregator, symbol, now, &self.config),
+            MomentumKind::ZScore => zscore_signal(&self.aggregator, symbol, now, &self.config),
+            MomentumKind::VolumeSpike => volume_spike_signal(&self.aggregator, symbol, now, &self.config),
+        }
+    }
+}
+
+/// BUY when the price rose at least `min_change` (a fraction) over the
+/// lookback, SELL when it fell as much, HOLD otherwise, with confidences as
+/// in [`momentum`](super::momentum). Mostly useful as a gate or veto; its
+/// signals carry a neutral sentiment.
+#[derive(Debug, Clone)]
+pub struct PriceTrendFilter {
+    lookback_secs: i64,
+    min_change: Decimal,
+    prices: HashMap<String, Vec<PricePoint>>,
+}
+
+impl PriceTrendFilter {
+    /// Creates the filter; `lookback_secs` and `min_change` must be positive
+    pub fn new(lookback_secs: i64, min_change: Decimal) -> Result<Self> {
+        if lookback_secs <= 0 {
+            bail!("lookback_secs must be positive, got {}", lookback_secs);
+        }
+        if min_change <= Decimal::ZERO {
+            bail!("min_change must be positive, got {}", min_change);
+        }
+        Ok(Self {
+            lookback_secs,
+            min_change,
+            prices: HashMap::new(),
+        })
+    }
+}
+
+impl Strategy for PriceTrendFilter {
+    fn name(&self) -> &str {
+        "price-trend"
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        if let MarketEvent::Price { symbol, point } = event {
+            let points = self.prices.entry(symbol.to_uppercase()).or_default();
+            points.push(point.clone());
+            points.sort_by_key(|p| p.timestamp);
+            let newest = points.last().map(|p| p.timestamp).unwrap_or(point.timestamp);
+            // Keep one point at or before the lookback start as the reference
+            let keep_from = points
+                .iter()
+                .rposition(|p| p.timestamp <= newest - self.lookback_secs)
+                .unwrap_or(0);
+            points.drain(..keep_from);
+        }
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        let points = self.prices.get(&symbol.to_uppercase())?;
+        let latest = points.iter().rev().find(|p| p.timestamp <= now)?;
+        let reference = points.iter().rev().find(|p| p.timestamp <= now - self.lookback_secs)?;
+        if reference.price.is_zero() {
+            return None;
+        }
+        let change = (latest.price - reference.price) / reference.price;
+        let (action, confidence) = crossing(change, self.min_change);
+        let sentiment = SentimentScore {
+            positive: Decimal::ZERO,
+            negative: Decimal::ZERO,
+            neutral: Decimal::ONE,
+        };
+        Some(Signal::new(&symbol.to_uppercase(), action, sentiment, confidence, now))
+    }
+}
+
+fn direction(action: SignalType) -> Decimal {
+    match action {
+        SignalType::Buy => Decimal::ONE,
+        SignalType::Sell => -Decimal::ONE,
+        SignalType::Hold => Decimal::ZERO,
+    }
+}
+
+/// Builds the combined signal on top of the most confident of `parts`
+/// (preferring those with the chosen action), merging their source articles
+fn combine(parts: &[Signal], action: SignalType, confidence: Decimal, now: i64) -> Option<Signal> {
+    let base = parts.iter().max_by_key(|s| (s.action == action, s.confidence))?;
+    let sources: Vec<&str> = parts.iter().flat_map(|s| s.source_articles.iter().map(String::as_str)).collect();
+    let article_count = parts.iter().map(|s| s.article_count).max().unwrap_or(1);
+    Some(
+        Signal::new(&base.symbol, action, base.sentiment.clone(), confidence.round_dp(4), now)
+            .with_horizon_secs(base.horizon_secs)
+            .with_source_articles(&sources)
+            .with_article_count(article_count),
+    )
+}
+
+fn evaluate_all(children: &[Box<dyn Strategy>], symbol: &str, now: i64) -> Vec<Signal> {
+    children.iter().filter_map(|c| c.evaluate(symbol, now)).collect()
+}
+
+/// The action most children agree on; ties and no votes are a HOLD.
+/// Confidence is the mean confidence of the winning voters.
+pub struct MajorityVote {
+    name: String,
+    children: Vec<Box<dyn Strategy>>,
+}
+
+impl MajorityVote {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.children.push(Box::new(strategy));
+        self
+    }
+}
+
+impl Strategy for MajorityVote {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        self.children.iter_mut().for_each(|c| c.update(event));
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        let votes = evaluate_all(&self.children, symbol, now);
+        let count = |action| votes.iter().filter(|s| s.action == action).count();
+        let (buys, sells, holds) = (count(SignalType::Buy), count(SignalType::Sell), count(SignalType::Hold));
+        let action = if buys > sells && buys > holds {
+            SignalType::Buy
+        } else if sells > buys && sells > holds {
+            SignalType::Sell
+        } else {
+            SignalType::Hold
+        };
+        let winners: Vec<Decimal> = votes.iter().filter(|s| s.action == action).map(|s| s.confidence).collect();
+        let confidence = if winners.is_empty() {
+            dec!(0.5)
+        } else {
+            winners.iter().sum::<Decimal>() / Decimal::from(winners.len())
+        };
+        combine(&votes, action, confidence, now)
+    }
+}
+
+/// Weighted average of signed confidences (+ for BUY, - for SELL, 0 for
+/// HOLD). BUY or SELL when the average reaches `threshold`, with the
+/// average's magnitude as confidence; HOLD otherwise.
+pub struct WeightedAverage {
+    name: String,
+    threshold: Decimal,
+    children: Vec<(Box<dyn Strategy>, Decimal)>,
+}
+
+impl WeightedAverage {
+    /// Creates the combinator; `threshold` must be in (0, 1]
+    pub fn new(name: &str, threshold: Decimal) -> Result<Self> {
+        if threshold <= Decimal::ZERO || threshold > Decimal::ONE {
+            bail!("threshold must be in (0, 1], got {}", threshold);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            threshold,
+            children: Vec::new(),
+        })
+    }
+
+    /// Adds a child with a positive `weight`
+    pub fn with(mut self, strategy: impl Strategy + 'static, weight: Decimal) -> Result<Self> {
+        if weight <= Decimal::ZERO {
+            bail!("weight must be positive, got {}", weight);
+        }
+        self.children.push((Box::new(strategy), weight));
+        Ok(self)
+    }
+
+    /// Weighted score in [-1, 1], or `None` if no child has an opinion
+    pub fn score(&self, symbol: &str, now: i64) -> Option<(Decimal, Vec<Signal>)> {
+        let mut total = Decimal::ZERO;
+        let mut score = Decimal::ZERO;
+        let mut parts = Vec::new();
+        for (child, weight) in &self.children {
+            if let Some(signal) = child.evaluate(symbol, now) {
+                total += *weight;
+                score += *weight * direction(signal.action) * signal.confidence;
+                parts.push(signal);
+            }
+        }
+        (!total.is_zero()).then(|| (score / total, parts))
+    }
+}
+
+impl Strategy for WeightedAverage {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        self.children.iter_mut().for_each(|(c, _)| c.update(event));
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        let (score, parts) = self.score(symbol, now)?;
+        let (action, confidence) = if score >= self.threshold {
+            (SignalType::Buy, score)
+        } else if score <= -self.threshold {
+            (SignalType::Sell, -score)
+        } else {
+            (SignalType::Hold, Decimal::ONE - score.abs())
+        };
+        combine(&parts, action, confidence, now)
+    }
+}
+
+/// How a [`Gate`] combines its children
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateMode {
+    /// Fires only when every child gives the same BUY or SELL; confidence
+    /// is the lowest among them
+    All,
+    /// Fires when any child gives a BUY or SELL and none gives the opposite;
+    /// confidence is the highest among them
+    Any,
+}
+
+/// AND / OR gate over child strategies
+pub struct Gate {
+    name: String,
+    mode: GateMode,
+    children: Vec<Box<dyn Strategy>>,
+}
+
+impl Gate {
+    pub fn new(name: &str, mode: GateMode) -> Self {
+        Self {
+            name: name.to_string(),
+            mode,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn all(name: &str) -> Self {
+        Self::new(name, GateMode::All)
+    }
+
+    pub fn any(name: &str) -> Self {
+        Self::new(name, GateMode::Any)
+    }
+
+    pub fn with(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.children.push(Box::new(strategy));
+        self
+    }
+}
+
+impl Strategy for Gate {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        self.children.iter_mut().for_each(|c| c.update(event));
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        let parts = evaluate_all(&self.children, symbol, now);
+        let first = parts.first()?;
+        let fired = match self.mode {
+            GateMode::All => (parts.len() == self.children.len()
+                && first.action != SignalType::Hold
+                && parts.iter().all(|s| s.action == first.action))
+                .then(|| (first.action, parts.iter().map(|s| s.confidence).min().unwrap_or_default())),
+            GateMode::Any => {
+                let has = |action| parts.iter().any(|s| s.action == action);
+                let action = match (has(SignalType::Buy), has(SignalType::Sell)) {
+                    (true, false) => Some(SignalType::Buy),
+                    (false, true) => Some(SignalType::Sell),
+                    _ => None,
+                };
+                action.map(|action| {
+                    let confidence = parts.iter().filter(|s| s.action == action).map(|s| s.confidence).max();
+                    (action, confidence.unwrap_or_default())
+                })
+            }
+        };
+        let (action, confidence) = fired.unwrap_or((SignalType::Hold, dec!(0.5)));
+        combine(&parts, action, confidence, now)
+    }
+}
+
+/// Turns the inner strategy's BUY or SELL into a HOLD when any veto
+/// strategy gives the opposite action
+pub struct Veto {
+    inner: Box<dyn Strategy>,
+    vetoes: Vec<Box<dyn Strategy>>,
+}
+
+impl Veto {
+    pub fn new(inner: impl Strategy + 'static) -> Self {
+        Self {
+            inner: Box::new(inner),
+            vetoes: Vec::new(),
+        }
+    }
+
+    pub fn with_veto(mut self, veto: impl Strategy + 'static) -> Self {
+        self.vetoes.push(Box::new(veto));
+        self
+    }
+}
+
+impl Strategy for Veto {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        self.inner.update(event);
+        self.vetoes.iter_mut().for_each(|v| v.update(event));
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        let signal = self.inner.evaluate(symbol, now)?;
+        if signal.action == SignalType::Hold {
+            return Some(signal);
+        }
+        let opposite = -direction(signal.action);
+        let blocker = evaluate_all(&self.vetoes, symbol, now)
+            .into_iter()
+            .filter(|v| direction(v.action) == opposite)
+            .max_by_key(|v| v.confidence);
+        match blocker {
+            Some(veto) => {
+                log::debug!("{} {} vetoed for {}", self.name(), signal.action.as_str(), signal.symbol);
+                let mut held = Signal { action: SignalType::Hold, confidence: veto.confidence, levels: None, ..signal };
+                held.id = held.compute_id();
+                Some(held)
+            }
+            None => Some(signal),
+        }
+    }
+}
+
+/// Applies a [`ConfirmationFilter`] to the inner strategy's signals using
+/// the price history seen so far, keeping the last `history` points per symbol
+pub struct Confirmed {
+    inner: Box<dyn Strategy>,
+    filter: ConfirmationFilter,
+    history: usize,
+    prices: HashMap<String, Vec<PricePoint>>,
+}
+
+impl Confirmed {
+    pub fn new(inner: impl Strategy + 'static, filter: ConfirmationFilter) -> Self {
+        Self {
+            inner: Box::new(inner),
+            filter,
+            history: 500,
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Sets how many price points are kept per symbol
+    pub fn with_history(mut self, history: usize) -> Self {
+        self.history = history;
+        self
+    }
+}
+
+impl Strategy for Confirmed {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn update(&mut self, event: &MarketEvent) {
+        self.inner.update(event);
+        if let MarketEvent::Price { symbol, point } = event {
+            let points = self.prices.entry(symbol.to_uppercase()).or_default();
+            points.push(point.clone());
+            points.sort_by_key(|p| p.timestamp);
+            let excess = points.len().saturating_sub(self.history);
+            points.drain(..excess);
+        }
+    }
+
+    fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+        let signal = self.inner.evaluate(symbol, now)?;
+        let prices = self.prices.get(&symbol.to_uppercase()).map(Vec::as_slice).unwrap_or_default();
+        let end = prices.partition_point(|p| p.timestamp <= now);
+        Some(self.filter.confirm(signal, &prices[..end]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Always returns the same action and confidence
+    struct Fixed(SignalType, Decimal);
+
+    impl Strategy for Fixed {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        fn update(&mut self, _event: &MarketEvent) {}
+
+        fn evaluate(&self, symbol: &str, now: i64) -> Option<Signal> {
+            let sentiment = SentimentScore {
+                positive: dec!(0.3),
+                negative: dec!(0.3),
+                neutral: dec!(0.4),
+            };
+            Some(Signal::new(symbol, self.0, sentiment, self.1, now).with_source_articles(&[format!("{:?}", self.0)]))
+        }
+    }
+
+    /// Never has an opinion
+    struct Silent;
+
+    impl Strategy for Silent {
+        fn name(&self) -> &str {
+            "silent"
+        }
+
+        fn update(&mut self, _event: &MarketEvent) {}
+
+        fn evaluate(&self, _symbol: &str, _now: i64) -> Option<Signal> {
+            None
+        }
+    }
+
+    fn article(positive: Decimal, timestamp: i64, id: &str) -> MarketEvent {
+        MarketEvent::Sentiment {
+            symbol: "BTC".to_string(),
+            sentiment: SentimentScore {
+                positive,
+                negative: dec!(0.05),
+                neutral: Decimal::ONE - positive - dec!(0.05),
+            },
+            source: "Wire".to_string(),
+            timestamp,
+            article_id: id.to_string(),
+        }
+    }
+
+    fn price(price: Decimal, timestamp: i64) -> MarketEvent {
+        MarketEvent::Price {
+            symbol: "BTC".to_string(),
+            point: PricePoint { timestamp, price, volume: None },
+        }
+    }
+
+    #[test]
+    fn test_majority_vote() {
+        let vote = MajorityVote::new("vote")
+            .with(Fixed(SignalType::Buy, dec!(0.8)))
+            .with(Fixed(SignalType::Buy, dec!(0.6)))
+            .with(Fixed(SignalType::Sell, dec!(0.9)))
+            .with(Silent);
+        let signal = vote.evaluate("BTC", 100).unwrap();
+        assert_eq!(signal.action, SignalType::Buy);
+        assert_eq!(signal.confidence, dec!(0.7));
+        assert_eq!(signal.source_articles, ["Buy", "Sell"]);
+
+        let tie = MajorityVote::new("tie")
+            .with(Fixed(SignalType::Buy, dec!(0.8)))
+            .with(Fixed(SignalType::Sell, dec!(0.8)));
+        assert_eq!(tie.evaluate("BTC", 100).unwrap().action, SignalType::Hold);
+        assert!(MajorityVote::new("empty").with(Silent).evaluate("BTC", 100).is_none());
+    }
+
+    #[test]
+    fn test_weighted_average() {
+        let average = WeightedAverage::new("avg", dec!(0.4))
+            .unwrap()
+            .with(Fixed(SignalType::Buy, dec!(0.9)), dec!(3))
+            .unwrap()
+            .with(Fixed(SignalType::Sell, dec!(0.6)), dec!(1))
+            .unwrap();
+        let (score, _) = average.score("BTC", 0).unwrap();
+        assert_eq!(score, dec!(0.525));
+        let signal = average.evaluate("BTC", 0).unwrap();
+        assert_eq!(signal.action, SignalType::Buy);
+        assert_eq!(signal.confidence, dec!(0.525));
+
+        let even = WeightedAverage::new("even", dec!(0.4))
+            .unwrap()
+            .with(Fixed(SignalType::Buy, dec!(0.9)), dec!(1))
+            .unwrap()
+            .with(Fixed(SignalType::Sell, dec!(0.6)), dec!(1))
+            .unwrap();
+        assert_eq!(even.evaluate("BTC", 0).unwrap().action, SignalType::Hold);
+    }
+
+    #[test]
+    fn test_weighted_average_rejects_bad_bounds() {
+        assert!(WeightedAverage::new("zero", Decimal::ZERO).is_err());
+        assert!(WeightedAverage::new("negative", dec!(-0.4)).is_err());
+        assert!(WeightedAverage::new("above-one", dec!(1.5)).is_err());
+        assert!(WeightedAverage::new("one", Decimal::ONE).is_ok());
+
+        let average = WeightedAverage::new("avg", dec!(0.4)).unwrap();
+        assert!(average.with(Fixed(SignalType::Buy, dec!(0.9)), Decimal::ZERO).is_err());
+        let average = WeightedAverage::new("avg", dec!(0.4)).unwrap();
+        assert!(average.with(Fixed(SignalType::Buy, dec!(0.9)), dec!(-1)).is_err());
+    }
+
+    #[test]
+    fn test_gates() {
+        let all = Gate::all("and")
+            .with(Fixed(SignalType::Sell, dec!(0.8)))
+            .with(Fixed(SignalType::Sell, dec!(0.7)));
+        let signal = all.evaluate("ETH", 0).unwrap();
+        assert_eq!((signal.action, signal.confidence), (SignalType::Sell, dec!(0.7)));
+
+        let missing = Gate::all("and").with(Fixed(SignalType::Sell, dec!(0.8))).with(Silent);
+        assert_eq!(missing.evaluate("ETH", 0).unwrap().action, SignalType::Hold);
+
+        let any = Gate::any("or")
+            .with(Fixed(SignalType::Hold, dec!(0.5)))
+            .with(Fixed(SignalType::Buy, dec!(0.75)));
+        assert_eq!(any.evaluate("ETH", 0).unwrap().action, SignalType::Buy);
+
+        let conflict = Gate::any("or")
+            .with(Fixed(SignalType::Sell, dec!(0.8)))
+            .with(Fixed(SignalType::Buy, dec!(0.75)));
+        assert_eq!(conflict.evaluate("ETH", 0).unwrap().action, SignalType::Hold);
+    }
+
+    #[test]
+    fn test_sentiment_strategy_with_price_veto() {
+        let mut strategy = Veto::new(
+            SentimentStrategy::new(AggregatorConfig::default(), SignalConfig::default()).unwrap(),
+        )
+        .with_veto(PriceTrendFilter::new(3600, dec!(0.02)).unwrap());
+
+        assert!(strategy.on_event(&price(dec!(100), 0)).is_none());
+        for i in 0..4 {
+            strategy.on_event(&article(dec!(0.9), 3000 + i, &format!("a{}", i)));
+        }
+        assert_eq!(strategy.evaluate("BTC", 3600).unwrap().action, SignalType::Buy);
+
+        // Price falling 5% over the hour vetoes the BUY
+        let vetoed = strategy.on_event(&price(dec!(95), 3600)).unwrap();
+        assert_eq!(vetoed.action, SignalType::Hold);
+        assert_eq!(vetoed.source_articles.len(), 4);
+
+        let mut rising = PriceTrendFilter::new(3600, dec!(0.02)).unwrap();
+        rising.update(&price(dec!(100), 0));
+        assert_eq!(rising.on_event(&price(dec!(103), 3600)).unwrap().action, SignalType::Buy);
+
+        assert!(PriceTrendFilter::new(3600, Decimal::ZERO).is_err());
+        assert!(PriceTrendFilter::new(0, dec!(0.02)).is_err());
+    }
+
+    #[test]
+    fn test_confirmed_requires_uptrend() {
+        use crate::indicators::Condition;
+
+        let filter = ConfirmationFilter::default().require_for_buy(Condition::PriceAboveSma { period: 3 });
+        let mut strategy = Confirmed::new(Fixed(SignalType::Buy, dec!(0.8)), filter).with_history(3);
+        assert_eq!(strategy.evaluate("BTC", 0).unwrap().action, SignalType::Hold);
+
+        for (i, p) in [dec!(100), dec!(101), dec!(102), dec!(103)].into_iter().enumerate() {
+            strategy.update(&price(p, i as i64));
+        }
+        assert_eq!(strategy.evaluate("BTC", 3).unwrap().action, SignalType::Buy);
+        assert_eq!(strategy.prices["BTC"].len(), 3);
+        strategy.update(&price(dec!(90), 4));
+        assert_eq!(strategy.evaluate("BTC", 4).unwrap().action, SignalType::Hold);
+    }
+}
@@ -1,26 +1,399 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 
+/// A piece of text to analyze, with the metadata collected by its source.
+///
+/// Only `title`, `content`, `source` and `timestamp` are required when
+/// deserializing, so archives written before the other fields existed still load.
+/// A missing `id` is recomputed from the content.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "ArticleRecord")]
 pub struct Article {
+    /// Stable content hash of source, title and content (see `Article::content_id`)
+    pub id: String,
     pub title: String,
     pub content: String,
     pub source: String,
     pub timestamp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// ISO 639-1 language code, e.g. "en"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Trading symbols the article refers to, e.g. "BTC"
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+    /// Source-specific fields such as scores, engagement counts or filing numbers
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Wire format of `Article`; every field added after the original four is optional
+#[derive(Deserialize)]
+struct ArticleRecord {
+    #[serde(default)]
+    id: Option<String>,
+    title: String,
+    content: String,
+    source: String,
+    timestamp: i64,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    symbols: Vec<String>,
+    #[serde(default)]
+    metadata: BTreeMap<String, Value>,
+}
+
+impl From<ArticleRecord> for Article {
+    fn from(record: ArticleRecord) -> Self {
+        let id = record
+            .id
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| Article::content_id(&record.source, &record.title, &record.content));
+        Article {
+            id,
+            title: record.title,
+            content: record.content,
+            source: record.source,
+            timestamp: record.timestamp,
+            url: record.url,
+            author: record.author,
+            language: record.language,
+            tags: record.tags,
+            symbols: record.symbols,
+            metadata: record.metadata,
+        }
+    }
+}
+
+impl Article {
+    /// Creates an article with a content-hash id and no optional metadata
+    pub fn new(title: &str, content: &str, source: &str, timestamp: i64) -> Self {
+        Article {
+            id: Self::content_id(source, title, content),
+            title: title.to_string(),
+            content: content.to_string(),
+            source: source.to_string(),
+            timestamp,
+            url: None,
+            author: None,
+            language: None,
+            tags: Vec::new(),
+            symbols: Vec::new(),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Computes the stable id of an article: a 64-bit FNV-1a hash of its
+    /// source, title and content, as 16 hex digits. The value does not depend
+    /// on the platform or compiler version, so it can be stored and compared.
+    pub fn content_id(source: &str, title: &str, content: &str) -> String {
+        fnv1a_hex(&[source, title, content])
+    }
+
+    /// Sets the link to the original item
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Sets the author
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    /// Sets the language code
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Adds a tag, ignoring duplicates
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        if !self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.to_string());
+        }
+        self
+    }
+
+    /// Adds trading symbols, ignoring duplicates
+    pub fn with_symbols<S: AsRef<str>>(mut self, symbols: &[S]) -> Self {
+        for symbol in symbols {
+            let symbol = symbol.as_ref().to_uppercase();
+            if !self.symbols.contains(&symbol) {
+                self.symbols.push(symbol);
+            }
+        }
+        self
+    }
+
+    /// Adds the symbols found in the title and content by `nlp::extract_entities`
+    pub fn with_detected_symbols(self) -> Self {
+        let detected = crate::nlp::extract_entities(&format!("{} {}", self.title, self.content));
+        self.with_symbols(&detected)
+    }
+
+    /// Sets a metadata field
+    pub fn with_metadata(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+/// 64-bit FNV-1a over the parts, separated by 0x1f so ("ab", "c") and
+/// ("a", "bc") hash differently, rendered as 16 hex digits
+pub(crate) fn fnv1a_hex(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            hash ^= 0x1f;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        for byte in part.as_bytes() {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SentimentScore {
     pub positive: Decimal,
     pub negative: Decimal,
     pub neutral: Decimal,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Type of trading signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SignalType {
+    /// Buy signal - indicates positive sentiment
+    Buy,
+    /// Sell signal - indicates negative sentiment
+    Sell,
+    /// Hold signal - indicates neutral or uncertain sentiment
+    Hold,
+}
+
+impl SignalType {
+    /// Upper-case label, e.g. "BUY"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignalType::Buy => "BUY",
+            SignalType::Sell => "SELL",
+            SignalType::Hold => "HOLD",
+        }
+    }
+}
+
+/// Market regime a signal was generated in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Regime {
+    TrendingUp,
+    TrendingDown,
+    Ranging,
+    HighVolatility,
+}
+
+impl Regime {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Regime::TrendingUp => "trending_up",
+            Regime::TrendingDown => "trending_down",
+            Regime::Ranging => "ranging",
+            Regime::HighVolatility => "high_volatility",
+        }
+    }
+}
+
+/// Default holding horizon of a signal: one day
+pub const DEFAULT_HORIZON_SECS: i64 = 86_400;
+
+/// Trailing stop parameters, as absolute price distances
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrailingStop {
+    /// Price at which the stop starts trailing
+    pub activation_price: Decimal,
+    /// Distance the stop keeps from the best price once active
+    pub trail_distance: Decimal,
+}
+
+/// Stop-loss and take-profit levels of an actionable signal
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProtectiveLevels {
+    pub entry: Decimal,
+    pub stop_loss: Decimal,
+    pub take_profit: Decimal,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing: Option<TrailingStop>,
+    /// Reward distance over risk distance
+    pub risk_reward: Decimal,
+}
+
+/// A self-describing trading signal that can be stored, audited and sent downstream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Signal {
+    /// Stable hash of the other fields (see `Signal::compute_id`)
+    pub id: String,
     pub symbol: String,
+    pub action: SignalType,
     pub sentiment: SentimentScore,
     pub confidence: Decimal,
+    /// Unix time the signal was generated
+    pub generated_at: i64,
+    /// Intended holding period in seconds
+    pub horizon_secs: i64,
+    /// Ids of the articles the signal was derived from
+    #[serde(default)]
+    pub source_articles: Vec<String>,
+    /// Number of articles behind the sentiment (1 for a single-article signal)
+    #[serde(default = "default_article_count")]
+    pub article_count: usize,
+    /// Protective levels, set by `risk::stops` for BUY and SELL signals.
+    /// Not part of the id: they depend on prices, not on the signal itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub levels: Option<ProtectiveLevels>,
+    /// Regime whose thresholds produced the signal, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regime: Option<Regime>,
+    /// Symbols whose sentiment spilled over into this signal; empty for
+    /// signals derived from the symbol's own news
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spillover_from: Vec<String>,
+}
+
+fn default_article_count() -> usize {
+    1
+}
+
+impl Signal {
+    /// Creates a signal with the default horizon and no source articles
+    pub fn new(
+        symbol: &str,
+        action: SignalType,
+        sentiment: SentimentScore,
+        confidence: Decimal,
+        generated_at: i64,
+    ) -> Self {
+        let mut signal = Signal {
+            id: String::new(),
+            symbol: symbol.to_string(),
+            action,
+            sentiment,
+            confidence,
+            generated_at,
+            horizon_secs: DEFAULT_HORIZON_SECS,
+            source_articles: Vec::new(),
+            article_count: 1,
+            levels: None,
+            regime: None,
+            spillover_from: Vec::new(),
+        };
+        signal.id = signal.compute_id();
+        signal
+    }
+
+    /// Computes the id from symbol, action, sentiment, confidence, generation
+    /// time, horizon, source articles, article count, regime and spillover
+    /// sources (when set). Two signals share an id only if all
+    /// of these match, so regenerating a signal from the same inputs yields
+    /// the same id.
+    pub fn compute_id(&self) -> String {
+        let generated_at = self.generated_at.to_string();
+        let horizon = self.horizon_secs.to_string();
+        let sentiment = format!(
+            "{}/{}/{}",
+            self.sentiment.positive, self.sentiment.negative, self.sentiment.neutral
+        );
+        let confidence = self.confidence.to_string();
+        let sources = self.source_articles.join(",");
+        let article_count = self.article_count.to_string();
+        let spillover = format!("spillover:{}", self.spillover_from.join(","));
+        let mut fields = vec![
+            self.symbol.as_str(),
+            self.action.as_str(),
+            &sentiment,
+            &confidence,
+            &generated_at,
+            &horizon,
+            &sources,
+            &article_count,
+        ];
+        if let Some(regime) = self.regime {
+            fields.push(regime.as_str());
+        }
+        if !self.spillover_from.is_empty() {
+            fields.push(&spillover);
+        }
+        fnv1a_hex(&fields)
+    }
+
+    /// Sets the holding horizon
+    pub fn with_horizon_secs(mut self, horizon_secs: i64) -> Self {
+        self.horizon_secs = horizon_secs;
+        self.id = self.compute_id();
+        self
+    }
+
+    /// Sets the number of articles behind the sentiment
+    pub fn with_article_count(mut self, article_count: usize) -> Self {
+        self.article_count = article_count;
+        self.id = self.compute_id();
+        self
+    }
+
+    /// Records the regime the signal was generated in
+    pub fn with_regime(mut self, regime: Regime) -> Self {
+        self.regime = Some(regime);
+        self.id = self.compute_id();
+        self
+    }
+
+    /// Marks the signal as spilled over from the sentiment of `symbols`
+    pub fn with_spillover_from<S: AsRef<str>>(mut self, symbols: &[S]) -> Self {
+        for symbol in symbols {
+            let symbol = symbol.as_ref().to_uppercase();
+            if !self.spillover_from.contains(&symbol) {
+                self.spillover_from.push(symbol);
+            }
+        }
+        self.id = self.compute_id();
+        self
+    }
+
+    /// Sets the protective levels
+    pub fn with_levels(mut self, levels: ProtectiveLevels) -> Self {
+        self.levels = Some(levels);
+        self
+    }
+
+    /// Adds source article ids, ignoring duplicates
+    pub fn with_source_articles<S: AsRef<str>>(mut self, ids: &[S]) -> Self {
+        for id in ids {
+            let id = id.as_ref();
+            if !self.source_articles.iter().any(|existing| existing == id) {
+                self.source_articles.push(id.to_string());
+            }
+        }
+        self.id = self.compute_id();
+        self
+    }
 }
 
 #[cfg(test)]
@@ -30,12 +403,7 @@ mod tests {
 
     #[test]
     fn test_article_creation() {
-        let article = Article {
-            title: "Test Article".to_string(),
-            content: "Test content".to_string(),
-            source: "Test Source".to_string(),
-            timestamp: 1234567890,
-        };
+        let article = Article::new("Test Article", "Test content", "Test Source", 1234567890);
         
         assert_eq!(article.title, "Test Article");
         assert_eq!(article.content, "Test content");
@@ -64,25 +432,38 @@ mod tests {
             neutral: dec!(0.1),
         };
         
-        let signal = Signal {
-            symbol: "BTC".to_string(),
-            sentiment,
-            confidence: dec!(0.85),
-        };
+        let signal = Signal::new("BTC", SignalType::Buy, sentiment, dec!(0.85), 1696435200);
         
         assert_eq!(signal.symbol, "BTC");
         assert_eq!(signal.confidence, dec!(0.85));
         assert_eq!(signal.sentiment.positive, dec!(0.7));
+        assert_eq!(signal.horizon_secs, DEFAULT_HORIZON_SECS);
+        assert_eq!(signal.id.len(), 16);
     }
 
     #[test]
-    fn test_article_serialization() {
-        let article = Article {
-            title: "Test".to_string(),
-            content: "Content".to_string(),
-            source: "Source".to_string(),
-            timestamp: 123,
+    fn test_signal_id_and_serialization() {
+        let sentiment = SentimentScore {
+            positive: dec!(0.7),
+            negative: dec!(0.2),
+            neutral: dec!(0.1),
         };
+        let signal = Signal::new("ETH", SignalType::Sell, sentiment, dec!(0.7), 1696435200)
+            .with_horizon_secs(3600)
+            .with_source_articles(&["a1", "a2", "a1"]);
+        assert_eq!(signal.source_articles, ["a1", "a2"]);
+        assert_eq!(signal.id, signal.compute_id());
+        assert_ne!(signal.id, signal.clone().with_horizon_secs(7200).id);
+
+        let json = serde_json::to_string(&signal).unwrap();
+        assert!(json.contains("\"action\":\"SELL\""));
+        let back: Signal = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, signal);
+    }
+
+    #[test]
+    fn test_article_serialization() {
+        let article = Article::new("Test", "Content", "Source", 123);
         
         let json = serde_json::to_string(&article).unwrap();
         assert!(json.contains("Test"));
@@ -102,5 +483,56 @@ mod tests {
         assert!(json.contains("negative"));
         assert!(json.contains("neutral"));
     }
-}
 
+    #[test]
+    fn test_article_id_is_stable_content_hash() {
+        let a = Article::new("Title", "Body", "Source", 1);
+        let b = Article::new("Title", "Body", "Source", 2);
+        let c = Article::new("Title", "Body!", "Source", 1);
+
+        assert_eq!(a.id, b.id);
+        assert_ne!(a.id, c.id);
+        assert_eq!(a.id.len(), 16);
+        assert_eq!(a.id, Article::content_id("Source", "Title", "Body"));
+        assert_ne!(Article::content_id("ab", "c", ""), Article::content_id("a", "bc", ""));
+    }
+
+    #[test]
+    fn test_article_legacy_json_still_loads() {
+        let json = r#"{"title":"Old","content":"Archived","source":"FinanceTimes","timestamp":1696435200}"#;
+        let article: Article = serde_json::from_str(json).unwrap();
+
+        assert_eq!(article.title, "Old");
+        assert_eq!(article.id, Article::content_id("FinanceTimes", "Old", "Archived"));
+        assert!(article.url.is_none());
+        assert!(article.tags.is_empty());
+        assert!(article.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_article_metadata_round_trip() {
+        let article = Article::new("BTC rallies", "Bitcoin and ETH gain", "reddit", 10)
+            .with_url("https://example.com/post")
+            .with_author("satoshi")
+            .with_language("en")
+            .with_tag("social")
+            .with_tag("social")
+            .with_detected_symbols()
+            .with_metadata("score", 42);
+
+        assert_eq!(article.tags, ["social"]);
+        assert_eq!(article.symbols, ["BTC", "ETH"]);
+
+        let json = serde_json::to_string(&article).unwrap();
+        let loaded: Article = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.id, article.id);
+        assert_eq!(loaded.url.as_deref(), Some("https://example.com/post"));
+        assert_eq!(loaded.author.as_deref(), Some("satoshi"));
+        assert_eq!(loaded.metadata["score"], 42);
+
+        // Empty optional fields are left out so plain articles keep the compact format
+        let plain = serde_json::to_string(&Article::new("a", "b", "c", 1)).unwrap();
+        assert!(!plain.contains("metadata"));
+        assert!(!plain.contains("url"));
+    }
+}
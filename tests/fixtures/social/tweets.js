@@ -0,0 +1,35 @@
+window.YTD.tweets.part0 = [
+  {
+    "tweet" : {
+      "id_str" : "1709561234567890001",
+      "full_text" : "BTC just hit a new high, bullish momentum everywhere $BTC",
+      "created_at" : "Wed Oct 04 16:00:00 +0000 2023",
+      "favorite_count" : "250",
+      "retweet_count" : "42",
+      "lang" : "en",
+      "entities" : { "symbols" : [ { "text" : "BTC" } ] }
+    }
+  },
+  {
+    "tweet" : {
+      "id_str" : "1709561234567890002",
+      "full_text" : "@whale_alert Agreed, the ETH outflows look worrying",
+      "created_at" : "Wed Oct 04 17:30:00 +0000 2023",
+      "favorite_count" : "3",
+      "retweet_count" : "0",
+      "in_reply_to_status_id_str" : "1709560000000000000",
+      "in_reply_to_screen_name" : "whale_alert",
+      "lang" : "en"
+    }
+  },
+  {
+    "tweet" : {
+      "id_str" : "1709561234567890003",
+      "full_text" : "RT @cryptodesk: SEC delays decision on spot ETF again",
+      "created_at" : "Thu Oct 05 09:15:00 +0000 2023",
+      "favorite_count" : "0",
+      "retweet_count" : "18",
+      "lang" : "en"
+    }
+  }
+]